@@ -0,0 +1,71 @@
+//! Latency of sending a multi-frame command frame by frame versus as one batch
+//!
+//! Run with `cargo bench --bench send_batch`. The backend wraps the mock but
+//! keeps the default frame-by-frame `send_frames`, like the SocketCAN
+//! backend. Any difference is the per-call overhead of `CanInterface`
+//! (hooks, locks, bus-load bookkeeping), not fewer writes to the bus.
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use robomaster_rust::can::{CanBackend, MessageSplitter, MockCanBackend};
+use robomaster_rust::command::CommandBuilder;
+use robomaster_rust::{CanInterface, CommandCounters, MovementParams};
+use socketcan::CanFrame;
+use std::io;
+
+/// Mock backend without a batched `send_frames`
+struct FrameByFrame(MockCanBackend);
+
+impl CanBackend for FrameByFrame {
+    fn send_frame(&self, frame: &CanFrame) -> io::Result<()> {
+        self.0.send_frame(frame)
+    }
+
+    fn recv_frame(&self) -> io::Result<Option<CanFrame>> {
+        self.0.recv_frame()
+    }
+
+    fn name(&self) -> &str {
+        self.0.name()
+    }
+}
+
+fn frame_by_frame_interface() -> CanInterface {
+    CanInterface::with_backend(FrameByFrame(MockCanBackend::new()))
+}
+
+fn twist_frames() -> Vec<Vec<u8>> {
+    let command = CommandBuilder::new()
+        .build_twist_command(MovementParams { vx: 0.5, vy: 0.0, vz: 0.2 }, &CommandCounters::default())
+        .expect("twist command");
+    MessageSplitter::split_command(&command)
+}
+
+fn bench_send(c: &mut Criterion) {
+    let frames = twist_frames();
+    let mut group = c.benchmark_group("send_twist_command");
+
+    group.bench_function("per_frame", |b| {
+        b.iter_batched(
+            frame_by_frame_interface,
+            |can_interface| {
+                for frame in &frames {
+                    can_interface.send_message(frame).unwrap();
+                }
+            },
+            BatchSize::SmallInput,
+        )
+    });
+
+    group.bench_function("batched", |b| {
+        b.iter_batched(
+            frame_by_frame_interface,
+            |can_interface| can_interface.send_messages(&frames).unwrap(),
+            BatchSize::SmallInput,
+        )
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_send);
+criterion_main!(benches);
@@ -1,7 +1,7 @@
 /// Basic RoboMaster control example
 /// This example demonstrates how to use the high-level RoboMaster API
 
-use robomaster_rust::{RoboMaster, MovementCommand, LedCommand};
+use robomaster_rust::{RoboMaster, MovementCommand, LedCommand, LedEffect};
 use tokio::time::{sleep, Duration};
 use anyhow::Result;
 
@@ -31,6 +31,10 @@ async fn main() -> Result<()> {
     robot.control_led(LedCommand::white().color()).await?;
     sleep(Duration::from_millis(500)).await;
 
+    // The firmware runs effects itself; no blinking loop needed
+    robot.set_led_effect(LedCommand::rgb(255, 255, 0).color(), LedEffect::Flash { on_ms: 250, off_ms: 250 }).await?;
+    sleep(Duration::from_millis(2000)).await;
+
     // Test movement commands
     println!("Testing movement...");
     
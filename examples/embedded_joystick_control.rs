@@ -7,7 +7,8 @@
 /// - Performance monitoring
 /// - Graceful shutdown
 
-use robomaster_rust::{RoboMaster, MovementCommand, LedCommand};
+use robomaster_rust::{RoboMaster, RoboMasterConfig, MovementCommand, LedColor};
+use robomaster_rust::control::termination_signal;
 use tokio::time::{Duration, interval, timeout};
 use anyhow::{Result, Context};
 use gilrs::{Gilrs, Button, Axis, Event, EventType};
@@ -224,6 +225,10 @@ impl PerformanceMonitor {
 fn load_config() -> EmbeddedConfig {
     match std::fs::read_to_string("config/embedded_config.toml") {
         Ok(content) => {
+            if let Err(e) = RoboMasterConfig::from_toml_str(&content) {
+                println!("⚠️  Invalid config file: {}, using defaults", e);
+                return EmbeddedConfig::default();
+            }
             match toml::from_str(&content) {
                 Ok(config) => {
                     println!("✅ Loaded configuration from config/embedded_config.toml");
@@ -313,6 +318,9 @@ async fn recover_connection(robot: &mut RoboMaster, config: &EmbeddedConfig) ->
     ).await
     .context("Connection recovery timeout")?
     .context("Failed to reinitialize robot")?;
+
+    // Stop, recenter the gimbal and start over from fresh counters
+    robot.recover_to_idle().await.context("Failed to return to idle")?;
     
     println!("✅ Connection recovered");
     Ok(())
@@ -324,17 +332,9 @@ async fn set_led_by_name(robot: &mut RoboMaster, color_name: &str, config: &Embe
         return Ok(());
     }
     
-    let led_cmd = match color_name {
-        "green" => LedCommand::green().color(),
-        "red" => LedCommand::red().color(),
-        "blue" => LedCommand::blue().color(),
-        "yellow" => LedCommand::rgb(255, 255, 0).color(), // Yellow as RGB
-        "white" => LedCommand::white().color(),
-        "off" => LedCommand::off().color(),
-        _ => {
-            println!("⚠️  Unknown LED color: {}", color_name);
-            return Ok(());
-        }
+    let Some(led_cmd) = LedColor::from_name(color_name) else {
+        println!("⚠️  Unknown LED color: {}", color_name);
+        return Ok(());
     };
     
     robot.control_led(led_cmd).await
@@ -659,7 +659,7 @@ async fn run_embedded_control() -> Result<()> {
             },
             
             // Graceful shutdown
-            _ = tokio::signal::ctrl_c() => {
+            _ = termination_signal() => {
                 println!("\n🔄 Shutting down gracefully...");
                 break;
             }
@@ -714,7 +714,7 @@ async fn main() -> Result<()> {
                 
                 let restart = tokio::select! {
                     _ = tokio::time::sleep(Duration::from_secs(config.system.restart_delay_sec)) => true,
-                    _ = tokio::signal::ctrl_c() => false,
+                    _ = termination_signal() => false,
                 };
                 
                 if !restart {
@@ -1,5 +1,9 @@
 /// Sensor monitoring example for RoboMaster
 /// This example demonstrates how to read and monitor robot sensor data
+///
+/// Each update drains the telemetry received since the last one with
+/// `poll_sensors` and prints the decoded battery, temperature and IMU
+/// readings. Readings the robot has not reported yet are shown as `n/a`.
 
 use robomaster_rust::RoboMaster;
 use tokio::time::{Duration, interval};
@@ -16,51 +20,45 @@ async fn main() -> Result<()> {
 
     // Initialize the robot
     robot.initialize().await?;
-    
+
     let mut monitor_interval = interval(Duration::from_millis(500)); // 2 Hz monitoring
     let mut counter = 0;
-    
+
     println!("Starting sensor monitoring loop...");
     println!("(Press Ctrl+C to exit)");
     println!();
-    
+
     loop {
         monitor_interval.tick().await;
-        
-        // Receive and process messages
-        robot.receive_messages().await?;
-        
-        // Get current counters (example of internal state monitoring)
-        let counters = robot.get_counters();
-        
+
+        // Decode all telemetry received since the last update
+        let sensors = robot.poll_sensors().await?;
+
         // Display monitoring information
         counter += 1;
         println!("=== Monitor Update #{} ===", counter);
-        println!("Interface: {}", robot.interface_name());
-        println!("Command Counters:");
-        println!("  Joy: {}", counters.joy);
-        println!("  LED: {}", counters.led);
-        println!("  Gimbal: {}", counters.gimbal);
+        println!("{}", sensors);
+
+        if let Err(e) = sensors.battery_voltage() {
+            println!("(waiting for battery telemetry: {})", e);
+        }
         println!();
-        
-        // Note: Actual sensor data reading would be implemented here
-        // For now, we're monitoring the internal command state
-        
+
         // Send a periodic touch command to keep the connection alive
         if counter % 10 == 0 {
             robot.send_touch().await?;
             println!("Sent keep-alive touch command");
         }
-        
+
         // Exit after 60 updates (30 seconds)
         if counter >= 60 {
             break;
         }
     }
-    
+
     // Cleanup
     robot.shutdown().await?;
     println!("Sensor monitoring example completed!");
-    
+
     Ok(())
 }
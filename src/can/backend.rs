@@ -0,0 +1,318 @@
+//! Transports that carry CAN frames for a [`CanInterface`](super::CanInterface)
+//!
+//! [`SocketCanBackend`] talks to a real SocketCAN interface. [`MockCanBackend`]
+//! records sent frames and replays queued ones so the command pipeline can
+//! be tested without hardware.
+
+use super::TimestampedFrame;
+use socketcan::{CanFrame, CanSocket, EmbeddedFrame, Socket, SocketOptions};
+use std::collections::VecDeque;
+use std::io;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+/// A transport for raw CAN frames
+pub trait CanBackend: Send + Sync {
+    /// Write one frame to the bus
+    fn send_frame(&self, frame: &CanFrame) -> io::Result<()>;
+
+    /// Write several frames back-to-back
+    ///
+    /// The default writes them one at a time. [`SocketCanBackend`] keeps it,
+    /// since the socketcan crate has no batched write, so a batch still
+    /// costs one syscall per frame there. Backends that can hand a batch to
+    /// the transport in one call should override this.
+    fn send_frames(&self, frames: &[CanFrame]) -> io::Result<()> {
+        frames.iter().try_for_each(|frame| self.send_frame(frame))
+    }
+
+    /// Read one frame from the bus, or `None` if no frame is available
+    fn recv_frame(&self) -> io::Result<Option<CanFrame>>;
+
+    /// Read one frame along with the time it was received
+    ///
+    /// The default stamps the frame when the read returns. Backends with
+    /// access to kernel receive timestamps (`SO_TIMESTAMP`) should override
+    /// this to report when the frame actually arrived.
+    fn recv_frame_timestamped(&self) -> io::Result<Option<TimestampedFrame>> {
+        Ok(self.recv_frame()?.map(|frame| TimestampedFrame {
+            frame,
+            received_at: Instant::now(),
+        }))
+    }
+
+    /// Read one frame only if it is already waiting
+    ///
+    /// The default falls back to [`recv_frame`](Self::recv_frame), which
+    /// does not block for backends that return `None` when idle.
+    fn try_recv_frame(&self) -> io::Result<Option<CanFrame>> {
+        self.recv_frame()
+    }
+
+    /// Name of the interface the backend is attached to
+    fn name(&self) -> &str;
+
+    /// Receive a copy of each sent frame once it has been transmitted
+    ///
+    /// Backends that cannot report transmission return
+    /// [`io::ErrorKind::Unsupported`].
+    fn enable_tx_confirmation(&self) -> io::Result<()> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+
+    /// Reopen the transport after a send or receive failed
+    ///
+    /// Backends that cannot be reopened return [`io::ErrorKind::Unsupported`].
+    fn reconnect(&mut self) -> io::Result<()> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+}
+
+/// Backend using a SocketCAN socket
+pub struct SocketCanBackend {
+    socket: CanSocket,
+    interface_name: String,
+}
+
+impl SocketCanBackend {
+    /// Open the named SocketCAN interface
+    ///
+    /// Error frames are enabled so the bus state can be tracked.
+    pub fn open(interface_name: &str) -> io::Result<Self> {
+        Ok(Self {
+            socket: open_socket(interface_name)?,
+            interface_name: interface_name.to_string(),
+        })
+    }
+}
+
+impl CanBackend for SocketCanBackend {
+    fn send_frame(&self, frame: &CanFrame) -> io::Result<()> {
+        self.socket.write_frame(frame)
+    }
+
+    fn recv_frame(&self) -> io::Result<Option<CanFrame>> {
+        idle_as_none(self.socket.read_frame())
+    }
+
+    /// Poll with a zero timeout so an idle socket returns at once
+    fn try_recv_frame(&self) -> io::Result<Option<CanFrame>> {
+        idle_as_none(self.socket.read_frame_timeout(Duration::ZERO))
+    }
+
+    fn name(&self) -> &str {
+        &self.interface_name
+    }
+
+    /// Turn on own-message reception, and bound reads so that waiting for a
+    /// frame that never left the bus does not block forever
+    fn enable_tx_confirmation(&self) -> io::Result<()> {
+        self.socket.set_recv_own_msgs(true)?;
+        self.socket.set_read_timeout(super::DEFAULT_CAN_TIMEOUT)
+    }
+
+    fn reconnect(&mut self) -> io::Result<()> {
+        self.socket = open_socket(&self.interface_name)?;
+        Ok(())
+    }
+}
+
+/// Open a socket that also receives error frames
+fn open_socket(interface_name: &str) -> io::Result<CanSocket> {
+    let socket = CanSocket::open(interface_name)?;
+    socket.set_error_filter_accept_all()?;
+    Ok(socket)
+}
+
+/// Map the errors of a read that found no frame to `None`
+pub(super) fn idle_as_none<F>(result: io::Result<F>) -> io::Result<Option<F>> {
+    match result {
+        Ok(frame) => Ok(Some(frame)),
+        Err(e) if matches!(e.kind(), io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+#[derive(Debug, Default)]
+struct MockState {
+    sent: Vec<CanFrame>,
+    queued: VecDeque<CanFrame>,
+    loopback: bool,
+    disconnected: bool,
+    failing_reconnects: u32,
+    reconnects: u32,
+}
+
+/// In-memory backend for tests
+///
+/// Clones share the same state, so a test can keep one clone to inspect the
+/// frames sent through an interface that owns another.
+#[derive(Debug, Clone, Default)]
+pub struct MockCanBackend {
+    state: Arc<Mutex<MockState>>,
+}
+
+impl MockCanBackend {
+    /// Create a mock with no sent or queued frames
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue a frame to be returned by a later read
+    pub fn queue_frame(&self, frame: CanFrame) {
+        self.state().queued.push_back(frame);
+    }
+
+    /// Data of every frame sent so far, in order
+    pub fn sent_frames(&self) -> Vec<Vec<u8>> {
+        self.state().sent.iter().map(|frame| frame.data().to_vec()).collect()
+    }
+
+    /// Standard identifiers of every frame sent so far, in order
+    pub fn sent_ids(&self) -> Vec<u16> {
+        self.state().sent.iter().filter_map(super::standard_id).collect()
+    }
+
+    /// Take the data of every frame sent so far, clearing the record
+    pub fn take_sent_frames(&self) -> Vec<Vec<u8>> {
+        let sent = std::mem::take(&mut self.state().sent);
+        sent.iter().map(|frame| frame.data().to_vec()).collect()
+    }
+
+    /// Number of queued frames not read yet
+    pub fn queued(&self) -> usize {
+        self.state().queued.len()
+    }
+
+    /// Queue a copy of every frame sent from now on, like a SocketCAN
+    /// socket receiving its own messages
+    ///
+    /// Turning loopback off again simulates frames that never left the bus.
+    pub fn set_loopback(&self, enabled: bool) {
+        self.state().loopback = enabled;
+    }
+
+    /// Make every send and receive fail until the backend is reconnected,
+    /// like an unplugged adapter
+    pub fn disconnect(&self) {
+        self.state().disconnected = true;
+    }
+
+    /// Make the next `count` reconnection attempts fail
+    pub fn fail_reconnects(&self, count: u32) {
+        self.state().failing_reconnects = count;
+    }
+
+    /// Number of successful reconnections so far
+    pub fn reconnects(&self) -> u32 {
+        self.state().reconnects
+    }
+
+    fn state(&self) -> MutexGuard<'_, MockState> {
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl MockState {
+    fn check_connected(&self) -> io::Result<()> {
+        if self.disconnected {
+            return Err(io::ErrorKind::NotConnected.into());
+        }
+        Ok(())
+    }
+}
+
+impl CanBackend for MockCanBackend {
+    fn send_frame(&self, frame: &CanFrame) -> io::Result<()> {
+        let mut state = self.state();
+        state.check_connected()?;
+        state.sent.push(*frame);
+        if state.loopback {
+            state.queued.push_back(*frame);
+        }
+        Ok(())
+    }
+
+    /// Record the whole batch under one lock, as a single transport call
+    fn send_frames(&self, frames: &[CanFrame]) -> io::Result<()> {
+        let mut state = self.state();
+        state.check_connected()?;
+        state.sent.extend_from_slice(frames);
+        if state.loopback {
+            state.queued.extend(frames.iter().copied());
+        }
+        Ok(())
+    }
+
+    fn recv_frame(&self) -> io::Result<Option<CanFrame>> {
+        let mut state = self.state();
+        state.check_connected()?;
+        Ok(state.queued.pop_front())
+    }
+
+    fn name(&self) -> &str {
+        "mock"
+    }
+
+    fn enable_tx_confirmation(&self) -> io::Result<()> {
+        self.set_loopback(true);
+        Ok(())
+    }
+
+    fn reconnect(&mut self) -> io::Result<()> {
+        let mut state = self.state();
+        if state.failing_reconnects > 0 {
+            state.failing_reconnects -= 1;
+            return Err(io::ErrorKind::NotFound.into());
+        }
+        state.disconnected = false;
+        state.reconnects += 1;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use socketcan::StandardId;
+
+    fn frame(data: &[u8]) -> CanFrame {
+        CanFrame::new(StandardId::new(0x201).unwrap(), data).unwrap()
+    }
+
+    #[test]
+    fn test_mock_records_sent_frames() {
+        let mock = MockCanBackend::new();
+        let observer = mock.clone();
+
+        mock.send_frame(&frame(&[1, 2, 3])).unwrap();
+        mock.send_frame(&frame(&[4])).unwrap();
+
+        assert_eq!(observer.sent_frames(), vec![vec![1, 2, 3], vec![4]]);
+        assert_eq!(observer.sent_ids(), vec![0x201, 0x201]);
+        assert_eq!(observer.take_sent_frames().len(), 2);
+        assert!(observer.sent_frames().is_empty());
+    }
+
+    #[test]
+    fn test_mock_replays_queued_frames_in_order() {
+        let mock = MockCanBackend::new();
+        mock.queue_frame(frame(&[1]));
+        mock.queue_frame(frame(&[2]));
+
+        assert_eq!(mock.recv_frame().unwrap().unwrap().data(), &[1]);
+        assert_eq!(mock.recv_frame().unwrap().unwrap().data(), &[2]);
+        assert!(mock.recv_frame().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_mock_loopback_queues_sent_frames() {
+        let mock = MockCanBackend::new();
+        mock.send_frame(&frame(&[1])).unwrap();
+        assert_eq!(mock.queued(), 0);
+
+        mock.enable_tx_confirmation().unwrap();
+        mock.send_frame(&frame(&[2])).unwrap();
+        assert_eq!(mock.recv_frame().unwrap().unwrap().data(), &[2]);
+    }
+}
@@ -0,0 +1,138 @@
+//! Bus load estimation from transmitted frames
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Default CAN bitrate of the S1 bus in bits per second
+pub const DEFAULT_BITRATE: u32 = 1_000_000;
+
+/// Default window over which bus load is averaged
+pub const DEFAULT_LOAD_WINDOW: Duration = Duration::from_millis(100);
+
+/// Worst-case number of bits on the wire for a standard frame
+///
+/// Includes the fixed frame fields and worst-case bit stuffing.
+pub fn frame_bits(data_len: usize) -> u32 {
+    let data_bits = 8 * data_len as u32;
+    44 + data_bits + (34 + data_bits - 1) / 4
+}
+
+/// Share of the bus budgeted for periodic control commands
+///
+/// The rest is left for telemetry pushed by the robot and one-off commands.
+pub const COMMAND_BUS_SHARE: f32 = 0.5;
+
+/// Highest rate in Hz at which a cycle of frames fits in `share` of the bus
+///
+/// `frame_lens` are the data lengths of the frames sent once per cycle.
+pub fn max_cycle_rate(bitrate: u32, frame_lens: &[usize], share: f32) -> u32 {
+    let cycle_bits: u32 = frame_lens.iter().map(|&len| frame_bits(len)).sum();
+    if cycle_bits == 0 {
+        return u32::MAX;
+    }
+    (bitrate as f64 * share.clamp(0.0, 1.0) as f64 / cycle_bits as f64) as u32
+}
+
+/// Estimates bus utilisation from the frames sent in a sliding window
+#[derive(Debug, Clone)]
+pub struct BusLoadEstimator {
+    bitrate: u32,
+    window: Duration,
+    sent: VecDeque<(Instant, u32)>,
+    window_bits: u64,
+}
+
+impl BusLoadEstimator {
+    /// Create an estimator for the given bitrate and averaging window
+    pub fn new(bitrate: u32, window: Duration) -> Self {
+        Self {
+            bitrate: bitrate.max(1),
+            window,
+            sent: VecDeque::new(),
+            window_bits: 0,
+        }
+    }
+
+    /// Get the bitrate used to compute the load
+    pub fn bitrate(&self) -> u32 {
+        self.bitrate
+    }
+
+    /// Record a frame with `data_len` data bytes sent at `now`
+    pub fn record_frame(&mut self, now: Instant, data_len: usize) {
+        let bits = frame_bits(data_len);
+        self.sent.push_back((now, bits));
+        self.window_bits += bits as u64;
+        self.expire(now);
+    }
+
+    /// Fraction of the bus capacity used over the window ending at `now`
+    pub fn load(&mut self, now: Instant) -> f32 {
+        self.expire(now);
+        let capacity = self.bitrate as f64 * self.window.as_secs_f64();
+        if capacity <= 0.0 {
+            return 0.0;
+        }
+        (self.window_bits as f64 / capacity) as f32
+    }
+
+    /// Fraction of the bus capacity still available at `now`
+    pub fn headroom(&mut self, now: Instant) -> f32 {
+        (1.0 - self.load(now)).max(0.0)
+    }
+
+    fn expire(&mut self, now: Instant) {
+        while let Some(&(sent_at, bits)) = self.sent.front() {
+            if now.saturating_duration_since(sent_at) < self.window {
+                break;
+            }
+            self.sent.pop_front();
+            self.window_bits -= bits as u64;
+        }
+    }
+}
+
+impl Default for BusLoadEstimator {
+    fn default() -> Self {
+        Self::new(DEFAULT_BITRATE, DEFAULT_LOAD_WINDOW)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_bits() {
+        assert_eq!(frame_bits(8), 44 + 64 + 24);
+        assert_eq!(frame_bits(0), 44 + 8);
+    }
+
+    #[test]
+    fn test_max_cycle_rate_scales_with_bitrate() {
+        let frames = [8, 8, 8, 3];
+        let bits = 3 * frame_bits(8) + frame_bits(3);
+
+        assert_eq!(max_cycle_rate(1_000_000, &frames, 1.0), 1_000_000 / bits);
+        assert_eq!(max_cycle_rate(1_000_000, &frames, 0.5), 500_000 / bits);
+        assert!(max_cycle_rate(125_000, &frames, 0.5) < max_cycle_rate(1_000_000, &frames, 0.5));
+    }
+
+    #[test]
+    fn test_load_tracks_window() {
+        let start = Instant::now();
+        let mut estimator = BusLoadEstimator::new(1_000_000, Duration::from_millis(100));
+        assert_eq!(estimator.load(start), 0.0);
+
+        // 500 full frames in 50 ms is roughly two thirds of the bus
+        for i in 0..500 {
+            estimator.record_frame(start + Duration::from_micros(i * 100), 8);
+        }
+        let load = estimator.load(start + Duration::from_millis(50));
+        assert!((load - 0.66).abs() < 0.01, "load was {load}");
+
+        // Everything has left the window 150 ms later
+        assert_eq!(estimator.load(start + Duration::from_millis(200)), 0.0);
+        assert_eq!(estimator.headroom(start + Duration::from_millis(200)), 1.0);
+    }
+}
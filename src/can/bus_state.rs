@@ -0,0 +1,102 @@
+//! CAN controller error state, read from SocketCAN error frames
+//!
+//! A controller that sees too many errors, usually from bad cabling or
+//! termination, first goes error-passive and then bus-off, after which it
+//! stops transmitting altogether. SocketCAN reports these changes as error
+//! frames.
+
+use socketcan::{CanFrame, EmbeddedFrame};
+
+/// Error class bit for controller problems, detailed in data byte 1
+const CAN_ERR_CRTL: u32 = 0x0004;
+/// Error class bit for bus-off
+const CAN_ERR_BUSOFF: u32 = 0x0040;
+/// Error class bit for a controller restarted after bus-off
+const CAN_ERR_RESTARTED: u32 = 0x0100;
+/// Error class bit for error counters carried in data bytes 6 and 7
+const CAN_ERR_CNT: u32 = 0x0200;
+
+/// Controller status bits in data byte 1 for error-passive receive or transmit
+const CAN_ERR_CRTL_PASSIVE: u8 = 0x10 | 0x20;
+/// Controller status bit in data byte 1 for a return to error-active
+const CAN_ERR_CRTL_ACTIVE: u8 = 0x40;
+
+/// Error counter value at which a controller goes error-passive
+const ERROR_PASSIVE_COUNT: u8 = 128;
+
+/// Error state of the CAN controller
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BusState {
+    /// Normal operation
+    #[default]
+    Active,
+    /// Too many errors; the controller still sends but may not flag errors
+    ErrorPassive,
+    /// The controller has stopped sending until it is restarted
+    BusOff,
+}
+
+/// Get the bus state reported by an error frame
+///
+/// Returns `None` for data frames and error frames that do not report a
+/// state change, such as a single missing acknowledgement.
+pub fn error_frame_state(frame: &CanFrame) -> Option<BusState> {
+    let CanFrame::Error(error) = frame else {
+        return None;
+    };
+    let bits = error.error_bits();
+    let byte = |index: usize| frame.data().get(index).copied().unwrap_or(0);
+
+    if bits & CAN_ERR_BUSOFF != 0 {
+        return Some(BusState::BusOff);
+    }
+    if bits & CAN_ERR_RESTARTED != 0 {
+        return Some(BusState::Active);
+    }
+    if bits & CAN_ERR_CRTL != 0 {
+        if byte(1) & CAN_ERR_CRTL_PASSIVE != 0 {
+            return Some(BusState::ErrorPassive);
+        }
+        if byte(1) & CAN_ERR_CRTL_ACTIVE != 0 {
+            return Some(BusState::Active);
+        }
+    }
+    if bits & CAN_ERR_CNT != 0 {
+        let passive = byte(6) >= ERROR_PASSIVE_COUNT || byte(7) >= ERROR_PASSIVE_COUNT;
+        return Some(if passive { BusState::ErrorPassive } else { BusState::Active });
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use socketcan::{CanErrorFrame, StandardId};
+
+    fn error_frame(class: u32, data: [u8; 8]) -> CanFrame {
+        CanErrorFrame::new_error(class, &data).unwrap().into()
+    }
+
+    #[test]
+    fn test_error_frame_states() {
+        assert_eq!(error_frame_state(&error_frame(CAN_ERR_BUSOFF, [0; 8])), Some(BusState::BusOff));
+        assert_eq!(error_frame_state(&error_frame(CAN_ERR_RESTARTED, [0; 8])), Some(BusState::Active));
+        assert_eq!(
+            error_frame_state(&error_frame(CAN_ERR_CRTL, [0, 0x20, 0, 0, 0, 0, 0, 0])),
+            Some(BusState::ErrorPassive)
+        );
+        assert_eq!(
+            error_frame_state(&error_frame(CAN_ERR_CRTL, [0, 0x40, 0, 0, 0, 0, 0, 0])),
+            Some(BusState::Active)
+        );
+        assert_eq!(
+            error_frame_state(&error_frame(CAN_ERR_CNT, [0, 0, 0, 0, 0, 0, 130, 4])),
+            Some(BusState::ErrorPassive)
+        );
+        // A missing acknowledgement alone does not change the state
+        assert_eq!(error_frame_state(&error_frame(0x0020, [0; 8])), None);
+
+        let data = CanFrame::new(StandardId::new(0x201).unwrap(), &[0x40]).unwrap();
+        assert_eq!(error_frame_state(&data), None);
+    }
+}
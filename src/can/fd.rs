@@ -0,0 +1,316 @@
+//! CAN-FD transport for adapters that bridge the robot onto an FD bus
+//!
+//! The S1 itself speaks classic CAN, so this only helps when an adapter
+//! between the host and the robot accepts CAN-FD frames. Each command is
+//! then sent as one frame of up to 64 bytes instead of being split into
+//! 8-byte frames; longer commands, such as the boot sequence, are split
+//! into 64-byte frames with [`MessageSplitter::fd_chunks`].
+//!
+//! CAN-FD only allows data lengths of 0 to 8, 12, 16, 20, 24, 32, 48 and 64
+//! bytes, so the last frame of a command is padded with zeros up to the next
+//! allowed length; a 27-byte twist goes out as 32 bytes. The robot, and
+//! [`FdMessageReassembler`] on the receive side, cut messages using the
+//! length in their header, so the padding is never read as data.
+//!
+//! Received telemetry is reassembled the same way by
+//! [`CanFdInterface::receive_message`], which accepts both classic and FD
+//! frames so an adapter may forward the robot's pushes either way.
+//!
+//! Not yet tested against a physical adapter. Any SocketCAN interface with
+//! FD enabled should work, for example one set up with
+//! `ip link set can0 type can bitrate 1000000 dbitrate 5000000 fd on`, or a
+//! virtual interface with `ip link set vcan0 mtu 72`.
+
+use super::backend::idle_as_none;
+use super::{validate_can_id, CANFD_MAX_DATA_LEN, validate_message_length, MessageSplitter, MESSAGE_SOF, MIN_MESSAGE_LEN, ROBOMASTER_CAN_ID};
+use crate::error::{CanError, ProtocolError, RoboMasterError};
+use socketcan::{CanAnyFrame, CanFdFrame, CanFdSocket, EmbeddedFrame, Socket, StandardId};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+/// Data lengths a CAN-FD frame can carry, in increasing order
+pub const CANFD_FRAME_LENGTHS: [usize; 16] = [0, 1, 2, 3, 4, 5, 6, 7, 8, 12, 16, 20, 24, 32, 48, 64];
+
+/// CAN-FD interface that sends whole commands in single frames
+pub struct CanFdInterface {
+    socket: CanFdSocket,
+    can_id: u16,
+    interface_name: String,
+    send_lock: Mutex<()>,
+    reassembler: Mutex<FdMessageReassembler>,
+}
+
+impl CanFdInterface {
+    /// Open the named SocketCAN interface in FD mode
+    pub fn new(interface_name: &str) -> Result<Self, RoboMasterError> {
+        let socket = CanFdSocket::open(interface_name)
+            .map_err(|e| RoboMasterError::CanInterface(CanError::OpenFailed {
+                interface: interface_name.to_string(),
+                source: e,
+            }))?;
+
+        Ok(Self {
+            socket,
+            can_id: ROBOMASTER_CAN_ID,
+            interface_name: interface_name.to_string(),
+            send_lock: Mutex::new(()),
+            reassembler: Mutex::new(FdMessageReassembler::new()),
+        })
+    }
+
+    /// Change the arbitration ID used to send commands
+    pub fn set_can_id(&mut self, can_id: u16) -> Result<(), RoboMasterError> {
+        validate_can_id(can_id)?;
+        self.can_id = can_id;
+        Ok(())
+    }
+
+    /// Get the arbitration ID used to talk to the robot
+    pub fn can_id(&self) -> u16 {
+        self.can_id
+    }
+
+    /// Send a built command, in one frame if it fits in 64 bytes
+    ///
+    /// The last frame is zero-padded to a valid CAN-FD length. Every frame is built before any is sent, and the send lock keeps
+    /// frames from other threads from interleaving with them.
+    pub fn send_command(&self, command: &[u8]) -> Result<(), RoboMasterError> {
+        let frames = build_fd_frames(self.can_id, command)?;
+        let _send_guard = self.send_lock.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        for frame in &frames {
+            self.socket.write_frame(frame)
+                .map_err(|e| RoboMasterError::CanInterface(CanError::SendFailed(e)))?;
+        }
+        Ok(())
+    }
+
+    /// Receive a classic or FD frame, or `None` if none arrives within `timeout`
+    pub fn receive_frame(&self, timeout: Duration) -> Result<Option<CanAnyFrame>, RoboMasterError> {
+        idle_as_none(self.socket.read_frame_timeout(timeout))
+            .map_err(|e| RoboMasterError::CanInterface(CanError::ReceiveFailed(e)))
+    }
+
+    /// Receive the next complete protocol message, or `None` if none completes within `timeout`
+    ///
+    /// Frames are read until a message is complete. One FD frame may carry
+    /// several messages; the ones after the first are kept and returned by
+    /// later calls without reading the socket. Remote and error frames are
+    /// skipped, and malformed messages are dropped with a warning.
+    pub fn receive_message(&self, timeout: Duration) -> Result<Option<Vec<u8>>, RoboMasterError> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            if let Some(message) = self.next_buffered_message() {
+                return Ok(Some(message));
+            }
+
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let Some(frame) = self.receive_frame(remaining)? else {
+                return Ok(None);
+            };
+            match frame {
+                CanAnyFrame::Fd(frame) => self.lock_reassembler().push(frame.data()),
+                CanAnyFrame::Normal(frame) => self.lock_reassembler().push(frame.data()),
+                CanAnyFrame::Remote(_) | CanAnyFrame::Error(_) => {}
+            }
+        }
+    }
+
+    /// Take the next message already reassembled from received frames
+    fn next_buffered_message(&self) -> Option<Vec<u8>> {
+        let mut reassembler = self.lock_reassembler();
+        loop {
+            match reassembler.next_message() {
+                Ok(message) => return message,
+                Err(error) => tracing::warn!("dropping malformed CAN-FD message: {}", error),
+            }
+        }
+    }
+
+    fn lock_reassembler(&self) -> MutexGuard<'_, FdMessageReassembler> {
+        self.reassembler.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Get the interface name
+    pub fn interface_name(&self) -> &str {
+        &self.interface_name
+    }
+}
+
+/// Reassembles protocol messages from the data of received CAN-FD frames
+///
+/// Unlike [`MessageReassembler`](super::MessageReassembler), a frame may
+/// hold several messages and end in zero padding. Messages are cut at the
+/// length in their header, and bytes between messages that do not start a
+/// message are dropped as padding.
+#[derive(Debug, Default)]
+pub struct FdMessageReassembler {
+    buffer: Vec<u8>,
+}
+
+impl FdMessageReassembler {
+    /// Create an empty reassembler
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append the data of one received frame
+    pub fn push(&mut self, data: &[u8]) {
+        self.buffer.extend_from_slice(data);
+    }
+
+    /// Take the next complete message, if one has been received
+    ///
+    /// A header declaring a length below the minimum message length is
+    /// dropped with `InvalidHeader`; call again to continue with the bytes
+    /// after it.
+    pub fn next_message(&mut self) -> Result<Option<Vec<u8>>, ProtocolError> {
+        let start = self.buffer.iter().position(|&byte| byte == MESSAGE_SOF).unwrap_or(self.buffer.len());
+        self.buffer.drain(..start);
+
+        let Some(&declared) = self.buffer.get(1) else {
+            return Ok(None);
+        };
+        let declared = declared as usize;
+        if declared < MIN_MESSAGE_LEN {
+            self.buffer.drain(..1);
+            return Err(ProtocolError::InvalidHeader {
+                reason: format!("declared length {} is below the minimum of {}", declared, MIN_MESSAGE_LEN),
+            });
+        }
+        if self.buffer.len() < declared {
+            return Ok(None);
+        }
+
+        let message: Vec<u8> = self.buffer.drain(..declared).collect();
+        validate_message_length(&message).map(|_| Some(message))
+    }
+
+    /// Number of bytes buffered for messages not yet returned
+    pub fn pending(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Discard any buffered data
+    pub fn reset(&mut self) {
+        self.buffer.clear();
+    }
+}
+
+/// Smallest CAN-FD data length that holds `len` bytes
+fn padded_fd_len(len: usize) -> usize {
+    CANFD_FRAME_LENGTHS
+        .iter()
+        .copied()
+        .find(|&allowed| allowed >= len)
+        .unwrap_or(CANFD_MAX_DATA_LEN)
+}
+
+/// Build the FD frames carrying `command` on `can_id`, zero-padding the last one
+fn build_fd_frames(can_id: u16, command: &[u8]) -> Result<Vec<CanFdFrame>, RoboMasterError> {
+    let standard_id = StandardId::new(can_id)
+        .ok_or_else(|| RoboMasterError::CanInterface(CanError::InvalidMessage {
+            reason: "Invalid CAN ID".to_string(),
+        }))?;
+
+    MessageSplitter::fd_chunks(command)
+        .into_iter()
+        .map(|mut chunk| {
+            chunk.resize(padded_fd_len(chunk.len()), 0);
+            CanFdFrame::new(standard_id, &chunk)
+                .ok_or_else(|| RoboMasterError::CanInterface(CanError::FrameCreation(
+                    std::io::Error::new(std::io::ErrorKind::InvalidData, "Failed to create CAN-FD frame")
+                )))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::can::CommandCounters;
+    use crate::command::{CommandBuilder, MovementParams};
+    use crate::telemetry::tests::{build_message, chassis_status_payload};
+
+    #[test]
+    fn test_commands_fit_in_one_padded_fd_frame() {
+        let builder = CommandBuilder::new();
+        let twist = builder.build_twist_command(MovementParams::stopped(), &CommandCounters::default()).unwrap();
+        assert_eq!(twist.len(), 27);
+
+        let frames = build_fd_frames(ROBOMASTER_CAN_ID, &twist).unwrap();
+        assert_eq!(frames.len(), 1);
+        assert_eq!(frames[0].data().len(), 32);
+        assert_eq!(&frames[0].data()[..27], twist.as_slice());
+        assert_eq!(&frames[0].data()[27..], &[0; 5]);
+
+        let boot = builder.build_boot_sequence().unwrap();
+        let frames = build_fd_frames(ROBOMASTER_CAN_ID, &boot).unwrap();
+        assert_eq!(frames.len(), boot.len().div_ceil(CANFD_MAX_DATA_LEN));
+        let data: Vec<u8> = frames.iter().flat_map(|frame| frame.data().iter().copied()).collect();
+        assert_eq!(&data[..boot.len()], boot.as_slice());
+        assert!(data[boot.len()..].iter().all(|&byte| byte == 0));
+        assert_eq!(data.len() - boot.len(), padded_fd_len(boot.len() % CANFD_MAX_DATA_LEN) - boot.len() % CANFD_MAX_DATA_LEN);
+    }
+
+    #[test]
+    fn test_padded_fd_len() {
+        assert_eq!(padded_fd_len(8), 8);
+        assert_eq!(padded_fd_len(9), 12);
+        assert_eq!(padded_fd_len(27), 32);
+        assert_eq!(padded_fd_len(49), 64);
+        assert_eq!(padded_fd_len(64), 64);
+    }
+
+    #[test]
+    fn test_reassembler_strips_padding_between_messages() {
+        let builder = CommandBuilder::new();
+        let boot = builder.build_boot_sequence().unwrap();
+        let telemetry = build_message(0x3F, 0xA0, &chassis_status_payload());
+
+        let mut reassembler = FdMessageReassembler::new();
+        for frame in build_fd_frames(ROBOMASTER_CAN_ID, &boot).unwrap() {
+            reassembler.push(frame.data());
+        }
+        for frame in build_fd_frames(ROBOMASTER_CAN_ID, &telemetry).unwrap() {
+            reassembler.push(frame.data());
+        }
+
+        let mut messages = Vec::new();
+        while let Some(message) = reassembler.next_message().unwrap() {
+            messages.push(message);
+        }
+        assert_eq!(messages.concat(), [boot, telemetry.clone()].concat());
+        assert_eq!(messages.last(), Some(&telemetry));
+        assert_eq!(reassembler.pending(), 0);
+    }
+
+    #[test]
+    fn test_reassembler_waits_for_split_message() {
+        let telemetry = build_message(0x3F, 0xA0, &chassis_status_payload());
+        let mut reassembler = FdMessageReassembler::new();
+
+        reassembler.push(&telemetry[..10]);
+        assert_eq!(reassembler.next_message().unwrap(), None);
+        reassembler.push(&telemetry[10..]);
+        assert_eq!(reassembler.next_message().unwrap(), Some(telemetry));
+    }
+
+    #[test]
+    fn test_reassembler_skips_bad_header() {
+        let telemetry = build_message(0x3F, 0xA0, &chassis_status_payload());
+        let mut reassembler = FdMessageReassembler::new();
+
+        reassembler.push(&[MESSAGE_SOF, 0x02]);
+        reassembler.push(&telemetry);
+        assert!(matches!(reassembler.next_message(), Err(ProtocolError::InvalidHeader { .. })));
+        assert_eq!(reassembler.next_message().unwrap(), Some(telemetry));
+    }
+
+    #[test]
+    fn test_rejects_extended_can_id() {
+        assert!(matches!(
+            build_fd_frames(0x800, &[0x55]),
+            Err(RoboMasterError::CanInterface(CanError::InvalidMessage { .. }))
+        ));
+    }
+}
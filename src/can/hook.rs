@@ -0,0 +1,34 @@
+//! Observation hooks around every command send
+
+use crate::command::decoder::{COMMAND_ID_OFFSET, COMMAND_SET_OFFSET};
+use crate::command::CommandKind;
+use crate::error::RoboMasterError;
+use super::MESSAGE_SOF;
+
+/// Callbacks run around every command sent through a [`CanInterface`](super::CanInterface)
+///
+/// Register with [`RoboMaster::add_command_hook`](crate::RoboMaster::add_command_hook)
+/// to collect metrics or trace traffic without changing the control code.
+/// Hooks run on the sending task, so they should return quickly. Only
+/// [`send_messages`](super::CanInterface::send_messages) runs hooks; single
+/// frames and [`send_raw_frame`](super::CanInterface::send_raw_frame) skip them.
+pub trait CommandHook: Send + Sync {
+    /// Called with the frames of a command just before they are sent
+    fn before_send(&self, _kind: &CommandKind, _frames: &[Vec<u8>]) {}
+
+    /// Called with the outcome once the frames have been handed to the bus
+    fn after_send(&self, _kind: &CommandKind, _result: &Result<(), RoboMasterError>) {}
+}
+
+/// Identify the command carried by split frames from its header
+///
+/// Returns [`CommandKind::Other`] if the frames do not start a message.
+pub(crate) fn command_kind(frames: &[Vec<u8>]) -> CommandKind {
+    let header: Vec<u8> = frames.iter().flatten().copied().take(COMMAND_ID_OFFSET + 1).collect();
+    match header.as_slice() {
+        [MESSAGE_SOF, ..] if header.len() > COMMAND_ID_OFFSET => {
+            CommandKind::from_ids(header[COMMAND_SET_OFFSET], header[COMMAND_ID_OFFSET])
+        }
+        _ => CommandKind::Other,
+    }
+}
@@ -1,22 +1,83 @@
+pub mod backend;
+pub mod bus_load;
+pub mod bus_state;
+#[cfg(feature = "can-fd")]
+pub mod fd;
+pub mod hook;
+pub mod reconnect;
+pub mod tx_confirm;
+
 use anyhow::Result;
-use crate::error::{RoboMasterError, CanError};
-use socketcan::{CanSocket, CanFrame, Socket, EmbeddedFrame, StandardId};
-use std::time::Duration;
+use crate::error::{RoboMasterError, CanError, ProtocolError};
+use socketcan::{CanFrame, EmbeddedFrame, StandardId};
+use std::future::Future;
+use std::io;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
 use tokio::time::timeout;
 
-/// CAN arbitration ID used for RoboMaster communication
+pub use backend::{CanBackend, MockCanBackend, SocketCanBackend};
+pub use bus_load::BusLoadEstimator;
+pub use bus_state::BusState;
+#[cfg(feature = "can-fd")]
+pub use fd::{CanFdInterface, FdMessageReassembler};
+pub use hook::CommandHook;
+pub use reconnect::{AutoReconnect, ConnectionState};
+use reconnect::ReconnectBackoff;
+pub use tx_confirm::TxConfirmation;
+
+/// Default CAN arbitration ID used for RoboMaster communication
 pub const ROBOMASTER_CAN_ID: u16 = 0x201;
 
+/// Largest 11-bit standard CAN identifier
+pub const MAX_STANDARD_CAN_ID: u16 = 0x7FF;
+
 /// Default timeout for CAN operations
 pub const DEFAULT_CAN_TIMEOUT: Duration = Duration::from_millis(200);
 
 /// Maximum CAN frame data length
 pub const CAN_MAX_DATA_LEN: usize = 8;
 
+/// Maximum data length of a CAN-FD frame
+pub const CANFD_MAX_DATA_LEN: usize = 64;
+
+/// Start-of-frame byte of every protocol message
+pub const MESSAGE_SOF: u8 = 0x55;
+
+/// Smallest valid message: 11-byte header plus CRC16
+pub const MIN_MESSAGE_LEN: usize = 13;
+
+/// Most frames `receive_and_process` drains from the socket per call
+pub const MAX_DRAIN_FRAMES: usize = 64;
+
+/// A received frame with the time it arrived
+#[derive(Debug, Clone, Copy)]
+pub struct TimestampedFrame {
+    /// The received frame
+    pub frame: CanFrame,
+    /// When the frame was received
+    pub received_at: Instant,
+}
+
+/// Callback for extended frames, which the receive paths otherwise skip
+pub type ExtendedFrameHandler = Box<dyn Fn(&CanFrame) + Send + Sync>;
+
 /// CAN interface abstraction for RoboMaster communication
 pub struct CanInterface {
-    socket: CanSocket,
+    backend: RwLock<Box<dyn CanBackend>>,
     interface_name: String,
+    can_id: u16,
+    bus_load: Mutex<BusLoadEstimator>,
+    tx_confirmation: Mutex<TxConfirmation>,
+    send_lock: Mutex<()>,
+    skipped_extended: AtomicU64,
+    extended_handler: Mutex<Option<ExtendedFrameHandler>>,
+    command_hooks: Mutex<Vec<Arc<dyn CommandHook>>>,
+    auto_reconnect: Mutex<Option<AutoReconnect>>,
+    reconnect_backoff: Mutex<ReconnectBackoff>,
+    connection_state: Mutex<ConnectionState>,
+    bus_state: Mutex<BusState>,
 }
 
 impl CanInterface {
@@ -24,7 +85,7 @@ impl CanInterface {
     pub fn new(interface_name: &str) -> Result<Self, RoboMasterError> {
         println!("----------------------can open----------------------");
         
-        let socket = CanSocket::open(interface_name)
+        let backend = SocketCanBackend::open(interface_name)
             .map_err(|e| RoboMasterError::CanInterface(CanError::OpenFailed {
                 interface: interface_name.to_string(),
                 source: e,
@@ -32,55 +93,287 @@ impl CanInterface {
 
         println!("generated can bus");
         
-        Ok(Self {
-            socket,
-            interface_name: interface_name.to_string(),
-        })
+        Ok(Self::with_backend(backend))
+    }
+
+    /// Create a CAN interface that talks to the robot on `can_id` instead of the default
+    ///
+    /// Fails with [`CanError::InvalidMessage`] if `can_id` is not an 11-bit
+    /// standard identifier.
+    pub fn with_can_id(interface_name: &str, can_id: u16) -> Result<Self, RoboMasterError> {
+        validate_can_id(can_id)?;
+        let mut can_interface = Self::new(interface_name)?;
+        can_interface.can_id = can_id;
+        Ok(can_interface)
+    }
+
+    /// Create a CAN interface on top of any backend, such as [`MockCanBackend`]
+    pub fn with_backend(backend: impl CanBackend + 'static) -> Self {
+        Self {
+            interface_name: backend.name().to_string(),
+            backend: RwLock::new(Box::new(backend)),
+            can_id: ROBOMASTER_CAN_ID,
+            bus_load: Mutex::new(BusLoadEstimator::default()),
+            tx_confirmation: Mutex::new(TxConfirmation::default()),
+            send_lock: Mutex::new(()),
+            skipped_extended: AtomicU64::new(0),
+            extended_handler: Mutex::new(None),
+            command_hooks: Mutex::new(Vec::new()),
+            auto_reconnect: Mutex::new(None),
+            reconnect_backoff: Mutex::new(ReconnectBackoff::default()),
+            connection_state: Mutex::new(ConnectionState::Connected),
+            bus_state: Mutex::new(BusState::Active),
+        }
+    }
+
+    /// Change the arbitration ID used to send commands and filter echoes
+    pub fn set_can_id(&mut self, can_id: u16) -> Result<(), RoboMasterError> {
+        validate_can_id(can_id)?;
+        self.can_id = can_id;
+        Ok(())
+    }
+
+    /// Get the arbitration ID used to talk to the robot
+    pub fn can_id(&self) -> u16 {
+        self.can_id
+    }
+
+    /// Set the bus bitrate in bits per second, as configured on the interface
+    ///
+    /// The bitrate is not read from the interface, so set it when the bus
+    /// does not run at the S1's default of [`bus_load::DEFAULT_BITRATE`].
+    pub fn set_bitrate(&mut self, bitrate: u32) {
+        *self.bus_load.get_mut().unwrap_or_else(|poisoned| poisoned.into_inner()) =
+            BusLoadEstimator::new(bitrate, bus_load::DEFAULT_LOAD_WINDOW);
+    }
+
+    /// Get the bus bitrate in bits per second
+    pub fn bitrate(&self) -> u32 {
+        self.lock_bus_load().bitrate()
     }
 
     /// Send a single CAN message
     pub fn send_message(&self, data: &[u8]) -> Result<(), RoboMasterError> {
-        if data.len() > CAN_MAX_DATA_LEN {
-            return Err(RoboMasterError::CanInterface(CanError::InvalidDataLength {
-                length: data.len(),
-                max_length: CAN_MAX_DATA_LEN,
-            }));
-        }
+        let frame = self.build_frame(data)?;
+        self.send_frames(&[frame])
+    }
 
-        let standard_id = StandardId::new(ROBOMASTER_CAN_ID)
-            .ok_or_else(|| RoboMasterError::CanInterface(CanError::InvalidMessage {
-                reason: "Invalid CAN ID".to_string(),
-            }))?;
-            
-        let frame = CanFrame::new(standard_id, data)
-            .ok_or_else(|| RoboMasterError::CanInterface(CanError::FrameCreation(
-                std::io::Error::new(std::io::ErrorKind::InvalidData, "Failed to create CAN frame")
-            )))?;
+    /// Fraction of the bus capacity still available, estimated from sent frames
+    pub fn bus_headroom(&self) -> f32 {
+        self.lock_bus_load().headroom(Instant::now())
+    }
+
+    /// Fraction of the bus capacity used by recently sent frames
+    pub fn bus_load(&self) -> f32 {
+        self.lock_bus_load().load(Instant::now())
+    }
 
-        self.socket.write_frame(&frame)
-            .map_err(|e| RoboMasterError::CanInterface(CanError::SendFailed(e)))?;
+    fn lock_bus_load(&self) -> std::sync::MutexGuard<'_, BusLoadEstimator> {
+        self.bus_load.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
 
+    /// Ask the backend to loop back transmitted frames so sends can be confirmed
+    ///
+    /// Loopback copies are filtered out of [`receive_message`](Self::receive_message).
+    pub fn enable_tx_confirmation(&self) -> Result<(), RoboMasterError> {
+        self.backend().enable_tx_confirmation()
+            .map_err(|e| RoboMasterError::CanInterface(CanError::ConfigureFailed(e)))?;
+        self.lock_tx_confirmation().enable();
         Ok(())
     }
 
-    /// Send multiple CAN messages
+    /// Check whether transmit confirmation is enabled
+    pub fn tx_confirmation_enabled(&self) -> bool {
+        self.lock_tx_confirmation().is_enabled()
+    }
+
+    /// Check whether every frame sent so far has actually left the bus
+    ///
+    /// `send_message` only reports that a frame was queued. This reads
+    /// loopback copies until all sent frames are accounted for or none are
+    /// left to read; frames from the robot read on the way are kept for
+    /// [`receive_message`](Self::receive_message). Always `false` unless
+    /// [`enable_tx_confirmation`](Self::enable_tx_confirmation) was called.
+    pub fn confirm_tx(&self) -> Result<bool, RoboMasterError> {
+        loop {
+            {
+                let tx = self.lock_tx_confirmation();
+                if !tx.is_enabled() || tx.pending() == 0 {
+                    return Ok(tx.is_confirmed());
+                }
+            }
+
+            let frame = self.on_backend(|backend| backend.recv_frame())
+                .map_err(|e| e.into_error(CanError::ReceiveFailed))?;
+            if let Some(frame) = &frame {
+                if self.observe_error_frame(frame)? {
+                    continue;
+                }
+            }
+            let mut tx = self.lock_tx_confirmation();
+            match frame {
+                Some(frame) if tx.observe(&frame) => {}
+                Some(frame) => tx.defer(frame),
+                None => return Ok(false),
+            }
+        }
+    }
+
+    fn lock_tx_confirmation(&self) -> std::sync::MutexGuard<'_, TxConfirmation> {
+        self.tx_confirmation.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Call `handler` with every extended frame the receive paths skip
+    ///
+    /// The robot only uses standard identifiers, so extended frames are never
+    /// decoded. On a shared bus the handler can pass them on instead of
+    /// losing them. Replaces any previous handler.
+    pub fn set_extended_frame_handler(&self, handler: impl Fn(&CanFrame) + Send + Sync + 'static) {
+        *self.lock_extended_handler() = Some(Box::new(handler));
+    }
+
+    /// Remove the extended frame handler
+    pub fn clear_extended_frame_handler(&self) -> Option<ExtendedFrameHandler> {
+        self.lock_extended_handler().take()
+    }
+
+    /// Get the number of extended frames skipped while receiving
+    pub fn skipped_extended_frames(&self) -> u64 {
+        self.skipped_extended.load(Ordering::Relaxed)
+    }
+
+    /// Count and hand off `frame` if it is extended, returning whether to skip it
+    pub(crate) fn skip_extended(&self, frame: &CanFrame) -> bool {
+        if standard_id(frame).is_some() {
+            return false;
+        }
+        self.skipped_extended.fetch_add(1, Ordering::Relaxed);
+        if let Some(handler) = self.lock_extended_handler().as_ref() {
+            handler(frame);
+        }
+        true
+    }
+
+    fn lock_extended_handler(&self) -> std::sync::MutexGuard<'_, Option<ExtendedFrameHandler>> {
+        self.extended_handler.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Run `hook` around every command sent from now on
+    ///
+    /// Hooks run in the order they were added.
+    pub fn add_command_hook(&self, hook: Arc<dyn CommandHook>) {
+        self.lock_command_hooks().push(hook);
+    }
+
+    /// Remove every command hook, returning them
+    pub fn clear_command_hooks(&self) -> Vec<Arc<dyn CommandHook>> {
+        std::mem::take(&mut *self.lock_command_hooks())
+    }
+
+    fn lock_command_hooks(&self) -> std::sync::MutexGuard<'_, Vec<Arc<dyn CommandHook>>> {
+        self.command_hooks.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Send multiple CAN messages back-to-back
+    ///
+    /// Every frame is built before any is sent, so an invalid message sends
+    /// nothing. The frames are then handed to the backend in one call while
+    /// holding the send lock, without awaiting in between, so frames sent
+    /// from other tasks cannot interleave with them.
+    ///
+    /// Registered [`CommandHook`]s run before and after the send.
     pub fn send_messages(&self, messages: &[Vec<u8>]) -> Result<(), RoboMasterError> {
-        for msg in messages {
-            self.send_message(msg)?;
+        let hooks = self.lock_command_hooks().clone();
+        if hooks.is_empty() {
+            return self.send_built(messages);
+        }
+
+        let kind = hook::command_kind(messages);
+        for hook in &hooks {
+            hook.before_send(&kind, messages);
+        }
+        let result = self.send_built(messages);
+        for hook in &hooks {
+            hook.after_send(&kind, &result);
+        }
+        result
+    }
+
+    fn send_built(&self, messages: &[Vec<u8>]) -> Result<(), RoboMasterError> {
+        let frames = messages
+            .iter()
+            .map(|msg| self.build_frame(msg))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.send_frames(&frames)
+    }
+
+    fn build_frame(&self, data: &[u8]) -> Result<CanFrame, RoboMasterError> {
+        build_frame_on(self.can_id, data)
+    }
+
+    /// Send arbitrary bytes on any standard ID, bypassing the command protocol
+    ///
+    /// Meant for probing the bus during protocol research; the robot may
+    /// react unpredictably to frames it does not expect. Fails with
+    /// `InvalidMessage` if `id` is not an 11-bit standard identifier and
+    /// `InvalidDataLength` if `data` does not fit in one frame.
+    pub fn send_raw_frame(&self, id: u16, data: &[u8]) -> Result<(), RoboMasterError> {
+        validate_can_id(id)?;
+        let frame = build_frame_on(id, data)?;
+        self.send_frames(&[frame])
+    }
+
+    fn send_frames(&self, frames: &[CanFrame]) -> Result<(), RoboMasterError> {
+        let _send_guard = self.send_lock.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        self.on_backend(|backend| backend.send_frames(frames))
+            .map_err(|e| e.into_error(|e| match self.bus_state() {
+                BusState::BusOff => CanError::BusOff,
+                _ => CanError::SendFailed(e),
+            }))?;
+
+        let now = Instant::now();
+        let mut bus_load = self.lock_bus_load();
+        let mut tx_confirmation = self.lock_tx_confirmation();
+        for frame in frames {
+            bus_load.record_frame(now, frame.data().len());
+            tx_confirmation.record_sent(*frame);
         }
         Ok(())
     }
 
     /// Receive a CAN message with timeout
     pub async fn receive_message(&self, timeout_duration: Duration) -> Result<Option<CanFrame>, RoboMasterError> {
+        Ok(self.receive_timestamped(timeout_duration).await?.map(|received| received.frame))
+    }
+
+    /// Receive a CAN message with timeout, along with the time it arrived
+    ///
+    /// The time comes from the backend; see
+    /// [`CanBackend::recv_frame_timestamped`]. Frames read earlier while
+    /// confirming transmission are stamped when they are returned.
+    pub async fn receive_timestamped(&self, timeout_duration: Duration) -> Result<Option<TimestampedFrame>, RoboMasterError> {
+        if let Some(frame) = self.lock_tx_confirmation().take_deferred() {
+            return Ok(Some(TimestampedFrame {
+                frame,
+                received_at: Instant::now(),
+            }));
+        }
+
         let recv_future = async {
-            self.socket.read_frame()
-                .map_err(|e| RoboMasterError::CanInterface(CanError::ReceiveFailed(e)))
+            loop {
+                let received = self.on_backend_async(|backend| backend.recv_frame_timestamped())
+                    .await
+                    .map_err(|e| e.into_error(CanError::ReceiveFailed))?;
+                match received {
+                    Some(received) if self.observe_error_frame(&received.frame)? => continue,
+                    Some(received) if self.lock_tx_confirmation().observe(&received.frame) => continue,
+                    received => return Ok(received),
+                }
+            }
         };
 
         match timeout(timeout_duration, recv_future).await {
-            Ok(Ok(frame)) => Ok(Some(frame)),
-            Ok(Err(e)) => Err(e),
+            Ok(result) => result,
             Err(_) => {
                 println!("Time out");
                 Ok(None)
@@ -88,35 +381,283 @@ impl CanInterface {
         }
     }
 
+    /// Send a command and wait for the first received frame matching a predicate
+    ///
+    /// Frames that do not match are discarded. Returns
+    /// [`RoboMasterError::Timeout`] if no matching frame arrives in time.
+    pub async fn request_response<F>(
+        &self,
+        command: &[u8],
+        matches: F,
+        timeout_duration: Duration,
+    ) -> Result<CanFrame, RoboMasterError>
+    where
+        F: Fn(&CanFrame) -> bool,
+    {
+        self.send_messages(&MessageSplitter::split_command(command))?;
+        wait_for_matching_frame(|remaining| self.receive_message(remaining), matches, timeout_duration).await
+    }
+
+    /// Read the frames already waiting, up to `max`, without blocking
+    ///
+    /// Stops as soon as the backend has no frame ready. Loopback copies are
+    /// consumed for transmit confirmation and not returned.
+    pub fn drain_frames(&self, max: usize) -> Result<Vec<CanFrame>, RoboMasterError> {
+        let mut frames = Vec::new();
+        while frames.len() < max {
+            if let Some(frame) = self.lock_tx_confirmation().take_deferred() {
+                frames.push(frame);
+                continue;
+            }
+
+            let frame = self.on_backend(|backend| backend.try_recv_frame())
+                .map_err(|e| e.into_error(CanError::ReceiveFailed))?;
+            match frame {
+                Some(frame) if self.observe_error_frame(&frame)? => {}
+                Some(frame) if self.lock_tx_confirmation().observe(&frame) => {}
+                Some(frame) => frames.push(frame),
+                None => break,
+            }
+        }
+        Ok(frames)
+    }
+
     /// Receive and process messages to extract command counters
+    ///
+    /// Waits for one frame, then drains up to [`MAX_DRAIN_FRAMES`] more that
+    /// are already queued so bursts do not pile up in the receive buffer.
+    /// The latest counter echo wins. Extended frames are counted in
+    /// [`skipped_extended_frames`](Self::skipped_extended_frames) and passed
+    /// to the extended frame handler, if set.
     pub async fn receive_and_process(&self, cmd_counters: &mut CommandCounters) -> Result<(), RoboMasterError> {
-        if let Some(frame) = self.receive_message(DEFAULT_CAN_TIMEOUT).await? {
-            let frame_id = match frame.id() {
-                socketcan::Id::Standard(std_id) => std_id.as_raw(),
-                socketcan::Id::Extended(_) => return Ok(()), // Skip extended frames
-            };
-            
-            if frame_id == ROBOMASTER_CAN_ID {
-                let data = frame.data();
-                if data.len() >= 8 && data[0..6] == [0x55, 0x1b, 0x04, 0x75, 0x09, 0xc3] {
-                    let counter = (data[6] as u16) | ((data[7] as u16) << 8);
-                    cmd_counters.joy = counter + 1;
-                }
+        self.receive_and_process_timestamped(cmd_counters).await.map(|_| ())
+    }
+
+    /// Receive and process messages like
+    /// [`receive_and_process`](Self::receive_and_process), returning when the
+    /// first frame was received
+    ///
+    /// Returns `None` if no frame arrived, so callers can measure the
+    /// interval between bursts.
+    pub async fn receive_and_process_timestamped(&self, cmd_counters: &mut CommandCounters) -> Result<Option<Instant>, RoboMasterError> {
+        let Some(first) = self.receive_timestamped(DEFAULT_CAN_TIMEOUT).await? else {
+            return Ok(None);
+        };
+
+        let frames = std::iter::once(first.frame).chain(self.drain_frames(MAX_DRAIN_FRAMES)?);
+        for frame in frames {
+            if self.skip_extended(&frame) {
+                continue;
+            }
+            if let Some(counter) = echo_counter_on(&frame, self.can_id) {
+                cmd_counters.joy = counter.wrapping_add(1);
             }
         }
-        Ok(())
+        Ok(Some(first.received_at))
+    }
+
+    /// Receive one message and return the command counter if it was a counter echo
+    pub async fn receive_echo_counter(&self) -> Result<Option<u16>, RoboMasterError> {
+        let frame = self.receive_message(DEFAULT_CAN_TIMEOUT).await?;
+        Ok(frame.as_ref().and_then(|frame| echo_counter_on(frame, self.can_id)))
     }
 
     /// Close the CAN interface
     pub fn shutdown(&self) {
         println!("----------------------shutdown----------------------");
-        // The socket will be automatically closed when dropped
+        // The backend will be automatically closed when dropped
     }
 
     /// Get the interface name
     pub fn interface_name(&self) -> &str {
         &self.interface_name
     }
+
+    /// Reopen the backend, for example after the adapter was unplugged
+    ///
+    /// Transmit confirmation is re-enabled if it was on. Fails with
+    /// `OpenFailed` if the backend cannot be reopened.
+    pub fn reconnect(&self) -> Result<(), RoboMasterError> {
+        let result = self.reopen_backend();
+        self.lock_reconnect_backoff().reset();
+        self.set_connection_state(match result {
+            Ok(()) => ConnectionState::Connected,
+            Err(_) => ConnectionState::Disconnected,
+        });
+        result
+    }
+
+    /// Reopen the backend automatically when a send or receive fails
+    ///
+    /// Failed operations are retried after each successful reconnection;
+    /// once `policy.max_attempts` reconnections fail, the original error is
+    /// returned. Receives wait out the backoff delays asynchronously. Sends
+    /// and other synchronous calls never sleep: each makes at most one
+    /// attempt once its delay has passed and otherwise fails with
+    /// [`CanError::Reconnecting`] giving the time to retry after. `None`, the
+    /// default, returns errors at once.
+    pub fn set_auto_reconnect(&self, policy: Option<AutoReconnect>) {
+        *self.auto_reconnect.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = policy;
+    }
+
+    /// Get the automatic reconnection settings, if enabled
+    pub fn auto_reconnect(&self) -> Option<AutoReconnect> {
+        *self.auto_reconnect.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Get whether the bus is currently reachable
+    pub fn connection_state(&self) -> ConnectionState {
+        *self.lock_connection_state()
+    }
+
+    /// Get the controller error state reported by the latest error frames
+    ///
+    /// Error frames are read on the receive paths, so the state only
+    /// updates while frames are being received.
+    pub fn bus_state(&self) -> BusState {
+        *self.lock_bus_state()
+    }
+
+    fn lock_bus_state(&self) -> std::sync::MutexGuard<'_, BusState> {
+        self.bus_state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Update the bus state from an error frame, returning whether `frame` was one
+    ///
+    /// Fails with [`CanError::BusOff`] when the controller goes bus-off.
+    fn observe_error_frame(&self, frame: &CanFrame) -> Result<bool, RoboMasterError> {
+        if !matches!(frame, CanFrame::Error(_)) {
+            return Ok(false);
+        }
+        let Some(state) = bus_state::error_frame_state(frame) else {
+            return Ok(true);
+        };
+
+        let previous = std::mem::replace(&mut *self.lock_bus_state(), state);
+        if previous != state {
+            tracing::warn!(?previous, ?state, "CAN bus state changed");
+            if state == BusState::BusOff {
+                return Err(RoboMasterError::CanInterface(CanError::BusOff));
+            }
+        }
+        Ok(true)
+    }
+
+    fn set_connection_state(&self, state: ConnectionState) {
+        *self.lock_connection_state() = state;
+    }
+
+    fn lock_connection_state(&self) -> std::sync::MutexGuard<'_, ConnectionState> {
+        self.connection_state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn backend(&self) -> std::sync::RwLockReadGuard<'_, Box<dyn CanBackend>> {
+        self.backend.read().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn reopen_backend(&self) -> Result<(), RoboMasterError> {
+        let mut backend = self.backend.write().unwrap_or_else(|poisoned| poisoned.into_inner());
+        backend.reconnect()
+            .map_err(|e| RoboMasterError::CanInterface(CanError::OpenFailed {
+                interface: self.interface_name.clone(),
+                source: e,
+            }))?;
+        *self.lock_bus_state() = BusState::Active;
+        if self.tx_confirmation_enabled() {
+            backend.enable_tx_confirmation()
+                .map_err(|e| RoboMasterError::CanInterface(CanError::ConfigureFailed(e)))?;
+        }
+        Ok(())
+    }
+
+    fn lock_reconnect_backoff(&self) -> std::sync::MutexGuard<'_, ReconnectBackoff> {
+        self.reconnect_backoff.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Run `op` on the backend, reconnecting and retrying on failure if
+    /// automatic reconnection is enabled
+    ///
+    /// Never sleeps. While the next reconnection attempt is not yet due this
+    /// fails with [`BackendError::Reconnecting`].
+    fn on_backend<T>(&self, op: impl Fn(&dyn CanBackend) -> io::Result<T>) -> Result<T, BackendError> {
+        let error = match op(self.backend().as_ref()) {
+            Ok(value) => {
+                if self.connection_state() != ConnectionState::Connected {
+                    self.lock_reconnect_backoff().reset();
+                }
+                self.set_connection_state(ConnectionState::Connected);
+                return Ok(value);
+            }
+            Err(error) => error,
+        };
+        let policy = match self.auto_reconnect() {
+            Some(policy) if policy.max_attempts > 0 => policy,
+            _ => {
+                self.set_connection_state(ConnectionState::Disconnected);
+                return Err(BackendError::Failed(error));
+            }
+        };
+
+        self.set_connection_state(ConnectionState::Reconnecting);
+        let mut backoff = self.lock_reconnect_backoff();
+        let now = Instant::now();
+        if let Some(remaining) = backoff.remaining(&policy, now) {
+            return Err(BackendError::Reconnecting(remaining));
+        }
+        let retried = match self.reopen_backend() {
+            Ok(()) => op(self.backend().as_ref()),
+            Err(e) => {
+                tracing::warn!(attempt = backoff.attempt() + 1, "reconnection failed: {}", e);
+                Err(error)
+            }
+        };
+        match retried {
+            Ok(value) => {
+                backoff.reset();
+                self.set_connection_state(ConnectionState::Connected);
+                Ok(value)
+            }
+            Err(error) => match backoff.failed(&policy, now) {
+                Some(delay) => Err(BackendError::Reconnecting(delay)),
+                None => {
+                    self.set_connection_state(ConnectionState::Disconnected);
+                    Err(BackendError::Failed(error))
+                }
+            },
+        }
+    }
+
+    /// Like [`on_backend`](Self::on_backend), but waits out the reconnection
+    /// backoff on the tokio timer instead of failing
+    async fn on_backend_async<T>(&self, op: impl Fn(&dyn CanBackend) -> io::Result<T>) -> Result<T, BackendError> {
+        loop {
+            match self.on_backend(&op) {
+                Err(BackendError::Reconnecting(delay)) => tokio::time::sleep(delay).await,
+                result => return result,
+            }
+        }
+    }
+}
+
+/// Failure of a backend operation run by [`CanInterface::on_backend`]
+#[derive(Debug)]
+enum BackendError {
+    /// The operation failed and will not be retried
+    Failed(io::Error),
+    /// The backend is being reopened; retry after the delay
+    Reconnecting(Duration),
+}
+
+impl BackendError {
+    /// Convert to a [`RoboMasterError`], wrapping an I/O failure with `failed`
+    fn into_error(self, failed: impl FnOnce(io::Error) -> CanError) -> RoboMasterError {
+        RoboMasterError::CanInterface(match self {
+            Self::Failed(error) => failed(error),
+            Self::Reconnecting(delay) => CanError::Reconnecting {
+                retry_after_ms: delay.as_micros().div_ceil(1000) as u64,
+            },
+        })
+    }
 }
 
 impl Drop for CanInterface {
@@ -125,6 +666,40 @@ impl Drop for CanInterface {
     }
 }
 
+/// Pull frames from `receive` until one matches or `timeout_duration` elapses
+///
+/// `receive` is called with the time remaining and returns `Ok(None)` when
+/// nothing arrived within it.
+pub(crate) async fn wait_for_matching_frame<R, Fut, F>(
+    mut receive: R,
+    matches: F,
+    timeout_duration: Duration,
+) -> Result<CanFrame, RoboMasterError>
+where
+    R: FnMut(Duration) -> Fut,
+    Fut: Future<Output = Result<Option<CanFrame>, RoboMasterError>>,
+    F: Fn(&CanFrame) -> bool,
+{
+    let deadline = tokio::time::Instant::now() + timeout_duration;
+
+    loop {
+        let remaining = deadline.saturating_duration_since(tokio::time::Instant::now());
+        if remaining.is_zero() {
+            break;
+        }
+
+        match receive(remaining).await? {
+            Some(frame) if matches(&frame) => return Ok(frame),
+            Some(_) => continue,
+            None => break,
+        }
+    }
+
+    Err(RoboMasterError::Timeout {
+        timeout_ms: timeout_duration.as_millis() as u64,
+    })
+}
+
 /// Command counters for different command types
 #[derive(Debug, Clone)]
 pub struct CommandCounters {
@@ -160,12 +735,290 @@ impl MessageSplitter {
         
         can_command_list
     }
+
+    /// Split a command into 8-byte CAN frames, reusing `frames`
+    ///
+    /// `frames` is overwritten with the frames of `command`. Its existing
+    /// frame buffers are reused, so a buffer kept across calls stops
+    /// allocating once it has held the longest command.
+    pub fn split_command_into(command: &[u8], frames: &mut Vec<Vec<u8>>) {
+        let chunks = command.chunks(CAN_MAX_DATA_LEN);
+        let count = chunks.len();
+
+        for (i, chunk) in chunks.enumerate() {
+            match frames.get_mut(i) {
+                Some(frame) => {
+                    frame.clear();
+                    frame.extend_from_slice(chunk);
+                }
+                None => frames.push(chunk.to_vec()),
+            }
+        }
+        frames.truncate(count);
+    }
+
+    /// Split a command into CAN-FD frames of up to 64 bytes
+    ///
+    /// Every built command fits in one frame except the boot sequence. The
+    /// chunks are not padded to a valid CAN-FD length; the FD interface does
+    /// that when it builds the frames.
+    pub fn fd_chunks(command: &[u8]) -> Vec<Vec<u8>> {
+        command.chunks(CANFD_MAX_DATA_LEN).map(<[u8]>::to_vec).collect()
+    }
+}
+
+/// Get the standard identifier of a frame, or `None` for extended frames
+pub fn standard_id(frame: &CanFrame) -> Option<u16> {
+    match frame.id() {
+        socketcan::Id::Standard(std_id) => Some(std_id.as_raw()),
+        socketcan::Id::Extended(_) => None,
+    }
+}
+
+/// Build a frame carrying `data` on the standard ID `can_id`
+fn build_frame_on(can_id: u16, data: &[u8]) -> Result<CanFrame, RoboMasterError> {
+    if data.len() > CAN_MAX_DATA_LEN {
+        return Err(RoboMasterError::CanInterface(CanError::InvalidDataLength {
+            length: data.len(),
+            max_length: CAN_MAX_DATA_LEN,
+        }));
+    }
+
+    let standard_id = StandardId::new(can_id)
+        .ok_or_else(|| RoboMasterError::CanInterface(CanError::InvalidMessage {
+            reason: "Invalid CAN ID".to_string(),
+        }))?;
+
+    CanFrame::new(standard_id, data)
+        .ok_or_else(|| RoboMasterError::CanInterface(CanError::FrameCreation(
+            std::io::Error::new(std::io::ErrorKind::InvalidData, "Failed to create CAN frame")
+        )))
+}
+
+/// Check that `can_id` fits in an 11-bit standard identifier
+pub fn validate_can_id(can_id: u16) -> Result<(), RoboMasterError> {
+    if can_id > MAX_STANDARD_CAN_ID {
+        return Err(RoboMasterError::CanInterface(CanError::InvalidMessage {
+            reason: format!("CAN ID {:#x} does not fit in an 11-bit standard identifier", can_id),
+        }));
+    }
+    Ok(())
+}
+
+/// Get the command counter echoed by the robot, if `frame` is a counter echo
+pub fn echo_counter(frame: &CanFrame) -> Option<u16> {
+    echo_counter_on(frame, ROBOMASTER_CAN_ID)
+}
+
+/// Get the command counter echoed by a robot talking on `can_id`
+pub fn echo_counter_on(frame: &CanFrame, can_id: u16) -> Option<u16> {
+    if standard_id(frame)? != can_id {
+        return None;
+    }
+    let data = frame.data();
+    if data.len() >= 8 && data[0..6] == [0x55, 0x1b, 0x04, 0x75, 0x09, 0xc3] {
+        Some((data[6] as u16) | ((data[7] as u16) << 8))
+    } else {
+        None
+    }
+}
+
+/// Check a reassembled message against the length declared in its header
+///
+/// Returns the declared length on success.
+pub fn validate_message_length(message: &[u8]) -> Result<usize, ProtocolError> {
+    let declared = *message.get(1).ok_or(ProtocolError::MessageTooShort {
+        expected: 2,
+        actual: message.len(),
+    })? as usize;
+
+    if declared < MIN_MESSAGE_LEN {
+        return Err(ProtocolError::InvalidHeader {
+            reason: format!("declared length {} is below the minimum of {}", declared, MIN_MESSAGE_LEN),
+        });
+    }
+    if message.len() < declared {
+        return Err(ProtocolError::MessageTooShort {
+            expected: declared,
+            actual: message.len(),
+        });
+    }
+    if message.len() > declared {
+        return Err(ProtocolError::MessageTooLong {
+            max: declared,
+            actual: message.len(),
+        });
+    }
+    Ok(declared)
+}
+
+/// Reassembles protocol messages from the data of consecutive CAN frames
+#[derive(Debug, Default)]
+pub struct MessageReassembler {
+    buffer: Vec<u8>,
+}
+
+impl MessageReassembler {
+    /// Create an empty reassembler
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append the data of one CAN frame
+    ///
+    /// Returns the message once the declared length has been received.
+    /// Frames received outside a message are ignored, and a frame that runs
+    /// past the declared length discards the message with `MessageTooLong`.
+    pub fn push(&mut self, data: &[u8]) -> Result<Option<Vec<u8>>, ProtocolError> {
+        if self.buffer.is_empty() && data.first() != Some(&MESSAGE_SOF) {
+            return Ok(None);
+        }
+        self.buffer.extend_from_slice(data);
+
+        let Some(&declared) = self.buffer.get(1) else {
+            return Ok(None);
+        };
+        if self.buffer.len() < declared as usize {
+            return Ok(None);
+        }
+
+        let message = std::mem::take(&mut self.buffer);
+        validate_message_length(&message).map(|_| Some(message))
+    }
+
+    /// Number of bytes buffered for an incomplete message
+    pub fn pending(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Discard any partially received message
+    pub fn reset(&mut self) {
+        self.buffer.clear();
+    }
+}
+
+/// Check that a command survives splitting into CAN frames and reassembly
+///
+/// Returns `false` if the command is not a well-framed message or the
+/// reassembled message differs from the original.
+pub fn verify_roundtrip(command: &[u8]) -> bool {
+    let mut reassembler = MessageReassembler::new();
+    let mut reassembled = None;
+
+    for frame in MessageSplitter::split_command(command) {
+        if reassembled.is_some() {
+            return false;
+        }
+        match reassembler.push(&frame) {
+            Ok(message) => reassembled = message,
+            Err(_) => return false,
+        }
+    }
+
+    reassembled.as_deref() == Some(command)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn framed_message(len: usize) -> Vec<u8> {
+        let mut message = vec![0u8; len];
+        message[0] = MESSAGE_SOF;
+        message[1] = len as u8;
+        message
+    }
+
+    #[test]
+    fn test_validate_truncated_message() {
+        let message = framed_message(20);
+        assert!(matches!(
+            validate_message_length(&message[..15]),
+            Err(ProtocolError::MessageTooShort { expected: 20, actual: 15 })
+        ));
+    }
+
+    #[test]
+    fn test_validate_over_long_message() {
+        let mut message = framed_message(20);
+        message.extend([0, 0, 0]);
+        assert!(matches!(
+            validate_message_length(&message),
+            Err(ProtocolError::MessageTooLong { max: 20, actual: 23 })
+        ));
+        assert_eq!(validate_message_length(&framed_message(20)).unwrap(), 20);
+    }
+
+    #[test]
+    fn test_reassembler_round_trip() {
+        let message = framed_message(27);
+        let mut reassembler = MessageReassembler::new();
+
+        let frames = MessageSplitter::split_command(&message);
+        let (last, rest) = frames.split_last().unwrap();
+        for frame in rest {
+            assert_eq!(reassembler.push(frame).unwrap(), None);
+        }
+        assert_eq!(reassembler.push(last).unwrap(), Some(message));
+        assert_eq!(reassembler.pending(), 0);
+    }
+
+    #[test]
+    fn test_reassembler_rejects_over_long_frame() {
+        let mut reassembler = MessageReassembler::new();
+        let message = framed_message(13);
+
+        assert_eq!(reassembler.push(&message[..8]).unwrap(), None);
+        assert!(matches!(
+            reassembler.push(&[0; 8]),
+            Err(ProtocolError::MessageTooLong { max: 13, actual: 16 })
+        ));
+        assert_eq!(reassembler.pending(), 0);
+    }
+
+    #[test]
+    fn test_verify_roundtrip() {
+        assert!(verify_roundtrip(&framed_message(13)));
+        assert!(verify_roundtrip(&framed_message(16)));
+
+        // Declared length does not match the command
+        let mut mismatched = framed_message(20);
+        mismatched[1] = 18;
+        assert!(!verify_roundtrip(&mismatched));
+        assert!(!verify_roundtrip(&[0x12, 0x34]));
+        assert!(!verify_roundtrip(&[]));
+    }
+
+    #[test]
+    fn test_command_table_round_trips() {
+        let builder = crate::command::CommandBuilder::new();
+        let counters = CommandCounters::default();
+        let led = builder
+            .build_led_command(crate::command::LedColor { red: 1, green: 2, blue: 3 }, &counters)
+            .unwrap();
+        assert!(verify_roundtrip(&led));
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn prop_framed_commands_round_trip(
+            body in proptest::collection::vec(proptest::num::u8::ANY, (MIN_MESSAGE_LEN - 2)..=253),
+        ) {
+            let mut command = vec![MESSAGE_SOF, (body.len() + 2) as u8];
+            command.extend(body);
+            proptest::prop_assert!(verify_roundtrip(&command));
+        }
+
+        #[test]
+        fn prop_split_preserves_bytes(
+            command in proptest::collection::vec(proptest::num::u8::ANY, 0..512),
+        ) {
+            let frames = MessageSplitter::split_command(&command);
+            proptest::prop_assert!(frames.iter().all(|frame| !frame.is_empty() && frame.len() <= CAN_MAX_DATA_LEN));
+            proptest::prop_assert_eq!(frames.concat(), command);
+        }
+    }
+
     #[test]
     fn test_message_splitter_exact_size() {
         let command = vec![1, 2, 3, 4, 5, 6, 7, 8];
@@ -192,6 +1045,369 @@ mod tests {
         assert_eq!(result[1], vec![9]);
     }
 
+    #[test]
+    fn test_split_command_into_reuses_frames() {
+        let mut frames = Vec::new();
+        let long: Vec<u8> = (0..20).collect();
+        MessageSplitter::split_command_into(&long, &mut frames);
+        assert_eq!(frames, MessageSplitter::split_command(&long));
+        let first_frame = frames[0].as_ptr();
+
+        MessageSplitter::split_command_into(&[1, 2, 3, 4, 5, 6, 7, 8, 9], &mut frames);
+        assert_eq!(frames, vec![vec![1, 2, 3, 4, 5, 6, 7, 8], vec![9]]);
+        assert_eq!(frames[0].as_ptr(), first_frame, "Frame buffer should be reused");
+
+        MessageSplitter::split_command_into(&[], &mut frames);
+        assert!(frames.is_empty());
+    }
+
+    #[test]
+    fn test_fd_chunks_keeps_commands_whole() {
+        let twist = framed_message(27);
+        assert_eq!(MessageSplitter::fd_chunks(&twist), vec![twist.clone()]);
+
+        let long: Vec<u8> = (0..150).collect();
+        let chunks = MessageSplitter::fd_chunks(&long);
+        assert_eq!(chunks.iter().map(Vec::len).collect::<Vec<_>>(), vec![64, 64, 22]);
+        assert_eq!(chunks.concat(), long);
+        assert!(MessageSplitter::fd_chunks(&[]).is_empty());
+    }
+
+    fn frame(data: &[u8]) -> CanFrame {
+        CanFrame::new(StandardId::new(ROBOMASTER_CAN_ID).unwrap(), data).unwrap()
+    }
+
+    #[test]
+    fn test_can_id_must_fit_in_11_bits() {
+        assert!(validate_can_id(MAX_STANDARD_CAN_ID).is_ok());
+        assert!(matches!(
+            validate_can_id(0x800),
+            Err(RoboMasterError::CanInterface(CanError::InvalidMessage { .. }))
+        ));
+
+        let mut can_interface = CanInterface::with_backend(MockCanBackend::new());
+        assert!(can_interface.set_can_id(0x1000).is_err());
+        assert_eq!(can_interface.can_id(), ROBOMASTER_CAN_ID);
+    }
+
+    #[tokio::test]
+    async fn test_configured_can_id_used_for_tx_and_rx() {
+        let mock = MockCanBackend::new();
+        let mut can_interface = CanInterface::with_backend(mock.clone());
+        can_interface.set_can_id(0x202).unwrap();
+
+        can_interface.send_message(&[1, 2, 3]).unwrap();
+        assert_eq!(mock.sent_ids(), vec![0x202]);
+
+        let echo = |counter: u8| [0x55, 0x1b, 0x04, 0x75, 0x09, 0xc3, counter, 0x00];
+        mock.queue_frame(frame(&echo(5)));
+
+        let mut counters = CommandCounters::default();
+        can_interface.receive_and_process(&mut counters).await.unwrap();
+        assert_eq!(counters.joy, 0, "Echo on the default ID should be ignored");
+
+        mock.queue_frame(CanFrame::new(StandardId::new(0x202).unwrap(), &echo(9)).unwrap());
+        can_interface.receive_and_process(&mut counters).await.unwrap();
+        assert_eq!(counters.joy, 10);
+    }
+
+    #[tokio::test]
+    async fn test_receive_and_process_drains_queued_burst() {
+        let mock = MockCanBackend::new();
+        let can_interface = CanInterface::with_backend(mock.clone());
+
+        let echo = |counter: u8| [0x55, 0x1b, 0x04, 0x75, 0x09, 0xc3, counter, 0x00];
+        for counter in 1..=5 {
+            mock.queue_frame(frame(&echo(counter)));
+        }
+        mock.queue_frame(frame(&[0x55, 0x0d, 0x04]));
+
+        let mut counters = CommandCounters::default();
+        can_interface.receive_and_process(&mut counters).await.unwrap();
+        assert_eq!(counters.joy, 6, "Latest echo in the burst should win");
+        assert_eq!(mock.queued(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_receive_and_process_wraps_echoed_counter() {
+        let mock = MockCanBackend::new();
+        let can_interface = CanInterface::with_backend(mock.clone());
+        mock.queue_frame(frame(&[0x55, 0x1b, 0x04, 0x75, 0x09, 0xc3, 0xFF, 0xFF]));
+
+        let mut counters = CommandCounters::default();
+        can_interface.receive_and_process(&mut counters).await.unwrap();
+        assert_eq!(counters.joy, 0);
+    }
+
+    #[tokio::test]
+    async fn test_received_frames_are_timestamped() {
+        let mock = MockCanBackend::new();
+        let can_interface = CanInterface::with_backend(mock.clone());
+        let mut counters = CommandCounters::default();
+
+        let before = Instant::now();
+        mock.queue_frame(frame(&[1]));
+        let received = can_interface.receive_timestamped(DEFAULT_CAN_TIMEOUT).await.unwrap().unwrap();
+        assert_eq!(received.frame.data(), &[1]);
+        assert!(received.received_at >= before && received.received_at <= Instant::now());
+
+        mock.queue_frame(frame(&[2]));
+        let first = can_interface.receive_and_process_timestamped(&mut counters).await.unwrap().unwrap();
+        mock.queue_frame(frame(&[3]));
+        let second = can_interface.receive_and_process_timestamped(&mut counters).await.unwrap().unwrap();
+        assert!(first >= received.received_at && second >= first);
+        assert_eq!(can_interface.receive_and_process_timestamped(&mut counters).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_receive_and_process_counts_skipped_extended_frames() {
+        use socketcan::ExtendedId;
+        use std::sync::Arc;
+
+        let mock = MockCanBackend::new();
+        let can_interface = CanInterface::with_backend(mock.clone());
+        let handled = Arc::new(AtomicU64::new(0));
+        let seen = Arc::clone(&handled);
+        can_interface.set_extended_frame_handler(move |_| {
+            seen.fetch_add(1, Ordering::Relaxed);
+        });
+
+        let extended = CanFrame::new(ExtendedId::new(0x1234_5678).unwrap(), &[1, 2, 3]).unwrap();
+        mock.queue_frame(extended);
+        mock.queue_frame(frame(&[0x55, 0x1b, 0x04, 0x75, 0x09, 0xc3, 0x02, 0x00]));
+
+        let mut counters = CommandCounters::default();
+        can_interface.receive_and_process(&mut counters).await.unwrap();
+        assert_eq!(can_interface.skipped_extended_frames(), 1);
+        assert_eq!(handled.load(Ordering::Relaxed), 1);
+        assert_eq!(counters.joy, 3, "Standard frames are still processed");
+    }
+
+    #[test]
+    fn test_drain_frames_stops_at_max_or_when_empty() {
+        let mock = MockCanBackend::new();
+        let can_interface = CanInterface::with_backend(mock.clone());
+        for i in 0..5 {
+            mock.queue_frame(frame(&[i]));
+        }
+
+        assert_eq!(can_interface.drain_frames(3).unwrap().len(), 3);
+        let rest = can_interface.drain_frames(10).unwrap();
+        assert_eq!(rest.iter().map(|f| f.data()[0]).collect::<Vec<_>>(), vec![3, 4]);
+        assert!(can_interface.drain_frames(10).unwrap().is_empty());
+    }
+
+    fn quick_reconnect(max_attempts: u32) -> AutoReconnect {
+        AutoReconnect {
+            max_attempts,
+            initial_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(4),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_error_frames_track_bus_state() {
+        use socketcan::CanErrorFrame;
+
+        let mock = MockCanBackend::new();
+        let can_interface = CanInterface::with_backend(mock.clone());
+        let error_frame = |class: u32, status: u8| -> CanFrame {
+            CanErrorFrame::new_error(class, &[0, status, 0, 0, 0, 0, 0, 0]).unwrap().into()
+        };
+
+        // Controller error-passive, then bus-off
+        mock.queue_frame(error_frame(0x0004, 0x20));
+        mock.queue_frame(frame(&[1]));
+        let received = can_interface.receive_message(DEFAULT_CAN_TIMEOUT).await.unwrap();
+        assert_eq!(received.map(|f| f.data().to_vec()), Some(vec![1]));
+        assert_eq!(can_interface.bus_state(), BusState::ErrorPassive);
+
+        mock.queue_frame(error_frame(0x0040, 0));
+        assert!(matches!(
+            can_interface.receive_message(DEFAULT_CAN_TIMEOUT).await,
+            Err(RoboMasterError::CanInterface(CanError::BusOff))
+        ));
+        assert_eq!(can_interface.bus_state(), BusState::BusOff);
+
+        mock.disconnect();
+        let error = can_interface.send_message(&[2]).unwrap_err();
+        assert!(matches!(error, RoboMasterError::CanInterface(CanError::BusOff)));
+        assert!(!error.is_recoverable());
+
+        // Restarted controller
+        can_interface.reconnect().unwrap();
+        mock.queue_frame(error_frame(0x0040, 0));
+        mock.queue_frame(error_frame(0x0100, 0));
+        assert!(can_interface.drain_frames(10).is_err());
+        assert!(can_interface.drain_frames(10).unwrap().is_empty());
+        assert_eq!(can_interface.bus_state(), BusState::Active);
+    }
+
+    #[test]
+    fn test_manual_reconnect_after_send_failure() {
+        let mock = MockCanBackend::new();
+        let can_interface = CanInterface::with_backend(mock.clone());
+
+        mock.disconnect();
+        assert!(matches!(
+            can_interface.send_message(&[1]),
+            Err(RoboMasterError::CanInterface(CanError::SendFailed(_)))
+        ));
+        assert_eq!(can_interface.connection_state(), ConnectionState::Disconnected);
+
+        can_interface.reconnect().unwrap();
+        assert_eq!(can_interface.connection_state(), ConnectionState::Connected);
+        can_interface.send_message(&[2]).unwrap();
+        assert_eq!(mock.sent_frames(), vec![vec![2]]);
+    }
+
+    /// Send `data`, waiting out every `Reconnecting` error, and count the retries
+    fn send_retrying(can_interface: &CanInterface, data: &[u8]) -> (Result<(), RoboMasterError>, u32) {
+        let mut retries = 0;
+        loop {
+            match can_interface.send_message(data) {
+                Err(RoboMasterError::CanInterface(CanError::Reconnecting { retry_after_ms })) => {
+                    assert_eq!(can_interface.connection_state(), ConnectionState::Reconnecting);
+                    std::thread::sleep(Duration::from_millis(retry_after_ms));
+                    retries += 1;
+                }
+                result => return (result, retries),
+            }
+        }
+    }
+
+    #[test]
+    fn test_auto_reconnect_retries_with_backoff() {
+        let mock = MockCanBackend::new();
+        let can_interface = CanInterface::with_backend(mock.clone());
+        can_interface.set_auto_reconnect(Some(quick_reconnect(3)));
+
+        // Each failing send returns at once instead of sleeping through the backoff
+        mock.disconnect();
+        mock.fail_reconnects(2);
+        let (result, retries) = send_retrying(&can_interface, &[1]);
+        result.unwrap();
+        assert_eq!(retries, 3, "One wait before each of the three attempts");
+        assert_eq!(mock.reconnects(), 1);
+        assert_eq!(mock.sent_frames(), vec![vec![1]]);
+        assert_eq!(can_interface.connection_state(), ConnectionState::Connected);
+
+        mock.disconnect();
+        mock.fail_reconnects(3);
+        let (result, _) = send_retrying(&can_interface, &[2]);
+        assert!(matches!(result, Err(RoboMasterError::CanInterface(CanError::SendFailed(_)))));
+        assert_eq!(can_interface.connection_state(), ConnectionState::Disconnected);
+    }
+
+    #[tokio::test]
+    async fn test_auto_reconnect_on_receive() {
+        let mock = MockCanBackend::new();
+        let can_interface = CanInterface::with_backend(mock.clone());
+        can_interface.set_auto_reconnect(Some(quick_reconnect(1)));
+        mock.queue_frame(frame(&[7]));
+
+        mock.disconnect();
+        let received = can_interface.receive_message(DEFAULT_CAN_TIMEOUT).await.unwrap();
+        assert_eq!(received.map(|f| f.data().to_vec()), Some(vec![7]));
+        assert_eq!(mock.reconnects(), 1);
+    }
+
+    #[test]
+    fn test_send_raw_frame_uses_given_id_and_bytes() {
+        let mock = MockCanBackend::new();
+        let can_interface = CanInterface::with_backend(mock.clone());
+
+        can_interface.send_raw_frame(0x123, &[0xDE, 0xAD, 0xBE, 0xEF]).unwrap();
+        assert_eq!(mock.sent_ids(), vec![0x123]);
+        assert_eq!(mock.take_sent_frames(), vec![vec![0xDE, 0xAD, 0xBE, 0xEF]]);
+
+        assert!(matches!(
+            can_interface.send_raw_frame(0x800, &[0]),
+            Err(RoboMasterError::CanInterface(CanError::InvalidMessage { .. }))
+        ));
+        assert!(matches!(
+            can_interface.send_raw_frame(0x123, &[0; CAN_MAX_DATA_LEN + 1]),
+            Err(RoboMasterError::CanInterface(CanError::InvalidDataLength { .. }))
+        ));
+        assert!(mock.sent_frames().is_empty());
+    }
+
+    #[test]
+    fn test_send_messages_validates_every_frame_before_sending() {
+        let mock = MockCanBackend::new();
+        let can_interface = CanInterface::with_backend(mock.clone());
+
+        let result = can_interface.send_messages(&[vec![1, 2], vec![0; CAN_MAX_DATA_LEN + 1]]);
+        assert!(matches!(
+            result,
+            Err(RoboMasterError::CanInterface(CanError::InvalidDataLength { .. }))
+        ));
+        assert!(mock.sent_frames().is_empty(), "No frame of an invalid command should be sent");
+
+        can_interface.send_messages(&[vec![1, 2], vec![3]]).unwrap();
+        assert_eq!(mock.sent_frames(), vec![vec![1, 2], vec![3]]);
+    }
+
+    #[tokio::test]
+    async fn test_loopback_frames_confirm_tx_and_are_not_received() {
+        let mock = MockCanBackend::new();
+        let can_interface = CanInterface::with_backend(mock.clone());
+        assert!(!can_interface.confirm_tx().unwrap());
+
+        can_interface.enable_tx_confirmation().unwrap();
+        mock.queue_frame(frame(&[0x55, 0x0d, 0x04]));
+        can_interface.send_messages(&[vec![1, 2], vec![3]]).unwrap();
+        assert!(can_interface.confirm_tx().unwrap());
+
+        // The robot frame read while confirming is still delivered
+        let received = can_interface.receive_message(DEFAULT_CAN_TIMEOUT).await.unwrap();
+        assert_eq!(received.unwrap().data(), &[0x55, 0x0d, 0x04]);
+
+        can_interface.send_message(&[4]).unwrap();
+        mock.queue_frame(frame(&[0x55, 0x0e, 0x04]));
+        let received = can_interface.receive_message(DEFAULT_CAN_TIMEOUT).await.unwrap();
+        assert_eq!(received.unwrap().data(), &[0x55, 0x0e, 0x04], "Loopback copy should be skipped");
+        assert!(can_interface.confirm_tx().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_matching_frame_skips_other_frames() {
+        let mut queue = std::collections::VecDeque::from(vec![
+            frame(&[0x55, 0x0d, 0x04]),
+            frame(&[0x55, 0x1b, 0x04, 0x75, 0x09, 0xc3, 0x10, 0x00]),
+        ]);
+
+        let response = wait_for_matching_frame(
+            |_| {
+                let next = queue.pop_front();
+                async move { Ok(next) }
+            },
+            |frame| frame.data().get(1) == Some(&0x1b),
+            Duration::from_millis(100),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(response.data()[6], 0x10);
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_matching_frame_times_out() {
+        let mut queue = std::collections::VecDeque::from(vec![frame(&[0x55, 0x0d, 0x04])]);
+
+        let result = wait_for_matching_frame(
+            |_| {
+                let next = queue.pop_front();
+                async move { Ok(next) }
+            },
+            |frame| frame.data().get(1) == Some(&0x1b),
+            Duration::from_millis(50),
+        )
+        .await;
+
+        assert!(matches!(result, Err(RoboMasterError::Timeout { timeout_ms: 50 })));
+    }
+
     #[test]
     fn test_command_counters_default() {
         let counters = CommandCounters::default();
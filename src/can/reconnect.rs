@@ -0,0 +1,128 @@
+//! Reopening a failed CAN backend with exponential backoff
+
+use std::time::{Duration, Instant};
+
+/// Default number of reconnection attempts before an error is returned
+pub const DEFAULT_RECONNECT_ATTEMPTS: u32 = 5;
+
+/// Default delay before the first reconnection attempt
+pub const DEFAULT_RECONNECT_DELAY: Duration = Duration::from_millis(100);
+
+/// Default cap on the delay between reconnection attempts
+pub const DEFAULT_MAX_RECONNECT_DELAY: Duration = Duration::from_secs(2);
+
+/// Whether a [`CanInterface`](super::CanInterface) can currently reach the bus
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ConnectionState {
+    /// The last send or receive succeeded
+    #[default]
+    Connected,
+    /// A send or receive failed and the backend is being reopened
+    Reconnecting,
+    /// The last send or receive failed and the backend was not reopened
+    Disconnected,
+}
+
+/// Settings for reopening the backend when a send or receive fails
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AutoReconnect {
+    /// Reconnection attempts before the original error is returned
+    pub max_attempts: u32,
+    /// Delay before the first attempt, doubled after each failed one
+    pub initial_delay: Duration,
+    /// Cap on the delay between attempts
+    pub max_delay: Duration,
+}
+
+impl AutoReconnect {
+    /// Get the delay before reconnection attempt `attempt`, counting from zero
+    pub fn delay(&self, attempt: u32) -> Duration {
+        self.initial_delay
+            .saturating_mul(1 << attempt.min(16))
+            .min(self.max_delay)
+    }
+}
+
+impl Default for AutoReconnect {
+    fn default() -> Self {
+        Self {
+            max_attempts: DEFAULT_RECONNECT_ATTEMPTS,
+            initial_delay: DEFAULT_RECONNECT_DELAY,
+            max_delay: DEFAULT_MAX_RECONNECT_DELAY,
+        }
+    }
+}
+
+/// Progress through the attempts of an [`AutoReconnect`] policy
+///
+/// Tracks when the next attempt is due instead of sleeping, so a failed
+/// send or receive never blocks the thread it runs on.
+#[derive(Debug, Default)]
+pub(crate) struct ReconnectBackoff {
+    attempt: u32,
+    retry_at: Option<Instant>,
+}
+
+impl ReconnectBackoff {
+    /// Get the time left before the next attempt is due, if any
+    ///
+    /// The first call after a failure starts the wait before attempt zero.
+    pub(crate) fn remaining(&mut self, policy: &AutoReconnect, now: Instant) -> Option<Duration> {
+        let retry_at = *self.retry_at.get_or_insert_with(|| now + policy.delay(0));
+        (retry_at > now).then(|| retry_at - now)
+    }
+
+    /// Get the number of attempts made so far
+    pub(crate) fn attempt(&self) -> u32 {
+        self.attempt
+    }
+
+    /// Record a failed attempt and get the delay before the next one
+    ///
+    /// Returns `None` and starts over once the policy's attempts are used up.
+    pub(crate) fn failed(&mut self, policy: &AutoReconnect, now: Instant) -> Option<Duration> {
+        self.attempt += 1;
+        if self.attempt >= policy.max_attempts {
+            self.reset();
+            return None;
+        }
+        let delay = policy.delay(self.attempt);
+        self.retry_at = Some(now + delay);
+        Some(delay)
+    }
+
+    /// Forget any reconnection in progress
+    pub(crate) fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delay_doubles_up_to_cap() {
+        let policy = AutoReconnect::default();
+        let delays: Vec<u64> = (0..7).map(|attempt| policy.delay(attempt).as_millis() as u64).collect();
+        assert_eq!(delays, vec![100, 200, 400, 800, 1600, 2000, 2000]);
+        assert_eq!(policy.delay(u32::MAX), DEFAULT_MAX_RECONNECT_DELAY);
+    }
+
+    #[test]
+    fn test_backoff_waits_between_attempts_then_gives_up() {
+        let policy = AutoReconnect { max_attempts: 2, ..AutoReconnect::default() };
+        let mut backoff = ReconnectBackoff::default();
+        let start = Instant::now();
+
+        assert_eq!(backoff.remaining(&policy, start), Some(DEFAULT_RECONNECT_DELAY));
+        let due = start + DEFAULT_RECONNECT_DELAY;
+        assert_eq!(backoff.remaining(&policy, due), None);
+
+        assert_eq!(backoff.failed(&policy, due), Some(2 * DEFAULT_RECONNECT_DELAY));
+        assert_eq!(backoff.attempt(), 1);
+        assert!(backoff.remaining(&policy, due).is_some());
+        assert_eq!(backoff.failed(&policy, due + 2 * DEFAULT_RECONNECT_DELAY), None);
+        assert_eq!(backoff.attempt(), 0, "A new failure starts over");
+    }
+}
@@ -0,0 +1,128 @@
+//! Transmit confirmation from looped-back frames
+//!
+//! With own-message reception enabled, SocketCAN hands each frame back to the
+//! socket that sent it once the frame has left the bus. Matching those copies
+//! against the frames we wrote tells "queued" apart from "sent".
+
+use socketcan::{CanFrame, EmbeddedFrame};
+use std::collections::VecDeque;
+
+/// Most sent frames kept waiting for their loopback copy
+pub const MAX_PENDING_TX: usize = 256;
+
+/// Tracks sent frames until their loopback copy is received
+#[derive(Debug, Default)]
+pub struct TxConfirmation {
+    enabled: bool,
+    pending: VecDeque<CanFrame>,
+    deferred: VecDeque<CanFrame>,
+}
+
+impl TxConfirmation {
+    /// Start tracking sent frames
+    pub fn enable(&mut self) {
+        self.enabled = true;
+    }
+
+    /// Check whether sent frames are being tracked
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Record a frame handed to the backend
+    ///
+    /// The oldest frame is forgotten once [`MAX_PENDING_TX`] are waiting.
+    pub fn record_sent(&mut self, frame: CanFrame) {
+        if !self.enabled {
+            return;
+        }
+        if self.pending.len() == MAX_PENDING_TX {
+            self.pending.pop_front();
+        }
+        self.pending.push_back(frame);
+    }
+
+    /// Consume `frame` if it is the loopback copy of a sent frame
+    ///
+    /// Frames leave the bus in the order they were written, so every frame
+    /// sent before the matching one is confirmed along with it.
+    pub fn observe(&mut self, frame: &CanFrame) -> bool {
+        let position = self
+            .pending
+            .iter()
+            .position(|sent| sent.id() == frame.id() && sent.data() == frame.data());
+        match position {
+            Some(position) => {
+                self.pending.drain(..=position);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Check whether every sent frame has been confirmed
+    pub fn is_confirmed(&self) -> bool {
+        self.enabled && self.pending.is_empty()
+    }
+
+    /// Number of sent frames still waiting for confirmation
+    pub fn pending(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Keep a frame from the robot that was read while waiting for confirmation
+    pub fn defer(&mut self, frame: CanFrame) {
+        self.deferred.push_back(frame);
+    }
+
+    /// Take the oldest deferred frame
+    pub fn take_deferred(&mut self) -> Option<CanFrame> {
+        self.deferred.pop_front()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use socketcan::StandardId;
+
+    fn frame(data: &[u8]) -> CanFrame {
+        CanFrame::new(StandardId::new(0x201).unwrap(), data).unwrap()
+    }
+
+    #[test]
+    fn test_disabled_tracker_never_confirms() {
+        let mut tx = TxConfirmation::default();
+        tx.record_sent(frame(&[1]));
+
+        assert_eq!(tx.pending(), 0);
+        assert!(!tx.is_confirmed());
+    }
+
+    #[test]
+    fn test_loopback_copy_confirms_earlier_frames() {
+        let mut tx = TxConfirmation::default();
+        tx.enable();
+        tx.record_sent(frame(&[1]));
+        tx.record_sent(frame(&[2]));
+        tx.record_sent(frame(&[3]));
+
+        assert!(!tx.observe(&frame(&[9])));
+        assert!(tx.observe(&frame(&[2])));
+        assert_eq!(tx.pending(), 1);
+        assert!(!tx.is_confirmed());
+
+        assert!(tx.observe(&frame(&[3])));
+        assert!(tx.is_confirmed());
+    }
+
+    #[test]
+    fn test_pending_frames_are_bounded() {
+        let mut tx = TxConfirmation::default();
+        tx.enable();
+        for i in 0..MAX_PENDING_TX + 10 {
+            tx.record_sent(frame(&(i as u16).to_le_bytes()));
+        }
+        assert_eq!(tx.pending(), MAX_PENDING_TX);
+    }
+}
@@ -1,20 +1,159 @@
 /// Command builder for creating RoboMaster protocol messages
 /// This module contains the core logic for building commands from templates
 
-use crate::command::{get_command_table, commands, get_command_length, is_crc8_position, is_counter_position};
+use crate::command::{
+    shared_command_table, CommandTemplate, CommandId, BOOT_SEQUENCE, get_command_length, is_crc8_position,
+    is_counter_position, create_command_map, find_crc16_positions, proto,
+};
 use crate::crc::{crc8::append_crc8_checksum, crc16::append_crc16_checksum};
+use crate::crc::{verify_crc8_checksum, verify_crc16_checksum, Crc16};
 use crate::can::CommandCounters;
 use crate::error::{RoboMasterError, ProtocolError};
 use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+use std::sync::Arc;
 
 /// Movement command parameters
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
 pub struct MovementParams {
     pub vx: f32,  // Linear velocity X (forward/backward)
     pub vy: f32,  // Linear velocity Y (left/right)  
     pub vz: f32,  // Angular velocity Z (rotation)
 }
 
+impl MovementParams {
+    /// Movement that brings the chassis to a stop
+    pub const fn stopped() -> Self {
+        Self { vx: 0.0, vy: 0.0, vz: 0.0 }
+    }
+}
+
+/// Per-wheel speeds for the four mecanum wheels, in RPM
+///
+/// Positive values roll a wheel so that it drives the robot forward.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct WheelSpeeds {
+    /// Front-left wheel speed in RPM
+    pub front_left: f32,
+    /// Front-right wheel speed in RPM
+    pub front_right: f32,
+    /// Rear-left wheel speed in RPM
+    pub rear_left: f32,
+    /// Rear-right wheel speed in RPM
+    pub rear_right: f32,
+}
+
+impl WheelSpeeds {
+    /// All four wheels stopped
+    pub const fn stopped() -> Self {
+        Self { front_left: 0.0, front_right: 0.0, rear_left: 0.0, rear_right: 0.0 }
+    }
+
+    /// Clamp every wheel to ±[`MAX_WHEEL_RPM`]
+    pub fn clamped(self) -> Self {
+        self.capped(MAX_WHEEL_RPM)
+    }
+
+    /// Clamp every wheel to ±`max_rpm`, and at most ±[`MAX_WHEEL_RPM`]
+    pub fn capped(self, max_rpm: f32) -> Self {
+        let max_rpm = max_rpm.clamp(0.0, MAX_WHEEL_RPM);
+        let clamp = |rpm: f32| rpm.clamp(-max_rpm, max_rpm);
+        Self {
+            front_left: clamp(self.front_left),
+            front_right: clamp(self.front_right),
+            rear_left: clamp(self.rear_left),
+            rear_right: clamp(self.rear_right),
+        }
+    }
+
+    /// Largest wheel speed magnitude in RPM
+    pub fn max_abs(&self) -> f32 {
+        [self.front_left, self.front_right, self.rear_left, self.rear_right]
+            .iter()
+            .fold(0.0, |max, rpm| max.max(rpm.abs()))
+    }
+}
+
+/// Which parts of a twist command the chassis obeys
+///
+/// Disabled axes are ignored by the robot, so a yaw-only twist rotates in
+/// place regardless of `vx`/`vy`. The flags are byte 22 of the twist
+/// command; only [`TRANSLATION_FLAG`](Self::TRANSLATION_FLAG) and
+/// [`YAW_FLAG`](Self::YAW_FLAG) have an observed effect, and the other bits
+/// are sent as zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TwistEnable {
+    /// Obey `vx` and `vy`
+    pub translation: bool,
+    /// Obey `vz`
+    pub yaw: bool,
+}
+
+impl TwistEnable {
+    /// Enable-flag bit for translation
+    pub const TRANSLATION_FLAG: u8 = 0x04;
+    /// Enable-flag bit for yaw
+    pub const YAW_FLAG: u8 = 0x08;
+
+    /// Encode as the twist command's enable-flag byte
+    pub fn flag(&self) -> u8 {
+        let mut flag = 0;
+        if self.translation {
+            flag |= Self::TRANSLATION_FLAG;
+        }
+        if self.yaw {
+            flag |= Self::YAW_FLAG;
+        }
+        flag
+    }
+}
+
+impl Default for TwistEnable {
+    /// Both translation and yaw enabled
+    fn default() -> Self {
+        Self { translation: true, yaw: true }
+    }
+}
+
+/// How chassis rotation and the gimbal yaw are coupled
+///
+/// The S1 app selects its follow modes with a separate robot mode command
+/// that has not been captured, so these modes are built from the twist
+/// enable flags and the gimbal command sent with every movement.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ChassisMode {
+    /// The chassis turns with `vz` and the gimbal yaw turns along with it
+    #[default]
+    ChassisLead,
+    /// `vz` turns only the gimbal while the chassis keeps its heading
+    GimbalLead,
+    /// The chassis turns with `vz` and the gimbal is left independent
+    Free,
+}
+
+impl ChassisMode {
+    /// Get the twist axes the chassis obeys in this mode
+    pub fn twist_enable(self) -> TwistEnable {
+        TwistEnable {
+            translation: true,
+            yaw: self != Self::GimbalLead,
+        }
+    }
+
+    /// Check whether movements turn the gimbal yaw with `vz` in this mode
+    pub fn gimbal_follows(self) -> bool {
+        self != Self::Free
+    }
+
+    /// Find the mode matching a twist enable setting and gimbal coupling
+    pub fn from_parts(twist_enable: TwistEnable, gimbal_follows: bool) -> Option<Self> {
+        [Self::ChassisLead, Self::GimbalLead, Self::Free]
+            .into_iter()
+            .find(|mode| mode.twist_enable() == twist_enable && mode.gimbal_follows() == gimbal_follows)
+    }
+}
+
 /// Gimbal command parameters
 #[derive(Debug, Clone, Copy)]
 pub struct GimbalParams {
@@ -22,34 +161,308 @@ pub struct GimbalParams {
     pub rz: f32,  // Rotation around Z axis (yaw)
 }
 
+impl GimbalParams {
+    /// Gimbal command with no pitch or yaw rotation
+    pub const fn neutral() -> Self {
+        Self { ry: 0.0, rz: 0.0 }
+    }
+}
+
 /// LED color parameters
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct LedColor {
     pub red: u8,
     pub green: u8,
     pub blue: u8,
 }
 
+impl LedColor {
+    /// Look up a named color (`red`, `green`, `blue`, `yellow`, `white`, `off`)
+    pub fn from_name(name: &str) -> Option<Self> {
+        let (red, green, blue) = match name.to_ascii_lowercase().as_str() {
+            "red" => (255, 0, 0),
+            "green" => (0, 255, 0),
+            "blue" => (0, 0, 255),
+            "yellow" => (255, 255, 0),
+            "white" => (255, 255, 255),
+            "off" => (0, 0, 0),
+            _ => return None,
+        };
+        Some(Self { red, green, blue })
+    }
+
+    /// Scale all channels by a brightness level (0.0 to 1.0)
+    ///
+    /// A `NaN` level counts as full brightness and leaves the color unchanged.
+    pub fn with_brightness(self, level: f32) -> Self {
+        let level = if level.is_nan() { 1.0 } else { level.clamp(0.0, 1.0) };
+        let scale = |channel: u8| (channel as f32 * level).round() as u8;
+        Self {
+            red: scale(self.red),
+            green: scale(self.green),
+            blue: scale(self.blue),
+        }
+    }
+
+    /// Blend toward `to`, where `t` is 0.0 for `self` and 1.0 for `to`
+    ///
+    /// Channels are mixed in linear light using [`LED_GAMMA`], so the
+    /// midpoint of a fade looks as bright as its ends.
+    pub fn interpolate(self, to: Self, t: f32) -> Self {
+        let t = if t.is_nan() { 0.0 } else { t.clamp(0.0, 1.0) };
+        let mix = |from: u8, to: u8| {
+            let from = (from as f32 / 255.0).powf(LED_GAMMA);
+            let to = (to as f32 / 255.0).powf(LED_GAMMA);
+            ((from + (to - from) * t).powf(LED_GAMMA.recip()) * 255.0).round() as u8
+        };
+        Self {
+            red: mix(self.red, to.red),
+            green: mix(self.green, to.green),
+            blue: mix(self.blue, to.blue),
+        }
+    }
+}
+
+/// Gamma used to convert LED channel values to linear light
+pub const LED_GAMMA: f32 = 2.2;
+
+/// Offset of the red channel in an LED color command
+pub const LED_RED_OFFSET: usize = 14;
+/// Offset of the green channel in an LED color command
+pub const LED_GREEN_OFFSET: usize = 15;
+/// Offset of the blue channel in an LED color command
+pub const LED_BLUE_OFFSET: usize = 16;
+/// Offset of the effect mode in an LED color command
+pub const LED_EFFECT_OFFSET: usize = 11;
+/// Offset of the little-endian on period (ms) in an LED color command
+pub const LED_ON_PERIOD_OFFSET: usize = 18;
+/// Offset of the little-endian off period (ms) in an LED color command
+pub const LED_OFF_PERIOD_OFFSET: usize = 20;
+/// Offset of the last byte of the off period in an LED color command
+const LED_OFF_PERIOD_END: usize = LED_OFF_PERIOD_OFFSET + 1;
+
+/// Offset of the segment mask in an LED color command
+pub const LED_ZONE_MASK_OFFSET: usize = 22;
+
+/// LED segments that an LED command can address
+///
+/// Each chassis armor plate and each side of the gimbal is one segment;
+/// the grouped zones address several at once.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LedZone {
+    /// Rear chassis armor
+    Back,
+    /// Front chassis armor
+    Front,
+    /// Left chassis armor
+    Left,
+    /// Right chassis armor
+    Right,
+    /// Left side of the gimbal
+    GimbalLeft,
+    /// Right side of the gimbal
+    GimbalRight,
+    /// All four chassis segments
+    Chassis,
+    /// Both gimbal segments
+    Gimbal,
+    /// Every segment
+    #[default]
+    All,
+}
+
+/// Number of individually addressable LED segments
+pub const LED_SEGMENT_COUNT: usize = 6;
+
+impl LedZone {
+    /// Every single segment, in mask bit order
+    pub const SEGMENTS: [LedZone; LED_SEGMENT_COUNT] = [
+        Self::Back,
+        Self::Front,
+        Self::Left,
+        Self::Right,
+        Self::GimbalLeft,
+        Self::GimbalRight,
+    ];
+
+    /// Segment mask byte of the LED command
+    pub fn mask(&self) -> u8 {
+        match self {
+            Self::Back => 0x01,
+            Self::Front => 0x02,
+            Self::Left => 0x04,
+            Self::Right => 0x08,
+            Self::GimbalLeft => 0x10,
+            Self::GimbalRight => 0x20,
+            Self::Chassis => 0x0F,
+            Self::Gimbal => 0x30,
+            Self::All => 0x3F,
+        }
+    }
+}
+
+/// Effect played by the LEDs in the commanded color
+///
+/// The firmware runs the effect itself, so no further commands are needed
+/// to keep it going.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LedEffect {
+    /// Steady color
+    #[default]
+    Solid,
+    /// Fade in and back out
+    Breathe {
+        /// Fade-in time in milliseconds
+        on_ms: u16,
+        /// Fade-out time in milliseconds
+        off_ms: u16,
+    },
+    /// Blink on and off
+    Flash {
+        /// Time lit in milliseconds
+        on_ms: u16,
+        /// Time dark in milliseconds
+        off_ms: u16,
+    },
+    /// Color runs around the LED ring
+    Marquee,
+}
+
+impl LedEffect {
+    /// Effect mode byte of the LED command
+    ///
+    /// `None` for [`Solid`](Self::Solid), which keeps the captured template's
+    /// mode.
+    pub fn mode(&self) -> Option<u8> {
+        match self {
+            Self::Solid => None,
+            Self::Breathe { .. } => Some(0x02),
+            Self::Flash { .. } => Some(0x03),
+            Self::Marquee => Some(0x04),
+        }
+    }
+
+    /// On and off periods in milliseconds, zero for effects without timing
+    ///
+    /// `None` for [`Solid`](Self::Solid), which keeps the captured template's
+    /// periods.
+    pub fn periods(&self) -> Option<(u16, u16)> {
+        match *self {
+            Self::Breathe { on_ms, off_ms } | Self::Flash { on_ms, off_ms } => Some((on_ms, off_ms)),
+            Self::Marquee => Some((0, 0)),
+            Self::Solid => None,
+        }
+    }
+}
+
+/// Protocol units per unit of gimbal rate
+const GIMBAL_RATE_SCALE: f32 = 1024.0;
+
+/// Largest gimbal rate magnitude that can be encoded without clamping
+pub const MAX_GIMBAL_RATE: f32 = i16::MAX as f32 / GIMBAL_RATE_SCALE;
+
+/// Offset of the control mode byte in a gimbal command
+pub const GIMBAL_MODE_OFFSET: usize = 12;
+/// Gimbal control mode for angular rates (the template default)
+pub const GIMBAL_RATE_MODE: u8 = 0x05;
+/// Gimbal control mode for absolute pitch/yaw angles
+///
+/// Not taken from a captured template and not confirmed against a real S1.
+pub const GIMBAL_ANGLE_MODE: u8 = 0x01;
+
+/// Lowest gimbal pitch in degrees
+pub const MIN_GIMBAL_PITCH: f32 = -20.0;
+/// Highest gimbal pitch in degrees
+pub const MAX_GIMBAL_PITCH: f32 = 35.0;
+/// Largest gimbal yaw magnitude in degrees
+pub const MAX_GIMBAL_YAW: f32 = 250.0;
+
+/// Fastest wheel speed the S1 chassis accepts, in RPM
+pub const MAX_WHEEL_RPM: f32 = 1000.0;
+/// Protocol units per wheel RPM: speeds are sent as whole RPM
+///
+/// Not confirmed against a capture from a real S1.
+pub const WHEEL_RPM_SCALE: f32 = 1.0;
+/// Command set and ID of the chassis wheel speed command
+///
+/// Not taken from a captured template and not confirmed against a real S1;
+/// neither is the payload layout of [`CommandBuilder::build_wheel_command`].
+pub const WHEEL_SPEED_CMD: (u8, u8) = (0x3F, 0x26);
+/// Command set and ID of the serial number query and its response
+///
+/// Taken from DJI's published RoboMaster SDK protocol; the S1 is not
+/// confirmed to answer it over its internal CAN bus.
+pub const SERIAL_NUMBER_CMD: (u8, u8) = (0x00, 0x4F);
+/// Serial number type requested by the query: the robot's own serial
+const SERIAL_NUMBER_TYPE: u8 = 0x01;
+
+/// Offset of the fire byte in a blaster command
+pub const BLASTER_FIRE_OFFSET: usize = 11;
+/// Most shots a single blaster command may request
+pub const MAX_BLASTER_BURST: u8 = 8;
+
+/// Check that a blaster burst of `count` shots is one the hardware accepts
+pub fn check_blaster_burst(count: u8) -> Result<(), RoboMasterError> {
+    if count == 0 || count > MAX_BLASTER_BURST {
+        return Err(RoboMasterError::InvalidParameter {
+            parameter: "blaster_count".to_string(),
+            value: format!("{} (valid range: 1 to {})", count, MAX_BLASTER_BURST),
+        });
+    }
+    Ok(())
+}
+
 /// Command builder for creating protocol messages
+#[derive(Debug, Clone)]
 pub struct CommandBuilder {
-    command_table: Vec<Vec<u8>>,
+    command_table: Arc<[CommandTemplate]>,
+    crc16_init: u16,
+    twist_enable: TwistEnable,
 }
 
 impl CommandBuilder {
     /// Create a new command builder
+    ///
+    /// All builders share one validated [`shared_command_table`].
     pub fn new() -> Self {
+        Self::with_crc16_init(crate::crc::crc16::CRC16_INIT)
+    }
+
+    /// Create a command builder using a firmware-specific CRC16 init value
+    pub fn with_crc16_init(crc16_init: u16) -> Self {
         Self {
-            command_table: get_command_table(),
+            command_table: shared_command_table(),
+            crc16_init,
+            twist_enable: TwistEnable::default(),
         }
     }
 
+    /// Set the CRC16 init value used for every command
+    pub fn set_crc16_init(&mut self, crc16_init: u16) {
+        self.crc16_init = crc16_init;
+    }
+
+    /// Get the CRC16 init value used for every command
+    pub fn crc16_init(&self) -> u16 {
+        self.crc16_init
+    }
+
+    /// Set which twist axes the chassis obeys in later twist commands
+    pub fn set_twist_enable(&mut self, twist_enable: TwistEnable) {
+        self.twist_enable = twist_enable;
+    }
+
+    /// Get which twist axes the chassis obeys
+    pub fn twist_enable(&self) -> TwistEnable {
+        self.twist_enable
+    }
+
     /// Build boot sequence commands
     pub fn build_boot_sequence(&self) -> Result<Vec<u8>, RoboMasterError> {
         let mut boot_commands = Vec::new();
         
-        // Build boot commands (26-34)
-        for command_no in 26..=34 {
-            let cmd = self.build_command_from_template(command_no, &CommandCounters::default())?;
+        for id in BOOT_SEQUENCE {
+            let cmd = self.build_command_from_template(id, &CommandCounters::default())?;
             boot_commands.extend(cmd);
         }
         
@@ -62,16 +475,67 @@ impl CommandBuilder {
 
     /// Build LED on command
     pub fn build_led_on_command(&self, counters: &CommandCounters) -> Result<Vec<u8>, RoboMasterError> {
-        self.build_command_with_counter(commands::LED_ON, counters.led)
+        self.build_command_with_counter(CommandId::LedOn, counters.led)
     }
 
     /// Build LED color command
     pub fn build_led_command(&self, color: LedColor, counters: &CommandCounters) -> Result<Vec<u8>, RoboMasterError> {
-        let command_no = commands::LED_COLOR;
+        self.build_led_effect_command(color, LedEffect::Solid, counters)
+    }
+
+    /// Build an LED command that plays `effect` in `color`
+    ///
+    /// The segment mask is left as in the captured template; use
+    /// [`build_zone_led_command`](Self::build_zone_led_command) to address
+    /// specific segments.
+    pub fn build_led_effect_command(&self, color: LedColor, effect: LedEffect, counters: &CommandCounters) -> Result<Vec<u8>, RoboMasterError> {
+        self.build_led_frame(None, color, effect, counters)
+    }
+
+    /// Build an LED color command that only changes the segments in `zone`
+    pub fn build_zone_led_command(&self, zone: LedZone, color: LedColor, counters: &CommandCounters) -> Result<Vec<u8>, RoboMasterError> {
+        self.build_led_frame(Some(zone.mask()), color, LedEffect::Solid, counters)
+    }
+
+    /// Build the LED commands giving each segment its own color
+    ///
+    /// `colors` is in [`LedZone::SEGMENTS`] order. The LED template has a
+    /// single RGB field, so there is no one-command encoding for mixed
+    /// colors: segments sharing a color are combined into one command and
+    /// the commands take consecutive LED counters starting at
+    /// `counters.led`. When every segment has the same color this is a
+    /// single command covering every segment.
+    pub fn build_segment_led_commands(&self, colors: [LedColor; LED_SEGMENT_COUNT], counters: &CommandCounters) -> Result<Vec<Vec<u8>>, RoboMasterError> {
+        let mut groups: Vec<(LedColor, u8)> = Vec::new();
+        for (zone, color) in LedZone::SEGMENTS.iter().zip(colors) {
+            match groups.iter_mut().find(|(group_color, _)| *group_color == color) {
+                Some((_, mask)) => *mask |= zone.mask(),
+                None => groups.push((color, zone.mask())),
+            }
+        }
+
+        groups
+            .into_iter()
+            .enumerate()
+            .map(|(index, (color, mask))| {
+                let counters = CommandCounters {
+                    led: counters.led.wrapping_add(index as u16),
+                    ..counters.clone()
+                };
+                self.build_led_frame(Some(mask), color, LedEffect::Solid, &counters)
+            })
+            .collect()
+    }
+
+    /// Fill the LED color template for a segment mask, color and effect
+    ///
+    /// A `mask` of `None` keeps the template's segment mask.
+    fn build_led_frame(&self, mask: Option<u8>, color: LedColor, effect: LedEffect, counters: &CommandCounters) -> Result<Vec<u8>, RoboMasterError> {
+        let command_no = CommandId::LedColor;
         let template = self.get_command_template(command_no)?;
         let command_length = get_command_length(template)
             .ok_or_else(|| RoboMasterError::Protocol(ProtocolError::InvalidCommandLength {
-                command_id: command_no,
+                command_id: command_no.index(),
             }))?;
 
         let mut header_command = Vec::new();
@@ -86,39 +550,62 @@ impl CommandBuilder {
                 } else if i == 7 {
                     header_command.push(((counters.led >> 8) & 0xFF) as u8);
                 }
-            } else if i == 14 {
-                // RED color
+            } else if i == LED_RED_OFFSET {
                 header_command.push(color.red);
-            } else if i == 15 {
-                // GREEN color
+            } else if i == LED_GREEN_OFFSET {
                 header_command.push(color.green);
-            } else if i == 16 {
-                // BLUE color
+            } else if i == LED_BLUE_OFFSET {
                 header_command.push(color.blue);
+            } else if let (LED_EFFECT_OFFSET, Some(mode)) = (i, effect.mode()) {
+                header_command.push(mode);
+            } else if let (LED_ON_PERIOD_OFFSET..=LED_OFF_PERIOD_END, Some((on_ms, off_ms))) = (i, effect.periods()) {
+                let periods = [on_ms.to_le_bytes(), off_ms.to_le_bytes()].concat();
+                header_command.push(periods[i - LED_ON_PERIOD_OFFSET]);
+            } else if i == LED_ZONE_MASK_OFFSET {
+                header_command.push(mask.unwrap_or(template[i]));
             } else {
                 header_command.push(template[i]);
             }
         }
         
-        append_crc16_checksum(&mut header_command, crate::crc::crc16::CRC16_INIT);
+        append_crc16_checksum(&mut header_command, self.crc16_init);
         Ok(header_command)
     }
 
+    /// Decode the color from a built LED color command
+    ///
+    /// Returns `None` if the frame is too short or either checksum does not
+    /// match, so a successful decode also proves the CRCs cover the color.
+    pub fn decode_led_command(&self, frame: &[u8]) -> Option<LedColor> {
+        if frame.len() <= LED_BLUE_OFFSET + 2
+            || !verify_crc8_checksum(&frame[..4])
+            || !verify_crc16_checksum(frame, self.crc16_init)
+        {
+            return None;
+        }
+
+        Some(LedColor {
+            red: frame[LED_RED_OFFSET],
+            green: frame[LED_GREEN_OFFSET],
+            blue: frame[LED_BLUE_OFFSET],
+        })
+    }
+
     /// Build twist (movement) command
     pub fn build_twist_command(&self, params: MovementParams, counters: &CommandCounters) -> Result<Vec<u8>, RoboMasterError> {
-        let command_no = commands::TWIST;
+        let command_no = CommandId::Twist;
         let template = self.get_command_template(command_no)?;
         let command_length = get_command_length(template)
             .ok_or_else(|| RoboMasterError::Protocol(ProtocolError::InvalidCommandLength {
-                command_id: command_no,
+                command_id: command_no.index(),
             }))?;
 
         let mut header_command = Vec::new();
 
         // Convert movement parameters to protocol values
-        let linear_x = ((256.0 * params.vx + 1024.0) as i32).clamp(0, 2047) as u16;
-        let linear_y = ((256.0 * params.vy + 1024.0) as i32).clamp(0, 2047) as u16;
-        let angular_z = ((256.0 * params.vz + 1024.0) as i32).clamp(0, 2047) as u16;
+        let linear_x = proto::encode_linear(params.vx);
+        let linear_y = proto::encode_linear(params.vy);
+        let angular_z = proto::encode_angular(params.vz);
 
         // Build command excluding CRC16 (last 2 bytes)
         for i in 0..(command_length - 2) {
@@ -153,7 +640,7 @@ impl CommandBuilder {
             } else if i == 21 {
                 header_command.push(0x04);
             } else if i == 22 {
-                header_command.push(0x0C); // Enable Flag 4:x-y 8:yaw 0x0c
+                header_command.push(self.twist_enable.flag()); // Enable Flag 4:x-y 8:yaw 0x0c
             } else if i == 23 {
                 header_command.push(0x00);
             } else if i == 24 {
@@ -163,24 +650,139 @@ impl CommandBuilder {
             }
         }
 
-        append_crc16_checksum(&mut header_command, crate::crc::crc16::CRC16_INIT);
+        append_crc16_checksum(&mut header_command, self.crc16_init);
         Ok(header_command)
     }
 
-    /// Build gimbal command
-    pub fn build_gimbal_command(&self, params: GimbalParams, counters: &CommandCounters) -> Result<Vec<u8>, RoboMasterError> {
-        let command_no = commands::GIMBAL;
+    /// Build a chassis command setting each wheel's speed directly
+    ///
+    /// Each wheel is clamped to ±[`MAX_WHEEL_RPM`] and sent as a little-endian
+    /// `i16` of `rpm * WHEEL_RPM_SCALE`, in the order front-left, front-right,
+    /// rear-left, rear-right. Uses the same sequence counter as twist commands.
+    ///
+    /// Not confirmed against a capture from a real S1; see [`WHEEL_SPEED_CMD`].
+    pub fn build_wheel_command(&self, speeds: WheelSpeeds, counters: &CommandCounters) -> Result<Vec<u8>, RoboMasterError> {
+        let speeds = speeds.clamped();
+        let (cmd_set, cmd_id) = WHEEL_SPEED_CMD;
+
+        let mut header_command = vec![0x55, 0x15, 0x04];
+        append_crc8_checksum(&mut header_command);
+        header_command.extend([
+            0x09, 0xC3,
+            (counters.joy & 0xFF) as u8,
+            ((counters.joy >> 8) & 0xFF) as u8,
+            0x00, cmd_set, cmd_id,
+        ]);
+        for rpm in [speeds.front_left, speeds.front_right, speeds.rear_left, speeds.rear_right] {
+            header_command.extend(((rpm * WHEEL_RPM_SCALE).round() as i16).to_le_bytes());
+        }
+
+        append_crc16_checksum(&mut header_command, self.crc16_init);
+        Ok(header_command)
+    }
+
+    /// Build a query asking the robot for its serial number
+    ///
+    /// The attribute byte requests an answer, which arrives as a separate
+    /// message with the same command set and ID (see [`SERIAL_NUMBER_CMD`]). Uses the same sequence counter as
+    /// twist commands.
+    pub fn build_serial_number_query(&self, counters: &CommandCounters) -> Result<Vec<u8>, RoboMasterError> {
+        let (cmd_set, cmd_id) = SERIAL_NUMBER_CMD;
+
+        let mut header_command = vec![0x55, 0x0E, 0x04];
+        append_crc8_checksum(&mut header_command);
+        header_command.extend([
+            0x09, 0xC3,
+            (counters.joy & 0xFF) as u8,
+            ((counters.joy >> 8) & 0xFF) as u8,
+            0x40, cmd_set, cmd_id, SERIAL_NUMBER_TYPE,
+        ]);
+
+        append_crc16_checksum(&mut header_command, self.crc16_init);
+        Ok(header_command)
+    }
+
+    /// Build a blaster command firing `count` shots
+    ///
+    /// The low nibble of the fire byte carries the shot count; the high
+    /// nibble keeps the template's blaster type. Fails if `count` is zero or
+    /// above [`MAX_BLASTER_BURST`].
+    pub fn build_blaster_command(&self, count: u8, counters: &CommandCounters) -> Result<Vec<u8>, RoboMasterError> {
+        check_blaster_burst(count)?;
+
+        let command_no = CommandId::BlasterFire;
         let template = self.get_command_template(command_no)?;
         let command_length = get_command_length(template)
             .ok_or_else(|| RoboMasterError::Protocol(ProtocolError::InvalidCommandLength {
-                command_id: command_no,
+                command_id: command_no.index(),
             }))?;
 
         let mut header_command = Vec::new();
 
+        // Build command excluding CRC16 (last 2 bytes)
+        for i in 0..(command_length - 2) {
+            if is_crc8_position(template, i) {
+                append_crc8_checksum(&mut header_command);
+            } else if is_counter_position(template, i) {
+                if i == 6 {
+                    header_command.push((counters.joy & 0xFF) as u8);
+                } else if i == 7 {
+                    header_command.push(((counters.joy >> 8) & 0xFF) as u8);
+                }
+            } else if i == BLASTER_FIRE_OFFSET {
+                header_command.push((template[i] & 0xF0) | count);
+            } else {
+                header_command.push(template[i]);
+            }
+        }
+
+        append_crc16_checksum(&mut header_command, self.crc16_init);
+        Ok(header_command)
+    }
+
+    /// Build gimbal command
+    ///
+    /// Rates beyond [`MAX_GIMBAL_RATE`] in either direction are clamped
+    /// rather than wrapped.
+    pub fn build_gimbal_command(&self, params: GimbalParams, counters: &CommandCounters) -> Result<Vec<u8>, RoboMasterError> {
         // Convert gimbal parameters to protocol values
-        let angular_y = (-1024.0 * params.ry) as i16;
-        let angular_z = (-1024.0 * params.rz) as i16;
+        let angular_y = Self::encode_gimbal_rate(params.ry);
+        let angular_z = Self::encode_gimbal_rate(params.rz);
+
+        self.build_gimbal_frame(GIMBAL_RATE_MODE, angular_y, angular_z, counters)
+    }
+
+    /// Build a gimbal command that points the gimbal at an absolute angle
+    ///
+    /// Angles are in degrees and are clamped to [`MIN_GIMBAL_PITCH`] to
+    /// [`MAX_GIMBAL_PITCH`] and ±[`MAX_GIMBAL_YAW`]. The gimbal holds the
+    /// angle until the next gimbal command.
+    ///
+    /// Not confirmed against a capture from a real S1: the mode byte
+    /// ([`GIMBAL_ANGLE_MODE`]) and the sign convention are guesses. Angles
+    /// are sent as `+angle * 10`, while rates are sent negated, so one of
+    /// the two may turn out to need its sign flipped.
+    pub fn build_gimbal_position_command(&self, pitch_deg: f32, yaw_deg: f32, counters: &CommandCounters) -> Result<Vec<u8>, RoboMasterError> {
+        let pitch = pitch_deg.clamp(MIN_GIMBAL_PITCH, MAX_GIMBAL_PITCH);
+        let yaw = yaw_deg.clamp(-MAX_GIMBAL_YAW, MAX_GIMBAL_YAW);
+
+        // Angles are sent in tenths of a degree
+        let angle_y = (pitch * 10.0).round() as i16;
+        let angle_z = (yaw * 10.0).round() as i16;
+
+        self.build_gimbal_frame(GIMBAL_ANGLE_MODE, angle_y, angle_z, counters)
+    }
+
+    /// Fill the gimbal template with a control mode and pitch/yaw values
+    fn build_gimbal_frame(&self, mode: u8, value_y: i16, value_z: i16, counters: &CommandCounters) -> Result<Vec<u8>, RoboMasterError> {
+        let command_no = CommandId::Gimbal;
+        let template = self.get_command_template(command_no)?;
+        let command_length = get_command_length(template)
+            .ok_or_else(|| RoboMasterError::Protocol(ProtocolError::InvalidCommandLength {
+                command_id: command_no.index(),
+            }))?;
+
+        let mut header_command = Vec::new();
 
         // Build command excluding CRC16 (last 2 bytes)
         for i in 0..(command_length - 2) {
@@ -192,23 +794,71 @@ impl CommandBuilder {
                 } else if i == 7 {
                     header_command.push(((counters.gimbal >> 8) & 0xFF) as u8);
                 }
+            } else if i == GIMBAL_MODE_OFFSET {
+                header_command.push(mode);
             } else if i == 14 {
-                header_command.push(((angular_y >> 8) & 0xFF) as u8);
+                header_command.push(((value_y >> 8) & 0xFF) as u8);
             } else if i == 13 {
-                header_command.push((angular_y & 0xFF) as u8);
+                header_command.push((value_y & 0xFF) as u8);
             } else if i == 16 {
-                header_command.push(((angular_z >> 8) & 0xFF) as u8);
+                header_command.push(((value_z >> 8) & 0xFF) as u8);
             } else if i == 15 {
-                header_command.push((angular_z & 0xFF) as u8);
+                header_command.push((value_z & 0xFF) as u8);
             } else {
                 header_command.push(template[i]);
             }
         }
 
-        append_crc16_checksum(&mut header_command, crate::crc::crc16::CRC16_INIT);
+        append_crc16_checksum(&mut header_command, self.crc16_init);
         Ok(header_command)
     }
 
+    /// Dump the command table as a human-readable listing
+    ///
+    /// Each template is printed on one line with its index, optional name
+    /// and length. Placeholder bytes are shown as `C8` (CRC8), `CT`
+    /// (counter) and `C16` (CRC16) instead of `ff`.
+    pub fn dump_table(&self) -> String {
+        let names: std::collections::HashMap<usize, &str> = create_command_map()
+            .into_iter()
+            .map(|(name, index)| (index, name))
+            .collect();
+
+        let mut dump = String::new();
+        let _ = writeln!(dump, "RoboMaster command table ({} entries)", self.command_table.len());
+        let _ = writeln!(dump, "placeholders: C8 = CRC8, CT = counter, C16 = CRC16");
+
+        for (index, template) in self.command_table.iter().enumerate() {
+            let crc16 = find_crc16_positions(template);
+            let bytes: Vec<String> = template
+                .iter()
+                .enumerate()
+                .map(|(i, byte)| {
+                    if is_crc8_position(template, i) {
+                        "C8".to_string()
+                    } else if is_counter_position(template, i) {
+                        "CT".to_string()
+                    } else if matches!(crc16, Some((low, high)) if i == low || i == high) {
+                        "C16".to_string()
+                    } else {
+                        format!("{:02x}", byte)
+                    }
+                })
+                .collect();
+
+            let length = get_command_length(template).unwrap_or(template.len());
+            let name = names.get(&index).copied().unwrap_or("-");
+            let _ = writeln!(dump, "[{:2}] {:<10} len={:2}  {}", index, name, length, bytes.join(" "));
+        }
+
+        dump
+    }
+
+    /// Convert a gimbal rate to its protocol value, clamped to the `i16` range
+    fn encode_gimbal_rate(rate: f32) -> i16 {
+        (-GIMBAL_RATE_SCALE * rate).clamp(i16::MIN as f32, i16::MAX as f32) as i16
+    }
+
     /// Build touch command
     pub fn build_touch_command(&self, counters: &CommandCounters) -> Result<Vec<Vec<u8>>, RoboMasterError> {
         let touch_msg_list = vec![
@@ -220,12 +870,13 @@ impl CommandBuilder {
             vec![0x40, 0x04, 0x4c, 0x00, 0x00],
         ];
 
-        // Calculate CRC16 for combined message
-        let mut combined_msg = touch_msg_list[0].clone();
-        combined_msg.extend(&touch_msg_list[1]);
-        
-        let crc16 = crate::crc::crc16::get_crc16_checksum(&combined_msg, crate::crc::crc16::CRC16_INIT);
-        
+        // CRC16 covers both frames as one message
+        let mut crc = Crc16::new(self.crc16_init);
+        for msg in &touch_msg_list {
+            crc.update(msg);
+        }
+        let crc16 = crc.finalize();
+
         let mut result = touch_msg_list;
         result[1].push((crc16 & 0xFF) as u8);
         result[1].push(((crc16 >> 8) & 0xFF) as u8);
@@ -234,11 +885,11 @@ impl CommandBuilder {
     }
 
     /// Generic command builder from template
-    fn build_command_from_template(&self, command_no: usize, _counters: &CommandCounters) -> Result<Vec<u8>, RoboMasterError> {
+    fn build_command_from_template(&self, command_no: CommandId, _counters: &CommandCounters) -> Result<Vec<u8>, RoboMasterError> {
         let template = self.get_command_template(command_no)?;
         let command_length = get_command_length(template)
             .ok_or_else(|| RoboMasterError::Protocol(ProtocolError::InvalidCommandLength {
-                command_id: command_no,
+                command_id: command_no.index(),
             }))?;
 
         let mut header_command = Vec::new();
@@ -252,16 +903,16 @@ impl CommandBuilder {
             }
         }
 
-        append_crc16_checksum(&mut header_command, crate::crc::crc16::CRC16_INIT);
+        append_crc16_checksum(&mut header_command, self.crc16_init);
         Ok(header_command)
     }
 
     /// Generic command builder with counter
-    fn build_command_with_counter(&self, command_no: usize, counter: u16) -> Result<Vec<u8>, RoboMasterError> {
+    fn build_command_with_counter(&self, command_no: CommandId, counter: u16) -> Result<Vec<u8>, RoboMasterError> {
         let template = self.get_command_template(command_no)?;
         let command_length = get_command_length(template)
             .ok_or_else(|| RoboMasterError::Protocol(ProtocolError::InvalidCommandLength {
-                command_id: command_no,
+                command_id: command_no.index(),
             }))?;
 
         let mut header_command = Vec::new();
@@ -281,15 +932,15 @@ impl CommandBuilder {
             }
         }
 
-        append_crc16_checksum(&mut header_command, crate::crc::crc16::CRC16_INIT);
+        append_crc16_checksum(&mut header_command, self.crc16_init);
         Ok(header_command)
     }
 
-    /// Get command template by index
-    fn get_command_template(&self, command_no: usize) -> Result<&Vec<u8>, RoboMasterError> {
-        self.command_table.get(command_no)
+    /// Get the template of a named command
+    fn get_command_template(&self, id: CommandId) -> Result<&Vec<u8>, RoboMasterError> {
+        self.command_table.get(id.index())
             .ok_or_else(|| RoboMasterError::Protocol(ProtocolError::CommandNotFound {
-                command_id: command_no,
+                command_id: id.index(),
             }))
     }
 }
@@ -310,6 +961,20 @@ mod tests {
         assert_eq!(builder.command_table.len(), 38);
     }
 
+    #[test]
+    fn test_builders_share_command_table() {
+        let first = CommandBuilder::new();
+        let second = CommandBuilder::with_crc16_init(0x1234);
+        assert!(Arc::ptr_eq(&first.command_table, &second.command_table));
+
+        let counters = CommandCounters::default();
+        for builder in [&first, &second] {
+            let command = builder.build_led_command(LedColor::default(), &counters).unwrap();
+            assert_eq!(command.len(), command[1] as usize);
+            assert!(verify_crc16_checksum(&command, builder.crc16_init()));
+        }
+    }
+
     #[test]
     fn test_led_color_command() {
         let builder = CommandBuilder::new();
@@ -366,6 +1031,232 @@ mod tests {
         assert_eq!(cmd[0], 0x55); // Header
     }
 
+    #[test]
+    fn test_gimbal_large_rate_is_clamped() {
+        let builder = CommandBuilder::new();
+        let counters = CommandCounters::default();
+        let params = GimbalParams { ry: 100.0, rz: -100.0 };
+
+        let cmd = builder.build_gimbal_command(params, &counters).unwrap();
+        let angular_y = i16::from_le_bytes([cmd[13], cmd[14]]);
+        let angular_z = i16::from_le_bytes([cmd[15], cmd[16]]);
+
+        assert_eq!(angular_y, i16::MIN);
+        assert_eq!(angular_z, i16::MAX);
+    }
+
+    #[test]
+    fn test_gimbal_position_command() {
+        let builder = CommandBuilder::new();
+        let counters = CommandCounters::default();
+
+        let cmd = builder.build_gimbal_position_command(12.5, -90.0, &counters).unwrap();
+        assert_eq!(cmd.len(), builder.build_gimbal_command(GimbalParams::neutral(), &counters).unwrap().len());
+        assert_eq!(cmd[GIMBAL_MODE_OFFSET], GIMBAL_ANGLE_MODE);
+        assert_eq!(i16::from_le_bytes([cmd[13], cmd[14]]), 125);
+        assert_eq!(i16::from_le_bytes([cmd[15], cmd[16]]), -900);
+        assert!(verify_crc16_checksum(&cmd, builder.crc16_init()));
+
+        let rate = builder.build_gimbal_command(GimbalParams { ry: 0.1, rz: 0.0 }, &counters).unwrap();
+        assert_eq!(rate[GIMBAL_MODE_OFFSET], GIMBAL_RATE_MODE);
+    }
+
+    #[test]
+    fn test_gimbal_position_is_clamped() {
+        let builder = CommandBuilder::new();
+        let counters = CommandCounters::default();
+
+        let cmd = builder.build_gimbal_position_command(90.0, -400.0, &counters).unwrap();
+        assert_eq!(i16::from_le_bytes([cmd[13], cmd[14]]), 350);
+        assert_eq!(i16::from_le_bytes([cmd[15], cmd[16]]), -2500);
+    }
+
+    #[test]
+    fn test_wheel_command_encoding() {
+        let builder = CommandBuilder::new();
+        let counters = CommandCounters { joy: 0x0102, ..CommandCounters::default() };
+        let speeds = WheelSpeeds { front_left: 100.0, front_right: -250.0, rear_left: 1500.0, rear_right: 0.4 };
+
+        let cmd = builder.build_wheel_command(speeds, &counters).unwrap();
+        assert_eq!(cmd.len(), cmd[1] as usize);
+        assert_eq!((cmd[6], cmd[7]), (0x02, 0x01));
+        assert_eq!((cmd[9], cmd[10]), WHEEL_SPEED_CMD);
+
+        let wheels: Vec<i16> = cmd[11..19]
+            .chunks(2)
+            .map(|pair| i16::from_le_bytes([pair[0], pair[1]]))
+            .collect();
+        assert_eq!(wheels, vec![100, -250, MAX_WHEEL_RPM as i16, 0]);
+        assert!(verify_crc8_checksum(&cmd[..4]));
+        assert!(verify_crc16_checksum(&cmd, builder.crc16_init()));
+    }
+
+    #[test]
+    fn test_serial_number_query_framing() {
+        let builder = CommandBuilder::new();
+        let counters = CommandCounters { joy: 0x0304, ..CommandCounters::default() };
+
+        let cmd = builder.build_serial_number_query(&counters).unwrap();
+        assert_eq!(cmd.len(), cmd[1] as usize);
+        assert_eq!((cmd[6], cmd[7]), (0x04, 0x03));
+        assert_eq!((cmd[9], cmd[10]), SERIAL_NUMBER_CMD);
+        assert!(verify_crc8_checksum(&cmd[..4]));
+        assert!(verify_crc16_checksum(&cmd, builder.crc16_init()));
+    }
+
+    #[test]
+    fn test_solid_effect_matches_color_command() {
+        let builder = CommandBuilder::new();
+        let counters = CommandCounters::default();
+        let color = LedColor { red: 10, green: 20, blue: 30 };
+
+        assert_eq!(
+            builder.build_led_effect_command(color, LedEffect::Solid, &counters).unwrap(),
+            builder.build_led_command(color, &counters).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_led_command_matches_template() {
+        let builder = CommandBuilder::new();
+        let counters = CommandCounters { led: 0x0102, ..CommandCounters::default() };
+        let template = &shared_command_table()[CommandId::LedColor.index()];
+
+        // Only the counter, RGB and checksums differ from the captured frame
+        let mut expected = template[..3].to_vec();
+        append_crc8_checksum(&mut expected);
+        expected.extend_from_slice(&template[4..template.len() - 2]);
+        expected[6..8].copy_from_slice(&[0x02, 0x01]);
+        expected[LED_RED_OFFSET..=LED_BLUE_OFFSET].copy_from_slice(&[10, 20, 30]);
+        append_crc16_checksum(&mut expected, builder.crc16_init());
+
+        let color = LedColor { red: 10, green: 20, blue: 30 };
+        assert_eq!(builder.build_led_command(color, &counters).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_led_effect_encoding() {
+        let builder = CommandBuilder::new();
+        let counters = CommandCounters::default();
+        let color = LedColor { red: 255, green: 0, blue: 0 };
+
+        let cmd = builder
+            .build_led_effect_command(color, LedEffect::Flash { on_ms: 300, off_ms: 700 }, &counters)
+            .unwrap();
+        assert_eq!(cmd[LED_EFFECT_OFFSET], 0x03);
+        assert_eq!(u16::from_le_bytes([cmd[LED_ON_PERIOD_OFFSET], cmd[LED_ON_PERIOD_OFFSET + 1]]), 300);
+        assert_eq!(u16::from_le_bytes([cmd[LED_OFF_PERIOD_OFFSET], cmd[LED_OFF_PERIOD_OFFSET + 1]]), 700);
+        assert_eq!(builder.decode_led_command(&cmd), Some(color));
+
+        let breathe = builder
+            .build_led_effect_command(color, LedEffect::Breathe { on_ms: 1000, off_ms: 1000 }, &counters)
+            .unwrap();
+        assert_eq!(breathe[LED_EFFECT_OFFSET], 0x02);
+        assert_eq!(LedEffect::Marquee.periods(), Some((0, 0)));
+    }
+
+    #[test]
+    fn test_zone_led_command_sets_mask() {
+        let builder = CommandBuilder::new();
+        let counters = CommandCounters::default();
+        let color = LedColor { red: 255, green: 160, blue: 0 };
+
+        let left = builder.build_zone_led_command(LedZone::Left, color, &counters).unwrap();
+        assert_eq!(left[LED_ZONE_MASK_OFFSET], 0x04);
+        assert_eq!(builder.decode_led_command(&left), Some(color));
+
+        // The plain color command keeps the template's segment mask
+        let template = &shared_command_table()[CommandId::LedColor.index()];
+        let plain = builder.build_led_command(color, &counters).unwrap();
+        assert_eq!(plain[LED_ZONE_MASK_OFFSET], template[LED_ZONE_MASK_OFFSET]);
+        let all = builder.build_zone_led_command(LedZone::All, color, &counters).unwrap();
+        assert_eq!(all[LED_ZONE_MASK_OFFSET], 0x3F);
+
+        let singles = [LedZone::Back, LedZone::Front, LedZone::Left, LedZone::Right];
+        assert_eq!(singles.iter().fold(0, |mask, zone| mask | zone.mask()), LedZone::Chassis.mask());
+        assert_eq!(LedZone::GimbalLeft.mask() | LedZone::GimbalRight.mask(), LedZone::Gimbal.mask());
+    }
+
+    #[test]
+    fn test_segment_led_commands_group_by_color() {
+        let builder = CommandBuilder::new();
+        let counters = CommandCounters { led: 7, ..CommandCounters::default() };
+        let red = LedColor { red: 255, green: 0, blue: 0 };
+        let blue = LedColor { red: 0, green: 0, blue: 255 };
+
+        let uniform = builder.build_segment_led_commands([red; LED_SEGMENT_COUNT], &counters).unwrap();
+        assert_eq!(uniform, vec![builder.build_zone_led_command(LedZone::All, red, &counters).unwrap()]);
+
+        let commands = builder.build_segment_led_commands([red, blue, red, blue, blue, red], &counters).unwrap();
+        assert_eq!(commands.len(), 2);
+        assert_eq!((commands[0][LED_ZONE_MASK_OFFSET], builder.decode_led_command(&commands[0])), (0x25, Some(red)));
+        assert_eq!((commands[1][LED_ZONE_MASK_OFFSET], builder.decode_led_command(&commands[1])), (0x1A, Some(blue)));
+        assert_eq!((commands[0][6], commands[1][6]), (7, 8));
+    }
+
+    #[test]
+    fn test_blaster_command_encodes_burst() {
+        let builder = CommandBuilder::new();
+        let counters = CommandCounters::default();
+
+        let single = builder.build_blaster_command(1, &counters).unwrap();
+        assert_eq!((single[9], single[10]), (0x3F, 0x51));
+        assert_eq!(single[BLASTER_FIRE_OFFSET], 0x11);
+        assert!(verify_crc16_checksum(&single, builder.crc16_init()));
+
+        let burst = builder.build_blaster_command(MAX_BLASTER_BURST, &counters).unwrap();
+        assert_eq!(burst[BLASTER_FIRE_OFFSET] & 0x0F, MAX_BLASTER_BURST);
+    }
+
+    #[test]
+    fn test_blaster_burst_limit() {
+        let builder = CommandBuilder::new();
+        let counters = CommandCounters::default();
+
+        for count in [0, MAX_BLASTER_BURST + 1] {
+            assert!(matches!(
+                builder.build_blaster_command(count, &counters),
+                Err(RoboMasterError::InvalidParameter { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_twist_enable_flag() {
+        let flag = |translation, yaw| TwistEnable { translation, yaw }.flag();
+        assert_eq!(flag(true, false), 0x04);
+        assert_eq!(flag(false, true), 0x08);
+        assert_eq!(flag(true, true), 0x0C);
+        assert_eq!(flag(false, false), 0x00);
+        assert_eq!(TwistEnable::default().flag(), 0x0C);
+    }
+
+    #[test]
+    fn test_chassis_mode_flags() {
+        assert_eq!(ChassisMode::default().twist_enable().flag(), 0x0C);
+        assert_eq!(ChassisMode::GimbalLead.twist_enable().flag(), TwistEnable::TRANSLATION_FLAG);
+        assert_eq!(ChassisMode::Free.twist_enable().flag(), 0x0C);
+        assert!(!ChassisMode::Free.gimbal_follows());
+
+        for mode in [ChassisMode::ChassisLead, ChassisMode::GimbalLead, ChassisMode::Free] {
+            assert_eq!(ChassisMode::from_parts(mode.twist_enable(), mode.gimbal_follows()), Some(mode));
+        }
+        assert_eq!(ChassisMode::from_parts(TwistEnable { translation: false, yaw: true }, true), None);
+    }
+
+    #[test]
+    fn test_twist_command_carries_enable_flag() {
+        let mut builder = CommandBuilder::new();
+        let counters = CommandCounters::default();
+        let movement = MovementParams { vx: 0.5, vy: 0.0, vz: 0.5 };
+        assert_eq!(builder.build_twist_command(movement, &counters).unwrap()[22], 0x0C);
+
+        builder.set_twist_enable(TwistEnable { translation: false, yaw: true });
+        let yaw_only = builder.build_twist_command(movement, &counters).unwrap();
+        assert_eq!(yaw_only[22], 0x08);
+        assert!(verify_crc16_checksum(&yaw_only, builder.crc16_init()));
+    }
+
     #[test]
     fn test_boot_sequence() {
         let builder = CommandBuilder::new();
@@ -390,10 +1281,130 @@ mod tests {
         assert_eq!(msgs[1][0], 0x40);
     }
 
+    #[test]
+    fn test_led_color_with_brightness() {
+        let white = LedColor { red: 255, green: 255, blue: 255 };
+
+        assert_eq!(white.with_brightness(0.5), LedColor { red: 128, green: 128, blue: 128 });
+        assert_eq!(white.with_brightness(1.0), white);
+        assert_eq!(white.with_brightness(0.0), LedColor::default());
+        assert_eq!(white.with_brightness(2.0), white);
+        assert_eq!(white.with_brightness(f32::NAN), white);
+    }
+
+    #[test]
+    fn test_led_color_interpolate_in_linear_light() {
+        let red = LedColor { red: 255, green: 0, blue: 0 };
+        let green = LedColor { red: 0, green: 255, blue: 0 };
+
+        assert_eq!(red.interpolate(green, 0.0), red);
+        assert_eq!(red.interpolate(green, 1.0), green);
+        assert_eq!(red.interpolate(green, 0.5), LedColor { red: 186, green: 186, blue: 0 });
+        assert_eq!(red.interpolate(green, f32::NAN), red);
+    }
+
+    #[test]
+    fn test_led_color_from_name() {
+        assert_eq!(LedColor::from_name("yellow"), Some(LedColor { red: 255, green: 255, blue: 0 }));
+        assert_eq!(LedColor::from_name("Off"), Some(LedColor::default()));
+        assert_eq!(LedColor::from_name("purple"), None);
+    }
+
+    #[test]
+    fn test_dump_table() {
+        let dump = CommandBuilder::new().dump_table();
+        let entries: Vec<&str> = dump.lines().filter(|line| line.starts_with('[')).collect();
+        assert_eq!(entries.len(), 38);
+
+        let twist = entries[CommandId::Twist.index()];
+        assert!(twist.starts_with("[ 5] twist"));
+        assert!(twist.contains("55 1b 04 C8 09 c3 CT CT"));
+
+        let gimbal = entries[CommandId::Gimbal.index()];
+        assert!(gimbal.ends_with("C16 C16"));
+    }
+
+    #[test]
+    fn test_stopped_and_neutral_encoding() {
+        let stopped = MovementParams::stopped();
+        assert_eq!((stopped.vx, stopped.vy, stopped.vz), (0.0, 0.0, 0.0));
+
+        let neutral = GimbalParams::neutral();
+        assert_eq!((neutral.ry, neutral.rz), (0.0, 0.0));
+
+        let builder = CommandBuilder::new();
+        let counters = CommandCounters::default();
+
+        // All twist axes encode to the 1024 midpoint
+        let twist = builder.build_twist_command(stopped, &counters).unwrap();
+        assert_eq!(&twist[11..14], &[0x00, 0x04, 0x20]);
+        assert_eq!(&twist[16..18], &[0x08, 0x40]);
+        assert_eq!(&twist[19..21], &[0x02, 0x10]);
+
+        let gimbal = builder.build_gimbal_command(neutral, &counters).unwrap();
+        assert_eq!(&gimbal[13..17], &[0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_led_color_offsets_round_trip() {
+        let builder = CommandBuilder::new();
+        let color = LedColor { red: 1, green: 2, blue: 3 };
+        let mut cmd = builder.build_led_command(color, &CommandCounters::default()).unwrap();
+
+        assert_eq!(cmd[LED_RED_OFFSET], 1);
+        assert_eq!(cmd[LED_GREEN_OFFSET], 2);
+        assert_eq!(cmd[LED_BLUE_OFFSET], 3);
+        assert_eq!(builder.decode_led_command(&cmd), Some(color));
+
+        // Swapping channels must break the CRC16
+        cmd.swap(LED_RED_OFFSET, LED_BLUE_OFFSET);
+        assert_eq!(builder.decode_led_command(&cmd), None);
+    }
+
+    #[test]
+    fn test_custom_crc16_init_changes_checksum() {
+        let counters = CommandCounters::default();
+        let color = LedColor { red: 10, green: 20, blue: 30 };
+
+        let default_cmd = CommandBuilder::new().build_led_command(color, &counters).unwrap();
+        let custom = CommandBuilder::with_crc16_init(0x1234);
+        let custom_cmd = custom.build_led_command(color, &counters).unwrap();
+
+        let len = default_cmd.len();
+        assert_eq!(default_cmd[..len - 2], custom_cmd[..len - 2]);
+        assert_ne!(default_cmd[len - 2..], custom_cmd[len - 2..]);
+        assert!(verify_crc16_checksum(&custom_cmd, 0x1234));
+        assert_eq!(custom.decode_led_command(&custom_cmd), Some(color));
+        assert_eq!(CommandBuilder::new().decode_led_command(&custom_cmd), None);
+    }
+
+    #[test]
+    fn test_header_crc8_matches_python_per_command() {
+        let builder = CommandBuilder::new();
+        let counters = CommandCounters::default();
+
+        // Values from the Python implementation for each command's header
+        let led = builder.build_led_command(LedColor { red: 255, green: 0, blue: 0 }, &counters).unwrap();
+        assert_eq!(&led[..4], &[0x55, 0x1A, 0x04, 0xB1]);
+
+        let gimbal = builder.build_gimbal_command(GimbalParams::neutral(), &counters).unwrap();
+        assert_eq!(&gimbal[..4], &[0x55, 0x14, 0x04, 0x6D]);
+
+        let twist = builder.build_twist_command(MovementParams::stopped(), &counters).unwrap();
+        assert_eq!(&twist[..4], &[0x55, 0x1B, 0x04, 0x75]);
+
+        let blaster = builder.build_blaster_command(1, &counters).unwrap();
+        assert_eq!(&blaster[..4], &[0x55, 0x0E, 0x04, 0x66]);
+    }
+
     #[test]
     fn test_invalid_command_index() {
         let builder = CommandBuilder::new();
-        let result = builder.get_command_template(999);
-        assert!(result.is_err());
+        let truncated = CommandBuilder {
+            command_table: builder.command_table[..CommandId::Twist.index()].into(),
+            ..builder.clone()
+        };
+        assert!(truncated.get_command_template(CommandId::Gimbal).is_ok());
+        assert!(truncated.get_command_template(CommandId::Twist).is_err());
     }
 }
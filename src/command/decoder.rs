@@ -0,0 +1,259 @@
+//! Parsing built commands back from their CAN frames
+//!
+//! The inverse of [`CommandBuilder`](super::CommandBuilder): frames produced
+//! by [`MessageSplitter`](crate::can::MessageSplitter) are reassembled and
+//! every checksum is verified, which makes it easy to see exactly what was
+//! put on the bus and to catch builder regressions.
+
+use super::builder::WHEEL_SPEED_CMD;
+use crate::can::{validate_message_length, MessageReassembler, MESSAGE_SOF, MIN_MESSAGE_LEN};
+use crate::crc::{calculate_crc8, verify_crc16_detailed, CRC16_INIT};
+use crate::error::ProtocolError;
+
+/// Offset of the CRC8 closing the message header
+pub const HEADER_CRC8_OFFSET: usize = 3;
+/// Offset of the little-endian sequence counter
+pub const COUNTER_OFFSET: usize = 6;
+/// Offset of the command set byte
+pub const COMMAND_SET_OFFSET: usize = 9;
+/// Offset of the command ID byte
+pub const COMMAND_ID_OFFSET: usize = 10;
+/// Offset of the first payload byte after the command ID
+pub const PAYLOAD_OFFSET: usize = 11;
+
+/// Type of a command, identified by its command set and ID
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommandKind {
+    /// Chassis twist (movement)
+    Twist,
+    /// Chassis wheel speeds
+    WheelSpeeds,
+    /// Gimbal rotation
+    Gimbal,
+    /// LED color or effect
+    Led,
+    /// Blaster fire
+    BlasterFire,
+    /// Touch keep-alive
+    Touch,
+    /// Any other command, such as the boot sequence
+    Other,
+}
+
+impl CommandKind {
+    /// Identify a command from its command set and ID
+    pub fn from_ids(command_set: u8, command_id: u8) -> Self {
+        match (command_set, command_id) {
+            (0x3F, 0x60) => Self::Twist,
+            ids if ids == WHEEL_SPEED_CMD => Self::WheelSpeeds,
+            (0x04, 0x69) => Self::Gimbal,
+            (0x3F, 0x32) => Self::Led,
+            (0x3F, 0x51) => Self::BlasterFire,
+            (0x04, 0x4C) => Self::Touch,
+            _ => Self::Other,
+        }
+    }
+}
+
+/// A command whose header and checksums have been verified
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodedCommand {
+    /// Type of the command
+    pub kind: CommandKind,
+    /// Command set byte
+    pub command_set: u8,
+    /// Command ID byte
+    pub command_id: u8,
+    /// Sequence counter carried in the header
+    pub counter: u16,
+    /// Bytes between the command ID and the CRC16
+    pub payload: Vec<u8>,
+}
+
+/// Reassembles and verifies commands from CAN frame data
+#[derive(Debug)]
+pub struct CommandDecoder {
+    reassembler: MessageReassembler,
+    crc16_init: u16,
+}
+
+impl CommandDecoder {
+    /// Create a decoder for the default CRC16 init value
+    pub fn new() -> Self {
+        Self::with_crc16_init(CRC16_INIT)
+    }
+
+    /// Create a decoder for a firmware-specific CRC16 init value
+    pub fn with_crc16_init(crc16_init: u16) -> Self {
+        Self {
+            reassembler: MessageReassembler::new(),
+            crc16_init,
+        }
+    }
+
+    /// Append the data of one CAN frame
+    ///
+    /// Returns the decoded command once its last frame has arrived.
+    pub fn push_frame(&mut self, data: &[u8]) -> Result<Option<DecodedCommand>, ProtocolError> {
+        match self.reassembler.push(data)? {
+            Some(message) => self.decode(&message).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Decode a command from all of its frames
+    ///
+    /// Fails with `MessageTooShort` if the frames end before the command
+    /// does, and `MessageTooLong` if data follows it.
+    pub fn decode_frames<F: AsRef<[u8]>>(&self, frames: &[F]) -> Result<DecodedCommand, ProtocolError> {
+        let message: Vec<u8> = frames.iter().flat_map(|frame| frame.as_ref().iter().copied()).collect();
+        self.decode(&message)
+    }
+
+    /// Verify and parse one complete message
+    pub fn decode(&self, message: &[u8]) -> Result<DecodedCommand, ProtocolError> {
+        verify_message(message, self.crc16_init)?;
+
+        let command_set = message[COMMAND_SET_OFFSET];
+        let command_id = message[COMMAND_ID_OFFSET];
+        Ok(DecodedCommand {
+            kind: CommandKind::from_ids(command_set, command_id),
+            command_set,
+            command_id,
+            counter: u16::from_le_bytes([message[COUNTER_OFFSET], message[COUNTER_OFFSET + 1]]),
+            payload: message[PAYLOAD_OFFSET..message.len() - 2].to_vec(),
+        })
+    }
+}
+
+/// Check the framing, header CRC8 and message CRC16 of a complete message
+///
+/// Applies to commands and telemetry alike, since both use the same framing.
+pub fn verify_message(message: &[u8], crc16_init: u16) -> Result<(), ProtocolError> {
+    if message.len() < MIN_MESSAGE_LEN {
+        return Err(ProtocolError::MessageTooShort {
+            expected: MIN_MESSAGE_LEN,
+            actual: message.len(),
+        });
+    }
+    if message[0] != MESSAGE_SOF {
+        return Err(ProtocolError::InvalidHeader {
+            reason: format!("message starts with 0x{:02X} instead of 0x{:02X}", message[0], MESSAGE_SOF),
+        });
+    }
+    validate_message_length(message)?;
+
+    let header_crc = calculate_crc8(&message[..HEADER_CRC8_OFFSET]);
+    if header_crc != message[HEADER_CRC8_OFFSET] {
+        return Err(ProtocolError::CrcMismatch {
+            expected: message[HEADER_CRC8_OFFSET] as u16,
+            actual: header_crc as u16,
+        });
+    }
+    verify_crc16_detailed(message, crc16_init)
+}
+
+impl Default for CommandDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::can::{CommandCounters, MessageSplitter};
+    use crate::command::{CommandBuilder, GimbalParams, LedColor, MovementParams};
+
+    fn counters(joy: u16, led: u16, gimbal: u16) -> CommandCounters {
+        CommandCounters { joy, led, gimbal }
+    }
+
+    #[test]
+    fn test_decodes_builder_output() {
+        let builder = CommandBuilder::new();
+        let decoder = CommandDecoder::new();
+        let counters = counters(0x1234, 7, 42);
+
+        let twist = builder.build_twist_command(MovementParams { vx: 0.5, vy: 0.0, vz: 0.0 }, &counters).unwrap();
+        let decoded = decoder.decode_frames(&MessageSplitter::split_command(&twist)).unwrap();
+        assert_eq!(decoded.kind, CommandKind::Twist);
+        assert_eq!(decoded.counter, 0x1234);
+        assert_eq!(decoded.payload.len(), twist.len() - PAYLOAD_OFFSET - 2);
+
+        let gimbal = builder.build_gimbal_command(GimbalParams::neutral(), &counters).unwrap();
+        let decoded = decoder.decode_frames(&MessageSplitter::split_command(&gimbal)).unwrap();
+        assert_eq!(decoded.kind, CommandKind::Gimbal);
+        assert_eq!(decoded.counter, 42);
+
+        let led = builder.build_led_command(LedColor { red: 1, green: 2, blue: 3 }, &counters).unwrap();
+        assert_eq!(decoder.decode(&led).unwrap().kind, CommandKind::Led);
+
+        let touch = decoder.decode_frames(&builder.build_touch_command(&counters).unwrap()).unwrap();
+        assert_eq!(touch.kind, CommandKind::Touch);
+        assert_eq!(touch.counter, 0x1234);
+    }
+
+    #[test]
+    fn test_rejects_corrupted_commands() {
+        let builder = CommandBuilder::new();
+        let decoder = CommandDecoder::new();
+        let twist = builder.build_twist_command(MovementParams::stopped(), &CommandCounters::default()).unwrap();
+
+        let mut bad_sof = twist.clone();
+        bad_sof[0] = 0xAA;
+        assert!(matches!(decoder.decode(&bad_sof), Err(ProtocolError::InvalidHeader { .. })));
+
+        let mut bad_crc8 = twist.clone();
+        bad_crc8[HEADER_CRC8_OFFSET] ^= 0xFF;
+        assert!(matches!(decoder.decode(&bad_crc8), Err(ProtocolError::CrcMismatch { .. })));
+
+        let mut bad_payload = twist.clone();
+        bad_payload[PAYLOAD_OFFSET] ^= 0x01;
+        assert!(matches!(decoder.decode(&bad_payload), Err(ProtocolError::CrcMismatch { .. })));
+
+        assert!(matches!(
+            decoder.decode(&twist[..twist.len() - 1]),
+            Err(ProtocolError::MessageTooShort { .. })
+        ));
+        assert!(matches!(
+            CommandDecoder::with_crc16_init(0x1234).decode(&twist),
+            Err(ProtocolError::CrcMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_decode_frames_requires_exactly_one_command() {
+        let twist = CommandBuilder::new()
+            .build_twist_command(MovementParams::stopped(), &CommandCounters::default())
+            .unwrap();
+        let frames = MessageSplitter::split_command(&twist);
+        let decoder = CommandDecoder::new();
+
+        assert!(matches!(
+            decoder.decode_frames(&frames[..frames.len() - 1]),
+            Err(ProtocolError::MessageTooShort { .. })
+        ));
+
+        let mut doubled = frames.clone();
+        doubled.extend(frames.iter().cloned());
+        assert!(matches!(decoder.decode_frames(&doubled), Err(ProtocolError::MessageTooLong { .. })));
+        assert!(decoder.decode_frames(&frames).is_ok());
+    }
+
+    #[test]
+    fn test_push_frame_returns_command_on_last_frame() {
+        let builder = CommandBuilder::new();
+        let mut decoder = CommandDecoder::new();
+        let counters = counters(5, 0, 0);
+        let twist = builder.build_twist_command(MovementParams::stopped(), &counters).unwrap();
+        let frames = MessageSplitter::split_command(&twist);
+
+        for frame in &frames[..frames.len() - 1] {
+            assert_eq!(decoder.push_frame(frame).unwrap(), None);
+        }
+        let decoded = decoder.push_frame(frames.last().unwrap()).unwrap().unwrap();
+        assert_eq!(decoded.kind, CommandKind::Twist);
+        assert_eq!(decoded.counter, 5);
+    }
+}
@@ -2,11 +2,23 @@
 /// This is a direct port of the Python command_table.py with type safety improvements
 
 pub mod builder;
+pub mod decoder;
+pub mod proto;
 
+use crate::can::{MESSAGE_SOF, MIN_MESSAGE_LEN};
+use crate::error::ProtocolError;
 use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
 
 // Re-export builder types for convenience
-pub use builder::{CommandBuilder, MovementParams, GimbalParams, LedColor};
+pub use builder::{
+    CommandBuilder, MovementParams, GimbalParams, LedColor, MAX_GIMBAL_RATE,
+    LED_RED_OFFSET, LED_GREEN_OFFSET, LED_BLUE_OFFSET,
+    MIN_GIMBAL_PITCH, MAX_GIMBAL_PITCH, MAX_GIMBAL_YAW,
+    WheelSpeeds, MAX_WHEEL_RPM, WHEEL_RPM_SCALE, LedEffect, LedZone, LED_SEGMENT_COUNT,
+    MAX_BLASTER_BURST, check_blaster_burst, TwistEnable, ChassisMode, SERIAL_NUMBER_CMD,
+};
+pub use decoder::{verify_message, CommandDecoder, CommandKind, DecodedCommand};
 
 /// Command template type - each command is a vector of bytes with special values:
 /// - 0xFF: Placeholder for CRC8/CRC16 or counter values
@@ -33,6 +45,8 @@ pub mod commands {
     pub const TWIST: usize = 5;
     pub const LED_PATTERN_6: usize = 6;
     pub const LED_PATTERN_7: usize = 7;
+    /// Blaster fire (command set 0x3F, id 0x51); same template as `LED_PATTERN_7`
+    pub const BLASTER_FIRE: usize = 7;
     pub const LED_PATTERN_8: usize = 8;
     pub const LED_COLOR: usize = 9;
     pub const LED_BRIGHT_10: usize = 10;
@@ -68,6 +82,125 @@ pub mod commands {
 pub const BOOT_COMMAND_START: usize = 26;
 pub const BOOT_COMMAND_END: usize = 34;
 
+/// Named entries of the command table
+///
+/// Builders look templates up by name so an index cannot be mistyped; the
+/// table itself stays a plain numeric list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CommandId {
+    /// Gimbal rotation
+    Gimbal,
+    /// Chassis twist (movement)
+    Twist,
+    /// Blaster fire
+    BlasterFire,
+    /// LED color and effect
+    LedColor,
+    /// LED power on, sent after the boot sequence
+    LedOn,
+    /// First touch keep-alive variant
+    Touch20,
+    /// Second touch keep-alive variant
+    Touch21,
+    /// Boot sequence message at table entry 26
+    Boot26,
+    /// Boot sequence message at table entry 27
+    Boot27,
+    /// Boot sequence message at table entry 28
+    Boot28,
+    /// Boot sequence message at table entry 29
+    Boot29,
+    /// Boot sequence message at table entry 30
+    Boot30,
+    /// Boot sequence message at table entry 31
+    Boot31,
+    /// Boot sequence message at table entry 32
+    Boot32,
+    /// Boot sequence message at table entry 33
+    Boot33,
+    /// Boot sequence message at table entry 34
+    Boot34,
+}
+
+/// Messages of the boot sequence, in the order they are sent
+pub const BOOT_SEQUENCE: [CommandId; 9] = [
+    CommandId::Boot26,
+    CommandId::Boot27,
+    CommandId::Boot28,
+    CommandId::Boot29,
+    CommandId::Boot30,
+    CommandId::Boot31,
+    CommandId::Boot32,
+    CommandId::Boot33,
+    CommandId::Boot34,
+];
+
+impl CommandId {
+    /// Every named command
+    pub const ALL: [CommandId; 16] = [
+        Self::Gimbal,
+        Self::Twist,
+        Self::BlasterFire,
+        Self::LedColor,
+        Self::LedOn,
+        Self::Touch20,
+        Self::Touch21,
+        Self::Boot26,
+        Self::Boot27,
+        Self::Boot28,
+        Self::Boot29,
+        Self::Boot30,
+        Self::Boot31,
+        Self::Boot32,
+        Self::Boot33,
+        Self::Boot34,
+    ];
+
+    /// Get the index of the command's template in the command table
+    pub const fn index(self) -> usize {
+        match self {
+            Self::Gimbal => commands::GIMBAL,
+            Self::Twist => commands::TWIST,
+            Self::BlasterFire => commands::BLASTER_FIRE,
+            Self::LedColor => commands::LED_COLOR,
+            Self::LedOn => commands::LED_ON,
+            Self::Touch20 => commands::TOUCH_20,
+            Self::Touch21 => commands::TOUCH_21,
+            Self::Boot26 => commands::BOOT_8,
+            Self::Boot27 => commands::BOOT_9,
+            Self::Boot28 => commands::BOOT_10,
+            Self::Boot29 => commands::BOOT_11,
+            Self::Boot30 => commands::BOOT_12,
+            Self::Boot31 => commands::BOOT_13,
+            Self::Boot32 => commands::BOOT_14,
+            Self::Boot33 => commands::BOOT_15,
+            Self::Boot34 => commands::BOOT_16,
+        }
+    }
+
+    /// Get the name used by [`create_command_map`]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Gimbal => "gimbal",
+            Self::Twist => "twist",
+            Self::BlasterFire => "blaster_fire",
+            Self::LedColor => "led_color",
+            Self::LedOn => "led_on",
+            Self::Touch20 => "touch_20",
+            Self::Touch21 => "touch_21",
+            Self::Boot26 => "boot_26",
+            Self::Boot27 => "boot_27",
+            Self::Boot28 => "boot_28",
+            Self::Boot29 => "boot_29",
+            Self::Boot30 => "boot_30",
+            Self::Boot31 => "boot_31",
+            Self::Boot32 => "boot_32",
+            Self::Boot33 => "boot_33",
+            Self::Boot34 => "boot_34",
+        }
+    }
+}
+
 /// Get the complete command table
 pub fn get_command_table() -> Vec<CommandTemplate> {
     vec![
@@ -112,38 +245,50 @@ pub fn get_command_table() -> Vec<CommandTemplate> {
     ]
 }
 
+/// Get the command table shared by every [`CommandBuilder`]
+///
+/// The table is built and validated on first use, so calling this at startup
+/// pays that cost up front. Later calls return the same table.
+pub fn shared_command_table() -> Arc<[CommandTemplate]> {
+    static TABLE: OnceLock<Arc<[CommandTemplate]>> = OnceLock::new();
+    let table = TABLE.get_or_init(|| {
+        let table = get_command_table();
+        if let Err(e) = validate_command_table(&table) {
+            panic!("built-in command table is invalid: {}", e);
+        }
+        table.into()
+    });
+    Arc::clone(table)
+}
+
+/// Check that every template in a command table can be built into a message
+///
+/// Each template must start with the start-of-frame byte, carry its CRC8
+/// placeholder right after the three header bytes and declare a length
+/// between [`MIN_MESSAGE_LEN`] and its own length.
+pub fn validate_command_table(table: &[CommandTemplate]) -> Result<(), ProtocolError> {
+    for (command_id, template) in table.iter().enumerate() {
+        if template.first() != Some(&MESSAGE_SOF) {
+            return Err(ProtocolError::InvalidHeader {
+                reason: format!("command {} does not start with 0x{:02X}", command_id, MESSAGE_SOF),
+            });
+        }
+        if !is_crc8_position(template, 3) {
+            return Err(ProtocolError::InvalidHeader {
+                reason: format!("command {} has no CRC8 placeholder at byte 3", command_id),
+            });
+        }
+        match get_command_length(template) {
+            Some(length) if (MIN_MESSAGE_LEN..=template.len()).contains(&length) => {}
+            _ => return Err(ProtocolError::InvalidCommandLength { command_id }),
+        }
+    }
+    Ok(())
+}
+
 /// Create a lookup map for commands by name
 pub fn create_command_map() -> HashMap<&'static str, usize> {
-    let mut map = HashMap::new();
-    
-    // Boot commands
-    for i in BOOT_COMMAND_START..=BOOT_COMMAND_END {
-        map.insert(
-            match i {
-                26 => "boot_26",
-                27 => "boot_27", 
-                28 => "boot_28",
-                29 => "boot_29",
-                30 => "boot_30",
-                31 => "boot_31",
-                32 => "boot_32",
-                33 => "boot_33",
-                34 => "boot_34",
-                _ => "boot_unknown",
-            },
-            i,
-        );
-    }
-    
-    // Main commands
-    map.insert("gimbal", commands::GIMBAL);
-    map.insert("twist", commands::TWIST);
-    map.insert("led_color", commands::LED_COLOR);
-    map.insert("led_on", commands::LED_ON);
-    map.insert("touch_20", commands::TOUCH_20);
-    map.insert("touch_21", commands::TOUCH_21);
-    
-    map
+    CommandId::ALL.iter().map(|id| (id.name(), id.index())).collect()
 }
 
 /// Get command length (second byte in command template)
@@ -156,6 +301,9 @@ pub fn get_command_length(command_template: &CommandTemplate) -> Option<usize> {
 }
 
 /// Check if a byte position should be replaced with CRC8
+///
+/// The CRC8 covers the same three header bytes (start of frame, length and
+/// version) in every command, so it always sits at byte 3.
 pub fn is_crc8_position(command_template: &CommandTemplate, position: usize) -> bool {
     position == 3 && 
     position < command_template.len() && 
@@ -236,6 +384,36 @@ mod tests {
         assert_eq!(pos2, led_cmd.len() - 1);
     }
 
+    #[test]
+    fn test_builtin_command_table_is_valid() {
+        assert!(validate_command_table(&get_command_table()).is_ok());
+        assert_eq!(shared_command_table().len(), 38);
+    }
+
+    #[test]
+    fn test_validate_rejects_bad_templates() {
+        let mut table = get_command_table();
+        table[3][0] = 0x00;
+        assert!(matches!(
+            validate_command_table(&table),
+            Err(ProtocolError::InvalidHeader { .. })
+        ));
+
+        let mut table = get_command_table();
+        table[commands::GIMBAL][3] = 0x00;
+        assert!(matches!(
+            validate_command_table(&table),
+            Err(ProtocolError::InvalidHeader { .. })
+        ));
+
+        let mut table = get_command_table();
+        table[9][1] = 0x40;
+        assert!(matches!(
+            validate_command_table(&table),
+            Err(ProtocolError::InvalidCommandLength { command_id: 9 })
+        ));
+    }
+
     #[test]
     fn test_command_map_creation() {
         let map = create_command_map();
@@ -251,4 +429,16 @@ mod tests {
         assert_eq!(BOOT_COMMAND_END, 34);
         assert_eq!(BOOT_COMMAND_END - BOOT_COMMAND_START + 1, 9); // 9 boot commands
     }
+
+    #[test]
+    fn test_command_ids_map_to_distinct_valid_entries() {
+        let table = get_command_table();
+        let indices: std::collections::HashSet<usize> = CommandId::ALL.iter().map(|id| id.index()).collect();
+        assert_eq!(indices.len(), CommandId::ALL.len());
+        assert!(indices.iter().all(|&index| index < table.len()));
+
+        let boot: Vec<usize> = BOOT_SEQUENCE.iter().map(|id| id.index()).collect();
+        assert_eq!(boot, (BOOT_COMMAND_START..=BOOT_COMMAND_END).collect::<Vec<_>>());
+        assert_eq!(CommandId::LedColor.index(), commands::LED_COLOR);
+    }
 }
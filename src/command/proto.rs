@@ -0,0 +1,72 @@
+//! Conversion between normalized velocities and raw protocol values
+//!
+//! Twist commands carry each axis as an 11-bit value of `256 * v + 1024`,
+//! so 1024 is standstill and one normalized unit is 256 raw steps. These
+//! helpers are the encoding used by
+//! [`build_twist_command`](super::CommandBuilder::build_twist_command) and
+//! its inverse, for translating captured frames back into velocities.
+
+/// Raw protocol steps per normalized velocity unit
+pub const VELOCITY_SCALE: f32 = 256.0;
+/// Raw protocol value for zero velocity
+pub const VELOCITY_OFFSET: f32 = 1024.0;
+/// Largest raw protocol velocity value
+pub const MAX_RAW_VELOCITY: u16 = 2047;
+
+/// Encode a normalized linear velocity to its raw protocol value
+///
+/// The value is truncated toward zero and clamped to 0..=2047, so any
+/// velocity outside -4..4 saturates.
+pub fn encode_linear(v: f32) -> u16 {
+    ((VELOCITY_SCALE * v + VELOCITY_OFFSET) as i32).clamp(0, MAX_RAW_VELOCITY as i32) as u16
+}
+
+/// Decode a raw protocol linear velocity back to a normalized one
+///
+/// Values above 2047 do not fit in the 11-bit field and are clamped.
+pub fn decode_linear(raw: u16) -> f32 {
+    (raw.min(MAX_RAW_VELOCITY) as f32 - VELOCITY_OFFSET) / VELOCITY_SCALE
+}
+
+/// Encode a normalized angular velocity to its raw protocol value
+///
+/// Uses the same mapping as [`encode_linear`].
+pub fn encode_angular(vz: f32) -> u16 {
+    encode_linear(vz)
+}
+
+/// Decode a raw protocol angular velocity back to a normalized one
+pub fn decode_angular(raw: u16) -> f32 {
+    decode_linear(raw)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_across_range() {
+        for step in -400..=400 {
+            let v = step as f32 / 100.0;
+            let v = v.min(decode_linear(MAX_RAW_VELOCITY));
+            assert!((decode_linear(encode_linear(v)) - v).abs() < 1.0 / VELOCITY_SCALE, "v = {}", v);
+            assert!((decode_angular(encode_angular(v)) - v).abs() < 1.0 / VELOCITY_SCALE, "vz = {}", v);
+        }
+    }
+
+    #[test]
+    fn test_known_values() {
+        assert_eq!(encode_linear(0.0), 1024);
+        assert_eq!(encode_linear(1.0), 1280);
+        assert_eq!(encode_linear(-1.0), 768);
+        assert_eq!(encode_linear(10.0), MAX_RAW_VELOCITY);
+        assert_eq!(encode_linear(-10.0), 0);
+
+        assert_eq!(decode_linear(1024), 0.0);
+        assert_eq!(decode_linear(1280), 1.0);
+        assert_eq!(decode_linear(0), -4.0);
+        assert_eq!(decode_linear(u16::MAX), decode_linear(MAX_RAW_VELOCITY));
+        // Every raw value survives a round trip exactly
+        assert!((0..=MAX_RAW_VELOCITY).all(|raw| encode_linear(decode_linear(raw)) == raw));
+    }
+}
@@ -0,0 +1,123 @@
+//! Blaster safety interlock and fire rate limiting
+
+use crate::error::ControlError;
+use std::time::{Duration, Instant};
+
+/// Time the blaster needs per shot before it accepts another fire command
+pub const BLASTER_SHOT_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Blaster state reported by telemetry
+///
+/// Fields are `None` until the first blaster status message is processed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BlasterStatus {
+    /// Whether the blaster is armed
+    pub armed: Option<bool>,
+    /// Whether the gimbal is in a state that permits firing
+    pub gimbal_ready: Option<bool>,
+}
+
+impl BlasterStatus {
+    /// Check whether the robot would accept a fire command
+    ///
+    /// Returns `BlasterInterlocked` if the blaster is not armed, the gimbal
+    /// is not ready, or no status has been received yet.
+    pub fn check_fire(&self) -> Result<(), ControlError> {
+        let reason = match (self.armed, self.gimbal_ready) {
+            (Some(true), Some(true)) => return Ok(()),
+            (None, _) | (_, None) => "no blaster status received",
+            (Some(false), _) => "blaster is not armed",
+            (_, Some(false)) => "gimbal is not in a firing state",
+        };
+        Err(ControlError::BlasterInterlocked {
+            reason: reason.to_string(),
+        })
+    }
+}
+
+/// Spaces fire commands so none reaches the blaster before it is ready
+///
+/// A burst of `n` shots keeps the blaster busy for `n` shot intervals.
+#[derive(Debug, Clone)]
+pub struct FireRateLimiter {
+    shot_interval: Duration,
+    ready_at: Option<Instant>,
+}
+
+impl FireRateLimiter {
+    /// Create a limiter allowing one shot per `shot_interval`
+    pub fn new(shot_interval: Duration) -> Self {
+        Self {
+            shot_interval,
+            ready_at: None,
+        }
+    }
+
+    /// Time to wait at `now` before the next fire command may be sent
+    pub fn delay(&self, now: Instant) -> Duration {
+        self.ready_at.map_or(Duration::ZERO, |ready_at| ready_at.saturating_duration_since(now))
+    }
+
+    /// Record a fire command of `count` shots sent at `now`
+    pub fn record_fire(&mut self, now: Instant, count: u8) {
+        self.ready_at = Some(now + self.shot_interval * u32::from(count));
+    }
+}
+
+impl Default for FireRateLimiter {
+    fn default() -> Self {
+        Self::new(BLASTER_SHOT_INTERVAL)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::control::SensorData;
+    use crate::telemetry::tests::build_message;
+    use crate::telemetry::{dispatch, TelemetryKind, BLASTER_STATUS_LAYOUT};
+
+    fn status_after(armed: u8, gimbal_ready: u8) -> BlasterStatus {
+        let message = build_message(
+            BLASTER_STATUS_LAYOUT.cmd_set,
+            BLASTER_STATUS_LAYOUT.cmd_id,
+            &[armed, gimbal_ready],
+        );
+        let mut sensors = SensorData::default();
+        assert_eq!(dispatch(&message, &mut sensors), Some(TelemetryKind::Blaster));
+        sensors.blaster
+    }
+
+    #[test]
+    fn test_not_armed_blocks_firing() {
+        let status = status_after(0, 1);
+        assert_eq!(status.armed, Some(false));
+        assert!(matches!(
+            status.check_fire(),
+            Err(ControlError::BlasterInterlocked { reason }) if reason.contains("not armed")
+        ));
+    }
+
+    #[test]
+    fn test_armed_and_ready_permits_firing() {
+        assert!(status_after(1, 1).check_fire().is_ok());
+    }
+
+    #[test]
+    fn test_burst_delays_next_fire() {
+        let mut limiter = FireRateLimiter::new(Duration::from_millis(100));
+        let start = Instant::now();
+        assert_eq!(limiter.delay(start), Duration::ZERO);
+
+        limiter.record_fire(start, 3);
+        assert_eq!(limiter.delay(start), Duration::from_millis(300));
+        assert_eq!(limiter.delay(start + Duration::from_millis(250)), Duration::from_millis(50));
+        assert_eq!(limiter.delay(start + Duration::from_millis(400)), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_gimbal_not_ready_or_unknown_blocks_firing() {
+        assert!(status_after(1, 0).check_fire().is_err());
+        assert!(BlasterStatus::default().check_fire().is_err());
+    }
+}
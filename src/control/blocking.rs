@@ -0,0 +1,135 @@
+//! Synchronous commands for callers without an async runtime
+//!
+//! These mirror the async API using the same command builders and counters,
+//! but sleep and wait on the calling thread. They suit simple scripts and
+//! embedded loops; do not call them from inside an async task, where they
+//! would block the runtime's worker thread.
+
+use super::{boot, send_touch_frames, RoboMaster};
+use crate::can::MessageSplitter;
+use crate::command::{LedColor, LedEffect, MovementParams};
+use crate::error::RoboMasterError;
+use std::time::Instant;
+
+impl RoboMaster {
+    /// Send the boot sequence, blocking until the robot is ready
+    ///
+    /// Blocking counterpart of [`initialize`](Self::initialize).
+    pub fn initialize_blocking(&mut self) -> Result<(), RoboMasterError> {
+        if self.is_initialized {
+            return Ok(());
+        }
+
+        tracing::info!("Initializing RoboMaster...");
+        let boot_command = self.command_builder.build_boot_sequence()?;
+        let can_messages = MessageSplitter::split_command(&boot_command);
+        boot::send_repeated_blocking(self.boot_repeats, boot::BOOT_REPEAT_DELAY, || {
+            self.can_interface.send_messages(&can_messages)
+        })?;
+
+        std::thread::sleep(boot::BOOT_SETTLE_DELAY);
+
+        self.is_initialized = true;
+        tracing::info!("RoboMaster initialized successfully");
+        Ok(())
+    }
+
+    /// Move the robot, blocking until the command is sent
+    ///
+    /// Blocking counterpart of [`move_robot`](Self::move_robot). It shares
+    /// the lease and tip-over checks and the watchdog, hold, filter, slew and
+    /// safe-mode handling with the async path.
+    pub fn move_robot_blocking(&mut self, movement: MovementParams) -> Result<(), RoboMasterError> {
+        self.control_leases.check(None)?;
+        self.check_can_move()?;
+        self.initialize_blocking()?;
+
+        let _permit = self.send_limiter.acquire_blocking();
+        self.feed_and_commit_movement(movement)
+    }
+
+    /// Set the LED color, blocking until the command is sent
+    ///
+    /// Blocking counterpart of [`control_led`](Self::control_led).
+    pub fn control_led_blocking(&mut self, color: LedColor) -> Result<(), RoboMasterError> {
+        let color = color.with_brightness(self.led_brightness);
+        let _permit = self.send_limiter.acquire_blocking();
+        self.send_led_now(|builder, counters| builder.build_led_effect_command(color, LedEffect::Solid, counters))
+    }
+
+    /// Send a touch command, blocking until it is sent
+    ///
+    /// Blocking counterpart of [`send_touch`](Self::send_touch).
+    pub fn send_touch_blocking(&mut self) -> Result<(), RoboMasterError> {
+        {
+            let _permit = self.send_limiter.acquire_blocking();
+            send_touch_frames(&self.can_interface, &self.command_builder, &self.command_counters)?;
+        }
+        self.keepalive.mark_touch_sent(Instant::now());
+        Ok(())
+    }
+
+    /// Stop the robot, blocking until the stop commands are sent
+    ///
+    /// Blocking counterpart of [`stop`](Self::stop).
+    pub fn stop_blocking(&mut self) -> Result<(), RoboMasterError> {
+        self.initialize_blocking()?;
+
+        let _permit = self.send_limiter.acquire_blocking();
+        self.stop_now()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::can::{CanInterface, MockCanBackend};
+    use std::time::Duration;
+
+    #[test]
+    fn test_blocking_commands_match_async_frames() {
+        let blocking_mock = MockCanBackend::new();
+        let mut blocking = RoboMaster::with_interface(CanInterface::with_backend(blocking_mock.clone()));
+        blocking.is_initialized = true;
+        let async_mock = MockCanBackend::new();
+        let mut robot = RoboMaster::with_interface(CanInterface::with_backend(async_mock.clone()));
+        robot.is_initialized = true;
+
+        let movement = MovementParams { vx: 0.5, vy: 0.0, vz: 0.2 };
+        let color = LedColor { red: 0, green: 0, blue: 255 };
+        blocking.move_robot_blocking(movement).unwrap();
+        blocking.control_led_blocking(color).unwrap();
+        blocking.send_touch_blocking().unwrap();
+        blocking.stop_blocking().unwrap();
+
+        futures::executor::block_on(async {
+            robot.move_robot(movement).await.unwrap();
+            robot.control_led(color).await.unwrap();
+            robot.send_touch().await.unwrap();
+            robot.stop().await.unwrap();
+        });
+
+        assert_eq!(blocking_mock.sent_frames(), async_mock.sent_frames());
+        let (counters, expected) = (blocking.get_counters(), robot.get_counters());
+        assert_eq!((counters.joy, counters.led, counters.gimbal), (expected.joy, expected.led, expected.gimbal));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_blocking_move_feeds_watchdog_and_replaces_hold() {
+        let mut robot = RoboMaster::with_interface(CanInterface::with_backend(MockCanBackend::new()));
+        robot.is_initialized = true;
+        robot.enable_watchdog(Duration::from_millis(100)).unwrap();
+
+        let movement = MovementParams { vx: 0.2, vy: 0.0, vz: 0.0 };
+        for _ in 0..3 {
+            tokio::time::sleep(Duration::from_millis(60)).await;
+            robot.move_robot_blocking(movement).unwrap();
+        }
+        assert_eq!(robot.get_counters().joy, 3, "The watchdog should not have sent a stop");
+
+        let hold = robot.hold(MovementParams { vx: 0.4, vy: 0.0, vz: 0.0 }).await.unwrap();
+        robot.move_robot_blocking(movement).unwrap();
+        tokio::task::yield_now().await;
+        assert!(!hold.is_active(), "A blocking movement should replace the hold");
+    }
+}
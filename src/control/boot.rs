@@ -0,0 +1,80 @@
+//! Boot sequence repetition
+
+use crate::error::RoboMasterError;
+use std::time::Duration;
+
+/// Default number of times the boot sequence is sent
+pub const DEFAULT_BOOT_REPEATS: u32 = 1;
+
+/// Delay between boot sequence repetitions
+pub const BOOT_REPEAT_DELAY: Duration = Duration::from_millis(100);
+
+/// Time the robot needs after the boot sequence before accepting commands
+pub const BOOT_SETTLE_DELAY: Duration = Duration::from_millis(500);
+
+/// Call `send` `repeats` times, sleeping `delay` between calls
+///
+/// Stops at the first error.
+pub(crate) async fn send_repeated<F>(repeats: u32, delay: Duration, mut send: F) -> Result<(), RoboMasterError>
+where
+    F: FnMut() -> Result<(), RoboMasterError>,
+{
+    for attempt in 0..repeats {
+        if attempt > 0 {
+            tokio::time::sleep(delay).await;
+        }
+        send()?;
+    }
+    Ok(())
+}
+
+/// Like [`send_repeated`], but sleeps the calling thread between calls
+pub(crate) fn send_repeated_blocking<F>(repeats: u32, delay: Duration, mut send: F) -> Result<(), RoboMasterError>
+where
+    F: FnMut() -> Result<(), RoboMasterError>,
+{
+    for attempt in 0..repeats {
+        if attempt > 0 {
+            std::thread::sleep(delay);
+        }
+        send()?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::can::MessageSplitter;
+    use crate::command::CommandBuilder;
+
+    #[tokio::test]
+    async fn test_boot_frames_sent_configured_times() {
+        let boot = CommandBuilder::new().build_boot_sequence().unwrap();
+        let frames = MessageSplitter::split_command(&boot);
+        let mut sent: Vec<Vec<u8>> = Vec::new();
+
+        send_repeated(3, Duration::ZERO, || {
+            sent.extend(frames.iter().cloned());
+            Ok(())
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(sent.len(), frames.len() * 3);
+        assert!(sent.chunks(frames.len()).all(|chunk| chunk == frames.as_slice()));
+    }
+
+    #[tokio::test]
+    async fn test_send_stops_at_first_error() {
+        let mut calls = 0;
+        let result = send_repeated(3, Duration::ZERO, || {
+            calls += 1;
+            Err(RoboMasterError::NotInitialized)
+        })
+        .await;
+
+        assert!(result.is_err());
+        assert_eq!(calls, 1);
+    }
+}
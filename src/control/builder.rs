@@ -0,0 +1,145 @@
+//! Step-by-step construction of a ready [`RoboMaster`]
+
+use super::RoboMaster;
+use crate::can::bus_load::DEFAULT_BITRATE;
+use crate::can::{CanBackend, CanInterface, DEFAULT_CAN_TIMEOUT, ROBOMASTER_CAN_ID};
+use crate::error::RoboMasterError;
+use std::time::Duration;
+
+/// Default CAN interface opened by [`RoboMasterBuilder`]
+pub const DEFAULT_INTERFACE: &str = "can0";
+
+/// Default time allowed for one initialization attempt
+pub const DEFAULT_INIT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Default pause between initialization attempts
+pub const DEFAULT_INIT_RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// Builder for a [`RoboMaster`], created by [`RoboMaster::builder`]
+///
+/// Opens the CAN interface, applies the CAN ID and timeouts and, unless
+/// disabled, runs the boot sequence with retries so the returned controller
+/// is ready for commands.
+pub struct RoboMasterBuilder {
+    interface_name: String,
+    can_interface: Option<CanInterface>,
+    can_id: u16,
+    bitrate: u32,
+    timeout: Duration,
+    auto_initialize: bool,
+    init_attempts: u32,
+    init_timeout: Duration,
+}
+
+impl RoboMasterBuilder {
+    /// Create a builder with the default settings
+    pub fn new() -> Self {
+        Self {
+            interface_name: DEFAULT_INTERFACE.to_string(),
+            can_interface: None,
+            can_id: ROBOMASTER_CAN_ID,
+            bitrate: DEFAULT_BITRATE,
+            timeout: DEFAULT_CAN_TIMEOUT,
+            auto_initialize: true,
+            init_attempts: 1,
+            init_timeout: DEFAULT_INIT_TIMEOUT,
+        }
+    }
+
+    /// Set the name of the SocketCAN interface to open
+    pub fn interface(mut self, name: &str) -> Self {
+        self.interface_name = name.to_string();
+        self
+    }
+
+    /// Talk to the robot through `backend` instead of opening an interface
+    pub fn backend(mut self, backend: impl CanBackend + 'static) -> Self {
+        self.can_interface = Some(CanInterface::with_backend(backend));
+        self
+    }
+
+    /// Set the CAN ID the robot listens on
+    pub fn can_id(mut self, can_id: u16) -> Self {
+        self.can_id = can_id;
+        self
+    }
+
+    /// Set the bus bitrate in bits per second, as configured on the interface
+    pub fn bitrate(mut self, bitrate: u32) -> Self {
+        self.bitrate = bitrate;
+        self
+    }
+
+    /// Set how long to wait for each received frame
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Choose whether [`build`](Self::build) runs the boot sequence
+    pub fn auto_initialize(mut self, auto_initialize: bool) -> Self {
+        self.auto_initialize = auto_initialize;
+        self
+    }
+
+    /// Set how many times initialization is attempted (at least once)
+    pub fn init_attempts(mut self, attempts: u32) -> Self {
+        self.init_attempts = attempts.max(1);
+        self
+    }
+
+    /// Set the time allowed for each initialization attempt
+    pub fn init_timeout(mut self, timeout: Duration) -> Self {
+        self.init_timeout = timeout;
+        self
+    }
+
+    /// Open the interface and, if enabled, initialize the robot
+    ///
+    /// Fails with the last initialization error, or `Timeout` if the last
+    /// attempt did not finish in time.
+    pub async fn build(self) -> Result<RoboMaster, RoboMasterError> {
+        let mut can_interface = match self.can_interface {
+            Some(can_interface) => can_interface,
+            None => CanInterface::new(&self.interface_name)?,
+        };
+        can_interface.set_can_id(self.can_id)?;
+        can_interface.set_bitrate(self.bitrate);
+
+        let mut robot = RoboMaster::with_interface(can_interface);
+        robot.set_receive_timeout(self.timeout);
+        if !self.auto_initialize {
+            return Ok(robot);
+        }
+
+        let mut attempt = 1;
+        loop {
+            let error = match tokio::time::timeout(self.init_timeout, robot.initialize()).await {
+                Ok(Ok(())) => return Ok(robot),
+                Ok(Err(error)) => error,
+                Err(_) => RoboMasterError::Timeout {
+                    timeout_ms: self.init_timeout.as_millis() as u64,
+                },
+            };
+            if attempt >= self.init_attempts {
+                return Err(error);
+            }
+            tracing::warn!("initialization attempt {} failed: {}", attempt, error);
+            attempt += 1;
+            tokio::time::sleep(DEFAULT_INIT_RETRY_DELAY).await;
+        }
+    }
+}
+
+impl Default for RoboMasterBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RoboMaster {
+    /// Start building a controller with custom connection settings
+    pub fn builder() -> RoboMasterBuilder {
+        RoboMasterBuilder::new()
+    }
+}
@@ -0,0 +1,124 @@
+//! Channel-based command interface
+//!
+//! Producers push [`RobotCommand`]s into a bounded channel while a task owns
+//! the [`RoboMaster`] and executes them in order.
+
+use super::RoboMaster;
+use crate::command::{GimbalParams, LedColor, MovementParams};
+use crate::error::RoboMasterError;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+/// Default capacity of the command channel
+pub const DEFAULT_COMMAND_CHANNEL_CAPACITY: usize = 32;
+
+/// A command that can be sent to a [`RoboMaster`] through a channel
+#[derive(Debug, Clone, Copy)]
+pub enum RobotCommand {
+    /// Move the chassis
+    Move(MovementParams),
+    /// Set the LED color
+    Led(LedColor),
+    /// Rotate the gimbal
+    Gimbal(GimbalParams),
+    /// Stop the chassis
+    Stop,
+}
+
+impl RoboMaster {
+    /// Execute a single [`RobotCommand`]
+    pub async fn execute(&mut self, command: RobotCommand) -> Result<(), RoboMasterError> {
+        match command {
+            RobotCommand::Move(movement) => self.move_robot(movement).await,
+            RobotCommand::Led(color) => self.control_led(color).await,
+            RobotCommand::Gimbal(gimbal) => self.move_gimbal(gimbal).await,
+            RobotCommand::Stop => self.stop().await,
+        }
+    }
+
+    /// Move the robot into a task fed by a bounded command channel
+    ///
+    /// Returns the sending half of the channel and the task handle. The task
+    /// executes commands in order until every sender is dropped, then hands
+    /// the controller back. A failing command is logged and skipped, so a
+    /// transient error such as a reconnect or a held lease does not leave
+    /// the chassis running on its last twist with nobody in control.
+    pub fn command_sender(mut self, capacity: usize) -> (mpsc::Sender<RobotCommand>, JoinHandle<RoboMaster>) {
+        let (sender, mut receiver) = mpsc::channel(capacity.max(1));
+
+        let handle = tokio::spawn(async move {
+            while let Some(command) = receiver.recv().await {
+                if let Err(error) = self.execute(command).await {
+                    tracing::warn!(?command, "skipping failed command: {}", error);
+                }
+            }
+            self
+        });
+
+        (sender, handle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::can::{CanInterface, MockCanBackend};
+    use crate::error::ControlError;
+
+    fn mock_robot() -> RoboMaster {
+        let mut robot = RoboMaster::with_interface(CanInterface::with_backend(MockCanBackend::new()));
+        robot.is_initialized = true;
+        robot
+    }
+
+    #[tokio::test]
+    async fn test_execute_dispatches_each_command() {
+        let mut robot = mock_robot();
+
+        robot.execute(RobotCommand::Led(LedColor { red: 255, green: 0, blue: 0 })).await.unwrap();
+        robot.execute(RobotCommand::Gimbal(GimbalParams { ry: 0.1, rz: 0.0 })).await.unwrap();
+        robot.execute(RobotCommand::Move(MovementParams { vx: 0.2, vy: 0.0, vz: 0.0 })).await.unwrap();
+
+        let counters = robot.get_counters();
+        assert_eq!(counters.led, 1);
+        assert_eq!(counters.gimbal, 2, "Movement also refreshes the gimbal");
+        assert_eq!(counters.joy, 1);
+    }
+
+    #[tokio::test]
+    async fn test_sender_hands_robot_back_once_senders_drop() {
+        let (sender, handle) = mock_robot().command_sender(1);
+        let second = sender.clone();
+
+        sender.send(RobotCommand::Led(LedColor { red: 0, green: 255, blue: 0 })).await.unwrap();
+        second.send(RobotCommand::Led(LedColor { red: 0, green: 0, blue: 255 })).await.unwrap();
+        drop(sender);
+        drop(second);
+
+        let robot = handle.await.unwrap();
+        assert_eq!(robot.get_counters().led, 2);
+    }
+
+    #[tokio::test]
+    async fn test_sender_task_skips_failing_commands() {
+        let robot = mock_robot();
+        let lease = robot.acquire_control().unwrap();
+        let (sender, handle) = robot.command_sender(DEFAULT_COMMAND_CHANNEL_CAPACITY);
+
+        // Blocked by the lease, but later commands still run
+        sender.send(RobotCommand::Move(MovementParams { vx: 0.2, vy: 0.0, vz: 0.0 })).await.unwrap();
+        sender.send(RobotCommand::Led(LedColor { red: 255, green: 0, blue: 0 })).await.unwrap();
+        sender.send(RobotCommand::Stop).await.unwrap();
+        drop(sender);
+
+        let mut robot = handle.await.unwrap();
+        let counters = robot.get_counters();
+        assert_eq!(counters.led, 1);
+        assert_eq!(counters.joy, 1, "Only the stop should reach the chassis");
+        assert!(matches!(
+            robot.execute(RobotCommand::Move(MovementParams::stopped())).await,
+            Err(RoboMasterError::Control(ControlError::MovementBlocked { .. }))
+        ));
+        drop(lease);
+    }
+}
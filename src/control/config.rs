@@ -0,0 +1,181 @@
+//! Controller configuration file with validation
+//!
+//! The `[control]` and `[led]` sections match `config/embedded_config.toml`;
+//! other sections are ignored.
+
+use crate::command::LedColor;
+use crate::error::ConfigError;
+use serde::Deserialize;
+
+/// Controller configuration loaded from TOML
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct RoboMasterConfig {
+    /// Control loop settings
+    pub control: ControlConfig,
+    /// Named LED colors for each robot state
+    pub led: LedConfig,
+}
+
+/// `[control]` section of the configuration
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct ControlConfig {
+    /// Control loop frequency in Hz
+    pub control_frequency: u64,
+    /// Stick inputs below this magnitude are treated as zero (0.0 to 1.0)
+    pub deadzone_threshold: f32,
+    /// Maximum speed multiplier (above 0.0, up to 1.0)
+    pub max_speed: f32,
+}
+
+/// `[led]` section of the configuration
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct LedConfig {
+    /// Color shown when the robot is ready
+    pub ready_color: String,
+    /// Color shown during an emergency stop
+    pub emergency_color: String,
+    /// Color shown when errors are being recovered
+    pub warning_color: String,
+    /// Color shown on shutdown
+    pub off_color: String,
+}
+
+impl Default for ControlConfig {
+    fn default() -> Self {
+        Self {
+            control_frequency: 50,
+            deadzone_threshold: 0.08,
+            max_speed: crate::MAX_SPEED,
+        }
+    }
+}
+
+impl Default for LedConfig {
+    fn default() -> Self {
+        Self {
+            ready_color: "green".to_string(),
+            emergency_color: "red".to_string(),
+            warning_color: "yellow".to_string(),
+            off_color: "off".to_string(),
+        }
+    }
+}
+
+impl RoboMasterConfig {
+    /// Parse and validate a configuration from a TOML string
+    pub fn from_toml_str(content: &str) -> Result<Self, ConfigError> {
+        let config: Self = toml::from_str(content)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Check every value, reporting the first invalid one by its TOML key
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let control = &self.control;
+        if control.control_frequency == 0 {
+            return Err(invalid("control.control_frequency", control.control_frequency));
+        }
+        if !(0.0..=1.0).contains(&control.deadzone_threshold) {
+            return Err(invalid("control.deadzone_threshold", control.deadzone_threshold));
+        }
+        if !(control.max_speed > 0.0 && control.max_speed <= crate::MAX_SPEED) {
+            return Err(invalid("control.max_speed", control.max_speed));
+        }
+
+        let led = &self.led;
+        for (key, name) in [
+            ("led.ready_color", &led.ready_color),
+            ("led.emergency_color", &led.emergency_color),
+            ("led.warning_color", &led.warning_color),
+            ("led.off_color", &led.off_color),
+        ] {
+            if LedColor::from_name(name).is_none() {
+                return Err(invalid(key, name));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn invalid(key: &str, value: impl ToString) -> ConfigError {
+    ConfigError::InvalidValue {
+        key: key.to_string(),
+        value: value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_invalid(config: RoboMasterConfig, expected_key: &str) {
+        match config.validate() {
+            Err(ConfigError::InvalidValue { key, .. }) => assert_eq!(key, expected_key),
+            other => panic!("expected invalid {}, got {:?}", expected_key, other),
+        }
+    }
+
+    #[test]
+    fn test_default_config_is_valid() {
+        assert!(RoboMasterConfig::default().validate().is_ok());
+    }
+
+    #[test]
+    fn test_zero_control_frequency_is_invalid() {
+        let mut config = RoboMasterConfig::default();
+        config.control.control_frequency = 0;
+        assert_invalid(config, "control.control_frequency");
+    }
+
+    #[test]
+    fn test_deadzone_out_of_range_is_invalid() {
+        for deadzone in [-0.1, 1.5, f32::NAN] {
+            let mut config = RoboMasterConfig::default();
+            config.control.deadzone_threshold = deadzone;
+            assert_invalid(config, "control.deadzone_threshold");
+        }
+    }
+
+    #[test]
+    fn test_max_speed_out_of_range_is_invalid() {
+        for max_speed in [0.0, -0.5, 1.2] {
+            let mut config = RoboMasterConfig::default();
+            config.control.max_speed = max_speed;
+            assert_invalid(config, "control.max_speed");
+        }
+    }
+
+    #[test]
+    fn test_unknown_led_color_is_invalid() {
+        let mut config = RoboMasterConfig::default();
+        config.led.warning_color = "purple".to_string();
+
+        let error = config.validate().unwrap_err();
+        assert_eq!(error.to_string(), "Invalid config value: led.warning_color = purple");
+    }
+
+    #[test]
+    fn test_from_toml_str_validates() {
+        let config = RoboMasterConfig::from_toml_str(
+            r#"
+            [control]
+            control_frequency = 10
+            touch_frequency = 1
+
+            [led]
+            ready_color = "blue"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.control.control_frequency, 10);
+        assert_eq!(config.led.ready_color, "blue");
+
+        assert!(matches!(
+            RoboMasterConfig::from_toml_str("[control]\nmax_speed = 0.0"),
+            Err(ConfigError::InvalidValue { .. })
+        ));
+    }
+}
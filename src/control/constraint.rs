@@ -0,0 +1,76 @@
+//! Constraints on combining strafe and rotation
+
+use crate::command::MovementParams;
+
+/// How simultaneous strafe (`vy`) and rotation (`vz`) commands are combined
+///
+/// Combined strafe and rotation can make the mecanum wheels slip on some
+/// surfaces. The constrained modes drop one of the two channels whenever
+/// both are non-zero.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MotionConstraint {
+    /// Allow strafe and rotation at the same time
+    #[default]
+    Holonomic,
+    /// Keep strafe and drop rotation when both are commanded
+    PreferStrafe,
+    /// Keep rotation and drop strafe when both are commanded
+    PreferRotation,
+}
+
+impl MotionConstraint {
+    /// Apply the constraint to a movement
+    pub fn apply(self, movement: MovementParams) -> MovementParams {
+        if movement.vy == 0.0 || movement.vz == 0.0 {
+            return movement;
+        }
+
+        match self {
+            Self::Holonomic => movement,
+            Self::PreferStrafe => MovementParams { vz: 0.0, ..movement },
+            Self::PreferRotation => MovementParams { vy: 0.0, ..movement },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::can::CommandCounters;
+    use crate::command::CommandBuilder;
+
+    const COMBINED: MovementParams = MovementParams { vx: 0.2, vy: 0.5, vz: -0.4 };
+
+    #[test]
+    fn test_holonomic_keeps_both_channels() {
+        let movement = MotionConstraint::Holonomic.apply(COMBINED);
+        assert_eq!(movement.vy, 0.5);
+        assert_eq!(movement.vz, -0.4);
+    }
+
+    #[test]
+    fn test_single_channel_is_untouched() {
+        let strafe_only = MovementParams { vz: 0.0, ..COMBINED };
+        assert_eq!(MotionConstraint::PreferRotation.apply(strafe_only).vy, 0.5);
+    }
+
+    #[test]
+    fn test_constraint_zeroes_lower_priority_channel_in_frame() {
+        let builder = CommandBuilder::new();
+        let counters = CommandCounters::default();
+
+        let constrained = MotionConstraint::PreferStrafe.apply(COMBINED);
+        let frame = builder.build_twist_command(constrained, &counters).unwrap();
+        let expected = builder
+            .build_twist_command(MovementParams { vz: 0.0, ..COMBINED }, &counters)
+            .unwrap();
+        assert_eq!(frame, expected);
+
+        let constrained = MotionConstraint::PreferRotation.apply(COMBINED);
+        let frame = builder.build_twist_command(constrained, &counters).unwrap();
+        let expected = builder
+            .build_twist_command(MovementParams { vy: 0.0, ..COMBINED }, &counters)
+            .unwrap();
+        assert_eq!(frame, expected);
+    }
+}
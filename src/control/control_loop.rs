@@ -0,0 +1,158 @@
+//! Fixed-rate control loop driving the robot from an input source
+
+use super::keepalive::period_from_hz;
+use super::RoboMaster;
+use crate::command::MovementParams;
+use crate::error::RoboMasterError;
+use std::time::Duration;
+use tokio::time::{interval, interval_at, Instant, Interval, MissedTickBehavior};
+
+/// Runs `input → move_robot → keep-alives` at a fixed rate
+///
+/// Each cycle asks the input source for a movement, sends it with
+/// [`RoboMaster::move_robot`] and then sends any keep-alives that are due.
+///
+/// By default the loop free-runs from the moment it starts. With
+/// [`phase_aligned`](Self::phase_aligned), ticks land on whole periods after
+/// a shared anchor instead, so loops on several robots stay in phase.
+#[derive(Debug, Clone)]
+pub struct ControlLoop {
+    period: Duration,
+    phase_anchor: Option<Instant>,
+}
+
+impl ControlLoop {
+    /// Create a control loop running at `hz` cycles per second
+    pub fn new(hz: u32) -> Result<Self, RoboMasterError> {
+        Ok(Self {
+            period: period_from_hz("control_frequency", hz)?,
+            phase_anchor: None,
+        })
+    }
+
+    /// Align ticks to `anchor + n * period` instead of free-running
+    ///
+    /// A loop started after `anchor` waits for the next boundary. Missed
+    /// ticks are skipped rather than delayed so the phase never drifts.
+    pub fn phase_aligned(mut self, anchor: Instant) -> Self {
+        self.phase_anchor = Some(anchor);
+        self
+    }
+
+    /// Get the time between cycles
+    pub fn period(&self) -> Duration {
+        self.period
+    }
+
+    /// Get the instant ticks are aligned to, if phase alignment is on
+    pub fn phase_anchor(&self) -> Option<Instant> {
+        self.phase_anchor
+    }
+
+    /// Run until a cycle fails
+    pub async fn run<F>(&self, robot: &mut RoboMaster, input: F) -> Result<(), RoboMasterError>
+    where
+        F: FnMut() -> MovementParams,
+    {
+        self.run_cycles(robot, None, input).await
+    }
+
+    /// Run exactly `iterations` cycles and return
+    ///
+    /// Useful in tests and short demos that need a deterministic number of sends.
+    pub async fn run_for_iterations<F>(&self, robot: &mut RoboMaster, iterations: usize, input: F) -> Result<(), RoboMasterError>
+    where
+        F: FnMut() -> MovementParams,
+    {
+        self.run_cycles(robot, Some(iterations), input).await
+    }
+
+    async fn run_cycles<F>(&self, robot: &mut RoboMaster, iterations: Option<usize>, mut input: F) -> Result<(), RoboMasterError>
+    where
+        F: FnMut() -> MovementParams,
+    {
+        let mut ticker = self.ticker();
+
+        let mut remaining = iterations;
+        while remaining != Some(0) {
+            ticker.tick().await;
+            robot.move_robot(input()).await?;
+            robot.service_keepalive().await?;
+            if let Some(count) = remaining.as_mut() {
+                *count -= 1;
+            }
+        }
+        Ok(())
+    }
+
+    fn ticker(&self) -> Interval {
+        match self.phase_anchor {
+            Some(anchor) => {
+                let mut ticker = interval_at(next_boundary(anchor, Instant::now(), self.period), self.period);
+                ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+                ticker
+            }
+            None => {
+                let mut ticker = interval(self.period);
+                ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+                ticker
+            }
+        }
+    }
+}
+
+/// First instant at or after `now` that is a whole number of periods after `anchor`
+fn next_boundary(anchor: Instant, now: Instant, period: Duration) -> Instant {
+    let elapsed = now.saturating_duration_since(anchor);
+    let periods = elapsed.as_nanos().div_ceil(period.as_nanos());
+    anchor + period * periods as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_period_from_frequency() {
+        assert_eq!(ControlLoop::new(50).unwrap().period(), Duration::from_millis(20));
+        assert!(matches!(
+            ControlLoop::new(0),
+            Err(RoboMasterError::InvalidParameter { .. })
+        ));
+    }
+
+    #[test]
+    fn test_next_boundary_rounds_up_to_whole_periods() {
+        let anchor = Instant::now();
+        let period = Duration::from_millis(10);
+
+        assert_eq!(next_boundary(anchor, anchor, period), anchor);
+        assert_eq!(next_boundary(anchor, anchor + Duration::from_millis(3), period), anchor + period);
+        assert_eq!(next_boundary(anchor, anchor + Duration::from_millis(20), period), anchor + period * 2);
+        assert_eq!(next_boundary(anchor + period, anchor, period), anchor + period);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_phase_aligned_ticks_land_on_boundaries() {
+        use crate::can::{CanInterface, MockCanBackend};
+
+        let mut robot = RoboMaster::with_interface(CanInterface::with_backend(MockCanBackend::new()));
+        robot.initialize().await.unwrap();
+
+        let anchor = Instant::now();
+        tokio::time::advance(Duration::from_millis(3)).await;
+
+        let control_loop = ControlLoop::new(100).unwrap().phase_aligned(anchor);
+        let mut ticks = Vec::new();
+        control_loop
+            .run_for_iterations(&mut robot, 3, || {
+                ticks.push(Instant::now() - anchor);
+                MovementParams::stopped()
+            })
+            .await
+            .unwrap();
+
+        let expected: Vec<_> = [10, 20, 30].into_iter().map(Duration::from_millis).collect();
+        assert_eq!(ticks, expected);
+    }
+}
@@ -0,0 +1,112 @@
+//! Detection of and recovery from a desynced command counter
+//!
+//! The robot echoes the last command counter it accepted. Commands still in
+//! flight make the local counter run slightly ahead of the echo, which is
+//! normal. Dropped frames can make the two diverge further, after which the
+//! robot ignores commands until they realign.
+
+/// Default drift between the local and echoed counters before resyncing
+pub const DEFAULT_MAX_COUNTER_DRIFT: u16 = 8;
+
+/// Compares echoed counters against the local counter and resyncs on drift
+#[derive(Debug, Clone)]
+pub struct CounterSync {
+    max_drift: u16,
+    resyncs: u64,
+}
+
+impl CounterSync {
+    /// Create a tracker resyncing when the drift exceeds `max_drift`
+    pub fn new(max_drift: u16) -> Self {
+        Self { max_drift, resyncs: 0 }
+    }
+
+    /// Set the maximum tolerated drift
+    pub fn set_max_drift(&mut self, max_drift: u16) {
+        self.max_drift = max_drift;
+    }
+
+    /// Get the maximum tolerated drift
+    pub fn max_drift(&self) -> u16 {
+        self.max_drift
+    }
+
+    /// Get the number of resyncs performed so far
+    pub fn resyncs(&self) -> u64 {
+        self.resyncs
+    }
+
+    /// Forget the resyncs performed so far
+    pub fn reset(&mut self) {
+        self.resyncs = 0;
+    }
+
+    /// Get the signed distance of the local counter ahead of the echo
+    pub fn drift(local: u16, echoed: u16) -> i16 {
+        local.wrapping_sub(echoed.wrapping_add(1)) as i16
+    }
+
+    /// Compare an echoed counter with the local counter
+    ///
+    /// If the drift exceeds the maximum, `local` is set to follow the echo
+    /// and `true` is returned.
+    pub fn observe(&mut self, local: &mut u16, echoed: u16) -> bool {
+        let drift = Self::drift(*local, echoed);
+        if drift.unsigned_abs() <= self.max_drift {
+            return false;
+        }
+
+        tracing::warn!(local = *local, echoed, drift, "command counter desynced, resyncing");
+        *local = echoed.wrapping_add(1);
+        self.resyncs += 1;
+        true
+    }
+}
+
+impl Default for CounterSync {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_COUNTER_DRIFT)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_small_drift_is_tolerated() {
+        let mut sync = CounterSync::new(4);
+        let mut local = 103;
+
+        assert!(!sync.observe(&mut local, 100));
+        assert_eq!(local, 103);
+        assert_eq!(sync.resyncs(), 0);
+    }
+
+    #[test]
+    fn test_mismatched_echo_resyncs() {
+        let mut sync = CounterSync::new(4);
+        let mut local = 120;
+
+        assert!(sync.observe(&mut local, 100));
+        assert_eq!(local, 101);
+        assert_eq!(sync.resyncs(), 1);
+
+        let mut behind = 90;
+        assert!(sync.observe(&mut behind, 100));
+        assert_eq!(behind, 101);
+        assert_eq!(sync.resyncs(), 2);
+    }
+
+    #[test]
+    fn test_drift_wraps_around() {
+        assert_eq!(CounterSync::drift(2, u16::MAX), 2);
+        assert_eq!(CounterSync::drift(u16::MAX, 1), -3);
+
+        let mut sync = CounterSync::new(4);
+        let mut local = 2;
+        assert!(!sync.observe(&mut local, u16::MAX));
+        assert!(sync.observe(&mut local, u16::MAX - 10));
+        assert_eq!(local, u16::MAX - 9);
+    }
+}
@@ -0,0 +1,103 @@
+//! Built-in demo routines combining movement and LED colors
+
+use crate::command::{LedColor, MovementParams};
+use std::time::Duration;
+
+/// Built-in demo routines
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DemoKind {
+    /// Drive the four sides of a square, changing color on every leg
+    Square,
+    /// Wiggle side to side, then spin in both directions with cycling colors
+    Dance,
+}
+
+/// A single step of a demo routine
+#[derive(Debug, Clone, Copy)]
+pub struct DemoStep {
+    /// Movement held for the duration of the step
+    pub movement: MovementParams,
+    /// LED color set at the start of the step, if any
+    pub led: Option<LedColor>,
+    /// How long the step lasts
+    pub duration: Duration,
+}
+
+impl DemoStep {
+    fn new(vx: f32, vy: f32, vz: f32, led: Option<LedColor>, duration_ms: u64) -> Self {
+        Self {
+            movement: MovementParams { vx, vy, vz },
+            led,
+            duration: Duration::from_millis(duration_ms),
+        }
+    }
+}
+
+const RED: LedColor = LedColor { red: 255, green: 0, blue: 0 };
+const GREEN: LedColor = LedColor { red: 0, green: 255, blue: 0 };
+const BLUE: LedColor = LedColor { red: 0, green: 0, blue: 255 };
+const WHITE: LedColor = LedColor { red: 255, green: 255, blue: 255 };
+const OFF: LedColor = LedColor { red: 0, green: 0, blue: 0 };
+
+impl DemoKind {
+    /// Get the steps making up this demo
+    ///
+    /// Every demo ends with a stopped step that turns the LEDs off.
+    pub fn steps(self) -> Vec<DemoStep> {
+        let mut steps = match self {
+            Self::Square => vec![
+                DemoStep::new(0.3, 0.0, 0.0, Some(RED), 1000),
+                DemoStep::new(0.0, 0.3, 0.0, Some(GREEN), 1000),
+                DemoStep::new(-0.3, 0.0, 0.0, Some(BLUE), 1000),
+                DemoStep::new(0.0, -0.3, 0.0, Some(WHITE), 1000),
+            ],
+            Self::Dance => vec![
+                DemoStep::new(0.0, 0.3, 0.0, Some(RED), 400),
+                DemoStep::new(0.0, -0.3, 0.0, Some(BLUE), 400),
+                DemoStep::new(0.0, 0.3, 0.0, Some(RED), 400),
+                DemoStep::new(0.0, -0.3, 0.0, Some(BLUE), 400),
+                DemoStep::new(0.0, 0.0, 0.6, Some(GREEN), 1500),
+                DemoStep::new(0.0, 0.0, -0.6, Some(WHITE), 1500),
+            ],
+        };
+        steps.push(DemoStep::new(0.0, 0.0, 0.0, Some(OFF), 0));
+        steps
+    }
+
+    /// Total duration of the demo
+    pub fn duration(self) -> Duration {
+        self.steps().iter().map(|step| step.duration).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_stopped(movement: &MovementParams) -> bool {
+        movement.vx == 0.0 && movement.vy == 0.0 && movement.vz == 0.0
+    }
+
+    #[test]
+    fn test_demos_move_and_light_up() {
+        for demo in [DemoKind::Square, DemoKind::Dance] {
+            let steps = demo.steps();
+            assert!(steps.iter().any(|step| !is_stopped(&step.movement)), "{:?} never moves", demo);
+            assert!(steps.iter().filter(|step| step.led.is_some()).count() > 1, "{:?} has no LED changes", demo);
+        }
+    }
+
+    #[test]
+    fn test_demos_end_stopped() {
+        for demo in [DemoKind::Square, DemoKind::Dance] {
+            let last = *demo.steps().last().unwrap();
+            assert!(is_stopped(&last.movement));
+            assert_eq!(last.led, Some(OFF));
+        }
+    }
+
+    #[test]
+    fn test_square_duration() {
+        assert_eq!(DemoKind::Square.duration(), Duration::from_secs(4));
+    }
+}
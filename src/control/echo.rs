@@ -0,0 +1,153 @@
+//! Detection of commands the robot silently rejects
+//!
+//! The S1 echoes the command counter while it accepts commands. Frames with
+//! a bad CRC16 are dropped without any error, so a long run of commands with
+//! no echo usually means the CRC16 init value does not match the firmware.
+
+use crate::error::ProtocolError;
+use std::time::{Duration, Instant};
+
+/// Default time without a counter echo before commands are reported as rejected
+pub const DEFAULT_ECHO_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Tracks whether sent commands are being acknowledged with a counter echo
+#[derive(Debug, Clone)]
+pub struct EchoWatch {
+    timeout: Duration,
+    unanswered_since: Option<Instant>,
+    warned: bool,
+    unanswered_commands: u32,
+    echo_seen: bool,
+    max_unanswered: Option<u32>,
+}
+
+impl EchoWatch {
+    /// Create a watch reporting after `timeout` without an echo
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            timeout,
+            unanswered_since: None,
+            warned: false,
+            unanswered_commands: 0,
+            echo_seen: false,
+            max_unanswered: None,
+        }
+    }
+
+    /// Fail [`verify_protocol`](Self::verify_protocol) once `max` commands
+    /// have been sent without any echo, or never with `None`
+    pub fn set_max_unanswered(&mut self, max: Option<u32>) {
+        self.max_unanswered = max;
+    }
+
+    /// Record that a command was sent at `now`
+    pub fn record_sent(&mut self, now: Instant) {
+        self.unanswered_since.get_or_insert(now);
+        self.unanswered_commands = self.unanswered_commands.saturating_add(1);
+    }
+
+    /// Record that a counter echo was received
+    pub fn record_echo(&mut self) {
+        self.unanswered_since = None;
+        self.warned = false;
+        self.unanswered_commands = 0;
+        self.echo_seen = true;
+    }
+
+    /// Fail if the robot has never echoed any of the commands sent so far
+    ///
+    /// Only applies once a maximum is set with
+    /// [`set_max_unanswered`](Self::set_max_unanswered) and only until the
+    /// first echo, since a robot that echoed once speaks the protocol.
+    pub fn verify_protocol(&self, crc16_init: u16) -> Result<(), ProtocolError> {
+        match self.max_unanswered {
+            Some(max) if !self.echo_seen && self.unanswered_commands >= max => Err(ProtocolError::NoCounterEcho {
+                commands: self.unanswered_commands,
+                crc16_init,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Check whether commands have gone unanswered for longer than the timeout
+    pub fn is_rejecting(&self, now: Instant) -> bool {
+        match self.unanswered_since {
+            Some(since) => now.saturating_duration_since(since) >= self.timeout,
+            None => false,
+        }
+    }
+
+    /// Warn once per rejection episode, suggesting a CRC16 init mismatch
+    ///
+    /// Returns `true` if the warning was emitted by this call.
+    pub fn check(&mut self, now: Instant, crc16_init: u16) -> bool {
+        if self.warned || !self.is_rejecting(now) {
+            return false;
+        }
+
+        self.warned = true;
+        tracing::warn!(
+            crc16_init,
+            "no counter echo for {:?}; the robot may be rejecting commands, check that the CRC16 init value matches the firmware",
+            self.timeout
+        );
+        true
+    }
+}
+
+impl Default for EchoWatch {
+    fn default() -> Self {
+        Self::new(DEFAULT_ECHO_TIMEOUT)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_warns_once_without_echo() {
+        let start = Instant::now();
+        let mut watch = EchoWatch::new(Duration::from_secs(1));
+
+        watch.record_sent(start);
+        watch.record_sent(start + Duration::from_millis(500));
+        assert!(!watch.check(start + Duration::from_millis(900), 13970));
+        assert!(watch.check(start + Duration::from_secs(1), 13970));
+        assert!(!watch.check(start + Duration::from_secs(2), 13970));
+        assert!(watch.is_rejecting(start + Duration::from_secs(2)));
+    }
+
+    #[test]
+    fn test_protocol_mismatch_after_unanswered_commands() {
+        let start = Instant::now();
+        let mut watch = EchoWatch::default();
+        for _ in 0..10 {
+            watch.record_sent(start);
+        }
+        assert!(watch.verify_protocol(13970).is_ok(), "disabled by default");
+
+        watch.set_max_unanswered(Some(10));
+        assert!(matches!(
+            watch.verify_protocol(13970),
+            Err(ProtocolError::NoCounterEcho { commands: 10, crc16_init: 13970 })
+        ));
+
+        watch.record_echo();
+        for _ in 0..20 {
+            watch.record_sent(start);
+        }
+        assert!(watch.verify_protocol(13970).is_ok(), "an earlier echo proves the protocol matches");
+    }
+
+    #[test]
+    fn test_echo_clears_rejection() {
+        let start = Instant::now();
+        let mut watch = EchoWatch::new(Duration::from_secs(1));
+
+        watch.record_sent(start);
+        watch.record_echo();
+        assert!(!watch.is_rejecting(start + Duration::from_secs(5)));
+        assert!(!watch.check(start + Duration::from_secs(5), 13970));
+    }
+}
@@ -0,0 +1,150 @@
+//! Tip-over and stall detection from IMU telemetry
+
+use super::ImuData;
+use crate::command::MovementParams;
+use std::time::{Duration, Instant};
+
+/// Standard gravity in m/s²
+const STANDARD_GRAVITY: f32 = 9.80665;
+
+/// Smallest upward acceleration, in g, of a robot standing on its wheels
+///
+/// Below this the chassis is tilted more than 60° and treated as tipped over.
+pub const TIP_OVER_MIN_UPRIGHT_G: f32 = 0.5;
+
+/// How long commanded motion may go without any IMU response
+pub const STALL_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Horizontal acceleration, in g, that counts as the chassis responding
+pub const STALL_MIN_ACCEL_G: f32 = 0.05;
+
+/// Yaw rate, in rad/s, that counts as the chassis responding
+pub const STALL_MIN_YAW_RATE: f32 = 0.1;
+
+/// Kind of chassis fault detected from telemetry
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FaultKind {
+    /// The chassis is no longer upright
+    TipOver,
+    /// Motion was commanded but the IMU saw no response
+    Stall,
+}
+
+/// Detects tip-overs and stalls from IMU readings and commanded motion
+///
+/// A tip-over is reported when gravity no longer points mostly down the
+/// chassis Z axis. A stall is reported when a new non-zero movement has
+/// been commanded for [`STALL_TIMEOUT`] without the IMU seeing any
+/// acceleration or rotation. Faults stay set until [`clear`](Self::clear).
+#[derive(Debug, Clone, Default)]
+pub struct FaultDetector {
+    fault: Option<FaultKind>,
+    awaiting_response_since: Option<Instant>,
+}
+
+impl FaultDetector {
+    /// Record that `movement` was sent to the chassis
+    pub fn commanded(&mut self, movement: MovementParams, now: Instant) {
+        self.awaiting_response_since = (movement != MovementParams::stopped()).then_some(now);
+    }
+
+    /// Check an IMU reading and return the fault detected by it, if any
+    ///
+    /// Readings with missing values are ignored.
+    pub fn observe_imu(&mut self, imu: &ImuData, now: Instant) -> Option<FaultKind> {
+        let [ax, ay, az] = imu.acceleration;
+        if [ax, ay, az].iter().any(|value| value.is_nan()) {
+            return None;
+        }
+
+        if az < TIP_OVER_MIN_UPRIGHT_G * STANDARD_GRAVITY {
+            return self.raise(FaultKind::TipOver);
+        }
+
+        let since = self.awaiting_response_since?;
+        let horizontal_accel = ax.hypot(ay);
+        let yaw_rate = imu.angular_velocity[2].abs();
+        if horizontal_accel >= STALL_MIN_ACCEL_G * STANDARD_GRAVITY || yaw_rate >= STALL_MIN_YAW_RATE {
+            self.awaiting_response_since = None;
+            return None;
+        }
+        if now.duration_since(since) >= STALL_TIMEOUT {
+            self.awaiting_response_since = None;
+            return self.raise(FaultKind::Stall);
+        }
+        None
+    }
+
+    /// Get the current fault, if any
+    pub fn fault(&self) -> Option<FaultKind> {
+        self.fault
+    }
+
+    /// Forget the current fault
+    pub fn clear(&mut self) {
+        self.fault = None;
+        self.awaiting_response_since = None;
+    }
+
+    fn raise(&mut self, kind: FaultKind) -> Option<FaultKind> {
+        // A tip-over outranks a stall
+        if self.fault == Some(kind) || self.fault == Some(FaultKind::TipOver) {
+            return None;
+        }
+        tracing::warn!(?kind, "chassis fault detected");
+        self.fault = Some(kind);
+        Some(kind)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn imu(acceleration: [f32; 3], yaw_rate: f32) -> ImuData {
+        ImuData {
+            acceleration,
+            angular_velocity: [0.0, 0.0, yaw_rate],
+            ..ImuData::default()
+        }
+    }
+
+    const UPRIGHT: [f32; 3] = [0.0, 0.0, STANDARD_GRAVITY];
+
+    #[test]
+    fn test_tip_over_detected_from_gravity_direction() {
+        let mut detector = FaultDetector::default();
+        let now = Instant::now();
+
+        assert_eq!(detector.observe_imu(&imu(UPRIGHT, 0.0), now), None);
+        assert_eq!(detector.observe_imu(&imu([STANDARD_GRAVITY, 0.0, 0.0], 0.0), now), Some(FaultKind::TipOver));
+        assert_eq!(detector.fault(), Some(FaultKind::TipOver));
+
+        detector.clear();
+        assert_eq!(detector.fault(), None);
+    }
+
+    #[test]
+    fn test_stall_needs_motion_command_without_response() {
+        let mut detector = FaultDetector::default();
+        let start = Instant::now();
+        let later = start + STALL_TIMEOUT;
+
+        assert_eq!(detector.observe_imu(&imu(UPRIGHT, 0.0), later), None, "No command, no stall");
+
+        detector.commanded(MovementParams { vx: 0.5, vy: 0.0, vz: 0.0 }, start);
+        assert_eq!(detector.observe_imu(&imu(UPRIGHT, 0.0), start), None);
+        assert_eq!(detector.observe_imu(&imu(UPRIGHT, 0.0), later), Some(FaultKind::Stall));
+    }
+
+    #[test]
+    fn test_imu_response_prevents_stall() {
+        let mut detector = FaultDetector::default();
+        let start = Instant::now();
+
+        detector.commanded(MovementParams { vx: 0.0, vy: 0.0, vz: 1.0 }, start);
+        assert_eq!(detector.observe_imu(&imu(UPRIGHT, 0.5), start), None);
+        assert_eq!(detector.observe_imu(&imu(UPRIGHT, 0.0), start + STALL_TIMEOUT), None);
+        assert_eq!(detector.fault(), None);
+    }
+}
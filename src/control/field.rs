@@ -0,0 +1,54 @@
+//! Field-relative movement
+//!
+//! Field-relative commands are expressed in a fixed frame: `vx` points
+//! along the field's forward axis and `vy` to its right, matching the
+//! robot frame when the robot's yaw is zero. Yaw is positive clockwise,
+//! the same direction as a positive `vz`.
+
+use crate::command::MovementParams;
+
+/// Rotate a field-relative movement into the robot frame
+///
+/// `yaw` is the robot heading in radians relative to the field's forward
+/// axis. Rotation (`vz`) is passed through unchanged.
+pub fn field_to_robot(field_vx: f32, field_vy: f32, vz: f32, yaw: f32) -> MovementParams {
+    let (sin, cos) = yaw.sin_cos();
+    MovementParams {
+        vx: field_vx * cos + field_vy * sin,
+        vy: -field_vx * sin + field_vy * cos,
+        vz,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::{FRAC_PI_2, PI};
+
+    fn assert_close(actual: f32, expected: f32) {
+        assert!((actual - expected).abs() < 1e-6, "{actual} != {expected}");
+    }
+
+    #[test]
+    fn test_zero_yaw_is_robot_frame() {
+        let movement = field_to_robot(0.5, -0.2, 0.3, 0.0);
+        assert_close(movement.vx, 0.5);
+        assert_close(movement.vy, -0.2);
+        assert_close(movement.vz, 0.3);
+    }
+
+    #[test]
+    fn test_facing_right_turns_forward_into_strafe() {
+        // Facing 90° clockwise, field-forward is to the robot's left
+        let movement = field_to_robot(1.0, 0.0, 0.0, FRAC_PI_2);
+        assert_close(movement.vx, 0.0);
+        assert_close(movement.vy, -1.0);
+    }
+
+    #[test]
+    fn test_facing_backwards_reverses() {
+        let movement = field_to_robot(1.0, 0.5, 0.0, PI);
+        assert_close(movement.vx, -1.0);
+        assert_close(movement.vy, -0.5);
+    }
+}
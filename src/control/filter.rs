@@ -0,0 +1,199 @@
+//! Movement shaping applied to every commanded movement
+
+use crate::command::MovementParams;
+
+/// Shapes commanded movements before they are encoded
+///
+/// Inputs below the deadzone are zeroed, then every axis is scaled by the
+/// maximum speed and clamped to the valid protocol range. Nonzero axes
+/// below the minimum effective speed are raised to it, keeping their sign.
+/// A minimum axis change can be set so that movements which barely differ
+/// from the last one sent are not resent.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MovementFilter {
+    deadzone: f32,
+    max_speed: f32,
+    min_axis_change: f32,
+    min_effective_speed: f32,
+}
+
+impl MovementFilter {
+    /// Create a pass-through movement filter
+    pub fn new() -> Self {
+        Self {
+            deadzone: 0.0,
+            max_speed: crate::MAX_SPEED,
+            min_axis_change: 0.0,
+            min_effective_speed: 0.0,
+        }
+    }
+
+    /// Set the deadzone (0.0 to 1.0)
+    pub fn set_deadzone(&mut self, deadzone: f32) {
+        self.deadzone = deadzone.clamp(0.0, 1.0);
+    }
+
+    /// Set the maximum speed multiplier (0.0 to 1.0)
+    pub fn set_max_speed(&mut self, max_speed: f32) {
+        self.max_speed = max_speed.clamp(0.0, crate::MAX_SPEED);
+    }
+
+    /// Set the minimum change on any axis needed to resend a movement (0.0 to 1.0)
+    pub fn set_min_axis_change(&mut self, threshold: f32) {
+        self.min_axis_change = threshold.clamp(0.0, 1.0);
+    }
+
+    /// Set the speed that any nonzero axis is raised to (0.0 to 1.0)
+    ///
+    /// The S1 does not move at very low speeds because of static friction,
+    /// so small inputs are boosted to the slowest speed that still moves it.
+    pub fn set_min_effective_speed(&mut self, threshold: f32) {
+        self.min_effective_speed = threshold.clamp(0.0, 1.0);
+    }
+
+    /// Get the current deadzone
+    pub fn deadzone(&self) -> f32 {
+        self.deadzone
+    }
+
+    /// Get the current maximum speed multiplier
+    pub fn max_speed(&self) -> f32 {
+        self.max_speed
+    }
+
+    /// Get the minimum axis change
+    pub fn min_axis_change(&self) -> f32 {
+        self.min_axis_change
+    }
+
+    /// Get the minimum effective speed
+    pub fn min_effective_speed(&self) -> f32 {
+        self.min_effective_speed
+    }
+
+    /// Check whether `next` differs enough from `previous` to be sent
+    ///
+    /// Every movement is significant when no minimum axis change is set.
+    /// A stop is always significant so the robot never creeps.
+    pub fn is_significant_change(&self, previous: MovementParams, next: MovementParams) -> bool {
+        if self.min_axis_change == 0.0 || next == MovementParams::stopped() {
+            return true;
+        }
+        (next.vx - previous.vx).abs() > self.min_axis_change
+            || (next.vy - previous.vy).abs() > self.min_axis_change
+            || (next.vz - previous.vz).abs() > self.min_axis_change
+    }
+
+    /// Apply the filter to a movement
+    pub fn apply(&self, movement: MovementParams) -> MovementParams {
+        MovementParams {
+            vx: self.apply_axis(movement.vx),
+            vy: self.apply_axis(movement.vy),
+            vz: self.apply_axis(movement.vz),
+        }
+    }
+
+    fn apply_axis(&self, value: f32) -> f32 {
+        if value.abs() < self.deadzone {
+            return 0.0;
+        }
+        let value = (value * self.max_speed).clamp(-crate::MAX_SPEED, crate::MAX_SPEED);
+        if value != 0.0 && value.abs() < self.min_effective_speed {
+            return self.min_effective_speed.copysign(value);
+        }
+        value
+    }
+}
+
+impl Default for MovementFilter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_filter_passes_through() {
+        let movement = MovementParams { vx: 0.5, vy: -0.2, vz: 1.0 };
+        let filtered = MovementFilter::new().apply(movement);
+
+        assert_eq!(filtered.vx, 0.5);
+        assert_eq!(filtered.vy, -0.2);
+        assert_eq!(filtered.vz, 1.0);
+    }
+
+    #[test]
+    fn test_min_effective_speed_boosts_small_inputs() {
+        let mut filter = MovementFilter::new();
+        filter.set_min_effective_speed(0.05);
+
+        let filtered = filter.apply(MovementParams { vx: 0.02, vy: -0.02, vz: 0.0 });
+        assert_eq!(filtered, MovementParams { vx: 0.05, vy: -0.05, vz: 0.0 });
+        assert_eq!(filter.apply(MovementParams { vx: 0.3, vy: 0.0, vz: -0.05 }).vx, 0.3);
+
+        // Inputs zeroed by the deadzone stay at rest
+        filter.set_deadzone(0.03);
+        assert_eq!(filter.apply(MovementParams { vx: 0.02, vy: 0.0, vz: 0.0 }), MovementParams::stopped());
+    }
+
+    #[test]
+    fn test_deadzone_and_max_speed() {
+        let mut filter = MovementFilter::new();
+        filter.set_deadzone(0.1);
+        filter.set_max_speed(0.5);
+
+        let filtered = filter.apply(MovementParams { vx: 1.0, vy: 0.05, vz: -0.4 });
+        assert_eq!(filtered.vx, 0.5);
+        assert_eq!(filtered.vy, 0.0);
+        assert_eq!(filtered.vz, -0.2);
+    }
+
+    #[test]
+    fn test_settings_are_clamped() {
+        let mut filter = MovementFilter::new();
+        filter.set_deadzone(2.0);
+        filter.set_max_speed(-1.0);
+
+        assert_eq!(filter.deadzone(), 1.0);
+        assert_eq!(filter.max_speed(), 0.0);
+    }
+
+    #[test]
+    fn test_every_change_is_significant_by_default() {
+        let filter = MovementFilter::new();
+        let previous = MovementParams { vx: 0.5, vy: 0.0, vz: 0.0 };
+
+        assert!(filter.is_significant_change(previous, previous));
+        assert!(filter.is_significant_change(previous, MovementParams { vx: 0.501, ..previous }));
+    }
+
+    #[test]
+    fn test_sub_threshold_changes_are_not_significant() {
+        let mut filter = MovementFilter::new();
+        filter.set_min_axis_change(0.01);
+        let previous = MovementParams { vx: 0.5, vy: -0.2, vz: 0.1 };
+
+        for delta in [0.001, 0.005, -0.009] {
+            let next = MovementParams {
+                vx: previous.vx + delta,
+                vy: previous.vy - delta,
+                vz: previous.vz + delta,
+            };
+            assert!(!filter.is_significant_change(previous, next));
+        }
+        assert!(filter.is_significant_change(previous, MovementParams { vy: -0.25, ..previous }));
+    }
+
+    #[test]
+    fn test_stopping_is_always_significant() {
+        let mut filter = MovementFilter::new();
+        filter.set_min_axis_change(0.1);
+        let creeping = MovementParams { vx: 0.05, vy: 0.0, vz: 0.0 };
+
+        assert!(filter.is_significant_change(creeping, MovementParams::stopped()));
+        assert!(filter.is_significant_change(MovementParams::stopped(), MovementParams::stopped()));
+    }
+}
@@ -0,0 +1,114 @@
+//! Gimbal hardware limit flags reported by telemetry
+
+use crate::command::GimbalParams;
+
+/// Limit-flag bit set while pitch is at its upper limit
+pub const PITCH_UPPER_LIMIT: u8 = 0x01;
+/// Limit-flag bit set while pitch is at its lower limit
+pub const PITCH_LOWER_LIMIT: u8 = 0x02;
+/// Limit-flag bit set while yaw is at its upper limit
+pub const YAW_UPPER_LIMIT: u8 = 0x04;
+/// Limit-flag bit set while yaw is at its lower limit
+pub const YAW_LOWER_LIMIT: u8 = 0x08;
+
+/// Gimbal axes currently held at a hardware limit
+///
+/// All flags are clear until the gimbal reports otherwise.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GimbalLimits {
+    /// Pitch is at its upper limit
+    pub pitch_upper: bool,
+    /// Pitch is at its lower limit
+    pub pitch_lower: bool,
+    /// Yaw is at its upper limit
+    pub yaw_upper: bool,
+    /// Yaw is at its lower limit
+    pub yaw_lower: bool,
+}
+
+impl GimbalLimits {
+    /// Decode the limit-flag byte of a gimbal telemetry message
+    pub fn from_flags(flags: u8) -> Self {
+        Self {
+            pitch_upper: flags & PITCH_UPPER_LIMIT != 0,
+            pitch_lower: flags & PITCH_LOWER_LIMIT != 0,
+            yaw_upper: flags & YAW_UPPER_LIMIT != 0,
+            yaw_lower: flags & YAW_LOWER_LIMIT != 0,
+        }
+    }
+
+    /// Check whether any axis is at a limit
+    pub fn any(&self) -> bool {
+        self.pitch_upper || self.pitch_lower || self.yaw_upper || self.yaw_lower
+    }
+
+    /// Zero every rate that would drive an axis further into a tripped limit
+    ///
+    /// Positive rates move towards the upper limit. Rates moving away from a
+    /// limit are kept so the gimbal can be backed off it.
+    pub fn restrict(&self, gimbal: GimbalParams) -> GimbalParams {
+        let restrict_axis = |rate: f32, upper: bool, lower: bool| {
+            if (rate > 0.0 && upper) || (rate < 0.0 && lower) {
+                0.0
+            } else {
+                rate
+            }
+        };
+        GimbalParams {
+            ry: restrict_axis(gimbal.ry, self.pitch_upper, self.pitch_lower),
+            rz: restrict_axis(gimbal.rz, self.yaw_upper, self.yaw_lower),
+        }
+    }
+
+    /// Keep absolute `(pitch, yaw)` targets from going further into a tripped limit
+    ///
+    /// An axis at its upper limit is not sent above its `current` angle, and
+    /// one at its lower limit not below it. Axes whose current angle is
+    /// unknown (`NaN`) keep their target.
+    pub fn restrict_angles(&self, current: (f32, f32), target: (f32, f32)) -> (f32, f32) {
+        let restrict_axis = |current: f32, target: f32, upper: bool, lower: bool| {
+            if (upper && target > current) || (lower && target < current) {
+                current
+            } else {
+                target
+            }
+        };
+        (
+            restrict_axis(current.0, target.0, self.pitch_upper, self.pitch_lower),
+            restrict_axis(current.1, target.1, self.yaw_upper, self.yaw_lower),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_flags() {
+        let limits = GimbalLimits::from_flags(PITCH_UPPER_LIMIT | YAW_LOWER_LIMIT);
+        assert!(limits.pitch_upper && limits.yaw_lower);
+        assert!(!limits.pitch_lower && !limits.yaw_upper);
+        assert!(!GimbalLimits::from_flags(0).any());
+    }
+
+    #[test]
+    fn test_rates_into_tripped_limit_are_zeroed() {
+        let limits = GimbalLimits::from_flags(PITCH_UPPER_LIMIT | YAW_UPPER_LIMIT);
+
+        let restricted = limits.restrict(GimbalParams { ry: 0.5, rz: 0.8 });
+        assert_eq!((restricted.ry, restricted.rz), (0.0, 0.0));
+
+        let backing_off = limits.restrict(GimbalParams { ry: -0.5, rz: -0.8 });
+        assert_eq!((backing_off.ry, backing_off.rz), (-0.5, -0.8));
+    }
+
+    #[test]
+    fn test_angle_targets_beyond_tripped_limit_hold_current_angle() {
+        let limits = GimbalLimits::from_flags(PITCH_UPPER_LIMIT | YAW_LOWER_LIMIT);
+
+        assert_eq!(limits.restrict_angles((30.0, -200.0), (35.0, -250.0)), (30.0, -200.0));
+        assert_eq!(limits.restrict_angles((30.0, -200.0), (10.0, 0.0)), (10.0, 0.0));
+        assert_eq!(limits.restrict_angles((f32::NAN, f32::NAN), (35.0, -250.0)), (35.0, -250.0));
+    }
+}
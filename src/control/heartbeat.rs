@@ -0,0 +1,73 @@
+//! Touch keep-alives sent from a background task
+
+use super::{send_touch_frames, RoboMaster};
+use crate::error::RoboMasterError;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+/// Handle to the task started by [`RoboMaster::start_heartbeat`]
+///
+/// Touch commands are sent until the handle is dropped or stopped, the robot
+/// is shut down, or a send fails.
+#[derive(Debug)]
+pub struct HeartbeatHandle {
+    task: JoinHandle<()>,
+}
+
+impl HeartbeatHandle {
+    /// Stop sending touch commands
+    pub fn stop(self) {}
+
+    /// Check whether touch commands are still being sent
+    pub fn is_active(&self) -> bool {
+        !self.task.is_finished()
+    }
+}
+
+impl Drop for HeartbeatHandle {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+impl RoboMaster {
+    /// Send a touch command every `interval` from a background task
+    ///
+    /// This keeps the robot in remote control without calling
+    /// [`send_touch`](Self::send_touch) from the control loop. The task shares
+    /// the CAN interface and command counters with the controller, so it can
+    /// run alongside movement commands. It ends when the handle is dropped or
+    /// [`shutdown`](Self::shutdown) is called.
+    pub fn start_heartbeat(&self, interval: Duration) -> Result<HeartbeatHandle, RoboMasterError> {
+        if interval.is_zero() {
+            return Err(RoboMasterError::InvalidParameter {
+                parameter: "interval".to_string(),
+                value: format!("{:?}", interval),
+            });
+        }
+
+        let can_interface = Arc::clone(&self.can_interface);
+        let command_builder = self.command_builder.clone();
+        let command_counters = Arc::clone(&self.command_counters);
+        let send_limiter = self.send_limiter.clone();
+        let mut shutdown = self.shutdown_signal.subscribe();
+
+        let task = tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                tokio::select! {
+                    _ = shutdown.changed() => break,
+                    _ = ticker.tick() => {}
+                }
+                let _permit = send_limiter.acquire().await;
+                if let Err(error) = send_touch_frames(&can_interface, &command_builder, &command_counters) {
+                    tracing::warn!("stopped heartbeat: {}", error);
+                    break;
+                }
+            }
+        });
+
+        Ok(HeartbeatHandle { task })
+    }
+}
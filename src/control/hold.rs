@@ -0,0 +1,141 @@
+//! Holding a movement in a background task
+
+use super::{send_movement_frames, ControlLease, RoboMaster};
+use crate::command::MovementParams;
+use crate::error::RoboMasterError;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Instant;
+use tokio::task::{AbortHandle, JoinHandle};
+
+/// Handle to a movement held by [`RoboMaster::hold`]
+///
+/// The movement is resent until the handle is dropped or released. The
+/// chassis then halts once the robot stops receiving twist refreshes; call
+/// [`RoboMaster::stop`] to stop immediately.
+#[derive(Debug)]
+pub struct HoldHandle {
+    task: JoinHandle<()>,
+}
+
+impl HoldHandle {
+    /// Stop resending the held movement
+    pub fn release(self) {}
+
+    /// Check whether the movement is still being resent
+    pub fn is_active(&self) -> bool {
+        !self.task.is_finished()
+    }
+}
+
+impl Drop for HoldHandle {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Stop resending every movement held by [`RoboMaster::hold`]
+pub(super) fn cancel_holds(active_holds: &Mutex<Vec<AbortHandle>>) {
+    let held = std::mem::take(&mut *lock_holds(active_holds));
+    for task in held {
+        task.abort();
+    }
+}
+
+fn lock_holds(active_holds: &Mutex<Vec<AbortHandle>>) -> MutexGuard<'_, Vec<AbortHandle>> {
+    active_holds.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+impl RoboMaster {
+    /// Keep resending `movement` at the twist refresh rate in a background task
+    ///
+    /// The movement goes through the same orientation, filter, constraint,
+    /// slew and safe-mode stages as [`move_robot`](Self::move_robot) and
+    /// counts as a movement command for the watchdog. With a slew limit set,
+    /// the resent movement ramps from the last one sent to the held one.
+    ///
+    /// A hold replaces the current movement: any earlier hold is cancelled,
+    /// and so is this one by the next movement or hold. Resending also stops
+    /// when the returned handle is dropped, a send fails, or the robot is
+    /// stopped by [`stop`](Self::stop), the watchdog or a tip-over.
+    ///
+    /// Fails with `MovementBlocked` while another controller holds the
+    /// [`ControlLease`], and resending stops once a lease is acquired.
+    pub async fn hold(&mut self, movement: MovementParams) -> Result<HoldHandle, RoboMasterError> {
+        self.hold_checked(None, movement).await
+    }
+
+    /// Keep resending `movement` as the holder of `lease`
+    ///
+    /// Resending stops once the lease is released.
+    pub async fn hold_with_lease(&mut self, lease: &ControlLease, movement: MovementParams) -> Result<HoldHandle, RoboMasterError> {
+        self.hold_checked(Some(lease), movement).await
+    }
+
+    async fn hold_checked(&mut self, lease: Option<&ControlLease>, movement: MovementParams) -> Result<HoldHandle, RoboMasterError> {
+        self.control_leases.check(lease)?;
+        self.check_can_move()?;
+        self.ensure_initialized().await?;
+        cancel_holds(&self.active_holds);
+
+        let command = movement;
+        let filtered = self.shape_movement(movement);
+        let caps = self.output_caps();
+        let mut slew_limiter = self.slew_limiter.clone();
+
+        let can_interface = Arc::clone(&self.can_interface);
+        let command_builder = self.command_builder.clone();
+        let command_counters = Arc::clone(&self.command_counters);
+        let send_limiter = self.send_limiter.clone();
+        let period = self.keepalive.twist_period();
+        let control_leases = self.control_leases.clone();
+        let holder = control_leases.holder();
+
+        let task = tokio::spawn(async move {
+            let mut split_buffer = Vec::new();
+            let mut interval = tokio::time::interval(period);
+            loop {
+                interval.tick().await;
+                if control_leases.holder() != holder {
+                    tracing::warn!("stopped holding movement: the control lease changed hands");
+                    break;
+                }
+                let now = Instant::now();
+                let step = match &mut slew_limiter {
+                    Some(limiter) => limiter.apply(filtered, now),
+                    None => filtered,
+                };
+                let (movement, gimbal) = caps.apply(step, now);
+                let _permit = send_limiter.acquire().await;
+                if let Err(error) = send_movement_frames(&can_interface, &command_builder, &command_counters, &mut split_buffer, movement, gimbal) {
+                    tracing::warn!("stopped holding movement: {}", error);
+                    break;
+                }
+            }
+        });
+        let mut active_holds = lock_holds(&self.active_holds);
+        active_holds.retain(|held| !held.is_finished());
+        active_holds.push(task.abort_handle());
+        drop(active_holds);
+
+        self.feed_watchdog();
+        self.last_command = command;
+        self.last_movement = filtered;
+        self.wheel_speeds = None;
+        if let Some(limiter) = &mut self.slew_limiter {
+            limiter.reset(filtered);
+        }
+        self.last_movement_at = Some(Instant::now());
+        self.fault_detector.commanded(filtered, Instant::now());
+        Ok(HoldHandle { task })
+    }
+
+    /// Rotate in place at `rate` until the returned handle is dropped
+    ///
+    /// The accumulated yaw is reset first, so
+    /// [`accumulated_yaw`](Self::accumulated_yaw) reports the total rotation
+    /// of this run as IMU telemetry is processed.
+    pub async fn rotate_continuous(&mut self, rate: f32) -> Result<HoldHandle, RoboMasterError> {
+        self.reset_accumulated_yaw();
+        self.hold(MovementParams { vz: rate, ..MovementParams::stopped() }).await
+    }
+}
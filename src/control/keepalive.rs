@@ -0,0 +1,170 @@
+//! Keep-alive scheduling for the touch and twist refresh commands
+//!
+//! The S1 drops out of remote control if it stops receiving touch commands,
+//! and it halts the chassis if the twist command is not refreshed. The two
+//! have different cadences, so they are scheduled independently.
+
+use crate::error::RoboMasterError;
+use std::time::{Duration, Instant};
+
+/// Default touch keep-alive frequency in Hz
+pub const DEFAULT_TOUCH_FREQUENCY: u32 = 10;
+
+/// Default twist refresh frequency in Hz
+pub const DEFAULT_TWIST_REFRESH_FREQUENCY: u32 = 20;
+
+/// Keep-alive commands that are due at a given instant
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct KeepaliveDue {
+    /// A touch command should be sent
+    pub touch: bool,
+    /// The last movement should be resent
+    pub twist: bool,
+}
+
+/// Scheduler deciding when touch and twist keep-alives need to be resent
+#[derive(Debug, Clone)]
+pub struct KeepaliveScheduler {
+    touch_period: Duration,
+    twist_period: Duration,
+    last_touch: Option<Instant>,
+    last_twist: Option<Instant>,
+}
+
+impl KeepaliveScheduler {
+    /// Create a new scheduler with the given touch and twist rates in Hz
+    pub fn new(touch_hz: u32, twist_hz: u32) -> Result<Self, RoboMasterError> {
+        Ok(Self {
+            touch_period: period_from_hz("touch_rate", touch_hz)?,
+            twist_period: period_from_hz("twist_refresh_rate", twist_hz)?,
+            last_touch: None,
+            last_twist: None,
+        })
+    }
+
+    /// Set the touch keep-alive rate in Hz
+    pub fn set_touch_rate(&mut self, hz: u32) -> Result<(), RoboMasterError> {
+        self.touch_period = period_from_hz("touch_rate", hz)?;
+        Ok(())
+    }
+
+    /// Set the twist refresh rate in Hz
+    pub fn set_twist_rate(&mut self, hz: u32) -> Result<(), RoboMasterError> {
+        self.twist_period = period_from_hz("twist_refresh_rate", hz)?;
+        Ok(())
+    }
+
+    /// Get the interval between touch commands
+    pub fn touch_period(&self) -> Duration {
+        self.touch_period
+    }
+
+    /// Get the interval between twist refreshes
+    pub fn twist_period(&self) -> Duration {
+        self.twist_period
+    }
+
+    /// Record that a touch command was sent at `now`
+    pub fn mark_touch_sent(&mut self, now: Instant) {
+        self.last_touch = Some(now);
+    }
+
+    /// Record that a twist command was sent at `now`
+    pub fn mark_twist_sent(&mut self, now: Instant) {
+        self.last_twist = Some(now);
+    }
+
+    /// Check which keep-alives are due at `now` and mark them as sent
+    pub fn poll(&mut self, now: Instant) -> KeepaliveDue {
+        let touch = is_due(self.last_touch, self.touch_period, now);
+        let twist = is_due(self.last_twist, self.twist_period, now);
+
+        if touch {
+            self.mark_touch_sent(now);
+        }
+        if twist {
+            self.mark_twist_sent(now);
+        }
+
+        KeepaliveDue { touch, twist }
+    }
+}
+
+impl Default for KeepaliveScheduler {
+    fn default() -> Self {
+        Self {
+            touch_period: Duration::from_secs(1) / DEFAULT_TOUCH_FREQUENCY,
+            twist_period: Duration::from_secs(1) / DEFAULT_TWIST_REFRESH_FREQUENCY,
+            last_touch: None,
+            last_twist: None,
+        }
+    }
+}
+
+pub(super) fn period_from_hz(parameter: &str, hz: u32) -> Result<Duration, RoboMasterError> {
+    if hz == 0 {
+        return Err(RoboMasterError::InvalidParameter {
+            parameter: parameter.to_string(),
+            value: hz.to_string(),
+        });
+    }
+    Ok(Duration::from_secs(1) / hz)
+}
+
+fn is_due(last: Option<Instant>, period: Duration, now: Instant) -> bool {
+    match last {
+        Some(last) => now.saturating_duration_since(last) >= period,
+        None => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keepalive_independent_rates() {
+        let mut scheduler = KeepaliveScheduler::new(5, 20).unwrap();
+        let start = Instant::now();
+
+        let mut touch_count = 0;
+        let mut twist_count = 0;
+
+        // Simulate one second of a 100 Hz control loop
+        for tick in 0..100 {
+            let due = scheduler.poll(start + Duration::from_millis(tick * 10));
+            if due.touch {
+                touch_count += 1;
+            }
+            if due.twist {
+                twist_count += 1;
+            }
+        }
+
+        assert_eq!(touch_count, 5);
+        assert_eq!(twist_count, 20);
+    }
+
+    #[test]
+    fn test_explicit_twist_defers_refresh() {
+        let mut scheduler = KeepaliveScheduler::new(10, 10).unwrap();
+        let start = Instant::now();
+
+        assert!(scheduler.poll(start).twist);
+
+        // A movement sent explicitly pushes the next refresh back
+        scheduler.mark_twist_sent(start + Duration::from_millis(90));
+        assert!(!scheduler.poll(start + Duration::from_millis(100)).twist);
+        assert!(scheduler.poll(start + Duration::from_millis(190)).twist);
+    }
+
+    #[test]
+    fn test_zero_rate_rejected() {
+        assert!(KeepaliveScheduler::new(0, 20).is_err());
+
+        let mut scheduler = KeepaliveScheduler::default();
+        assert!(scheduler.set_twist_rate(0).is_err());
+        assert_eq!(scheduler.touch_period(), Duration::from_millis(100));
+        assert_eq!(scheduler.twist_period(), Duration::from_millis(50));
+    }
+}
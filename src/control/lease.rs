@@ -0,0 +1,127 @@
+//! Exclusive ownership of a robot's movement
+
+use crate::error::ControlError;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+#[derive(Debug, Default)]
+struct LeaseState {
+    holder: Option<u64>,
+    next_id: u64,
+}
+
+/// Hands out at most one [`ControlLease`] at a time
+///
+/// While a lease is held, movement is only accepted from its holder, so a
+/// joystick loop and a script cannot both drive the robot. Clones share the
+/// same lease.
+#[derive(Debug, Clone, Default)]
+pub struct ControlLeases {
+    state: Arc<Mutex<LeaseState>>,
+}
+
+impl ControlLeases {
+    /// Take the lease, failing with `MovementBlocked` if it is already held
+    pub fn acquire(&self) -> Result<ControlLease, ControlError> {
+        let mut state = lock_state(&self.state);
+        if state.holder.is_some() {
+            return Err(ControlError::MovementBlocked {
+                reason: "another controller holds the control lease".to_string(),
+            });
+        }
+        let id = state.next_id;
+        state.next_id = state.next_id.wrapping_add(1);
+        state.holder = Some(id);
+        Ok(ControlLease {
+            id,
+            state: Arc::clone(&self.state),
+        })
+    }
+
+    /// Check whether a lease is currently held
+    pub fn is_held(&self) -> bool {
+        lock_state(&self.state).holder.is_some()
+    }
+
+    /// Get the id of the current lease, if one is held
+    ///
+    /// Lets a background sender that passed [`check`](Self::check) notice
+    /// when the lease it ran under is released or taken.
+    pub(crate) fn holder(&self) -> Option<u64> {
+        lock_state(&self.state).holder
+    }
+
+    /// Check that a sender presenting `lease` may move the robot
+    ///
+    /// Anyone may move while no lease is held. Otherwise only the current
+    /// lease from this robot is accepted.
+    pub fn check(&self, lease: Option<&ControlLease>) -> Result<(), ControlError> {
+        let holder = lock_state(&self.state).holder;
+        let allowed = match (holder, lease) {
+            (None, _) => true,
+            (Some(id), Some(lease)) => lease.id == id && Arc::ptr_eq(&lease.state, &self.state),
+            (Some(_), None) => false,
+        };
+        if allowed {
+            Ok(())
+        } else {
+            Err(ControlError::MovementBlocked {
+                reason: "another controller holds the control lease".to_string(),
+            })
+        }
+    }
+}
+
+/// Exclusive right to move a robot, released when dropped
+#[derive(Debug)]
+pub struct ControlLease {
+    id: u64,
+    state: Arc<Mutex<LeaseState>>,
+}
+
+impl ControlLease {
+    /// Give up control so another controller can acquire it
+    pub fn release(self) {}
+}
+
+impl Drop for ControlLease {
+    fn drop(&mut self) {
+        let mut state = lock_state(&self.state);
+        if state.holder == Some(self.id) {
+            state.holder = None;
+        }
+    }
+}
+
+fn lock_state(state: &Mutex<LeaseState>) -> MutexGuard<'_, LeaseState> {
+    state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_second_acquirer_is_rejected_until_release() {
+        let leases = ControlLeases::default();
+        let lease = leases.acquire().unwrap();
+
+        assert!(matches!(leases.acquire(), Err(ControlError::MovementBlocked { .. })));
+        assert!(leases.check(Some(&lease)).is_ok());
+        assert!(leases.check(None).is_err());
+
+        lease.release();
+        assert!(!leases.is_held());
+        assert!(leases.check(None).is_ok());
+        assert!(leases.acquire().is_ok());
+    }
+
+    #[test]
+    fn test_lease_from_another_robot_is_rejected() {
+        let ours = ControlLeases::default();
+        let theirs = ControlLeases::default();
+        let _held = ours.acquire().unwrap();
+        let foreign = theirs.acquire().unwrap();
+
+        assert!(ours.check(Some(&foreign)).is_err());
+    }
+}
@@ -0,0 +1,82 @@
+//! Dropping LED animation frames when the bus is busy
+
+/// Default bus headroom below which LED animation frames are dropped
+pub const DEFAULT_LED_MIN_HEADROOM: f32 = 0.3;
+
+/// Decides whether an LED animation frame may use the bus
+///
+/// Control commands always go out; LED frames are only sent while the
+/// remaining bus headroom stays above the configured minimum.
+#[derive(Debug, Clone)]
+pub struct LedThrottle {
+    min_headroom: f32,
+    skipped: u64,
+}
+
+impl LedThrottle {
+    /// Create a throttle requiring `min_headroom` (0.0 to 1.0) for LED frames
+    pub fn new(min_headroom: f32) -> Self {
+        Self {
+            min_headroom: min_headroom.clamp(0.0, 1.0),
+            skipped: 0,
+        }
+    }
+
+    /// Set the minimum headroom (0.0 to 1.0) required for LED frames
+    pub fn set_min_headroom(&mut self, min_headroom: f32) {
+        self.min_headroom = min_headroom.clamp(0.0, 1.0);
+    }
+
+    /// Get the minimum headroom required for LED frames
+    pub fn min_headroom(&self) -> f32 {
+        self.min_headroom
+    }
+
+    /// Check whether an LED frame may be sent with the given bus headroom
+    pub fn allow(&mut self, headroom: f32) -> bool {
+        if headroom < self.min_headroom {
+            self.skipped += 1;
+            return false;
+        }
+        true
+    }
+
+    /// Number of LED frames dropped so far
+    pub fn skipped(&self) -> u64 {
+        self.skipped
+    }
+}
+
+impl Default for LedThrottle {
+    fn default() -> Self {
+        Self::new(DEFAULT_LED_MIN_HEADROOM)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::can::BusLoadEstimator;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_led_frames_throttled_under_control_traffic() {
+        let start = Instant::now();
+        let mut bus = BusLoadEstimator::new(1_000_000, Duration::from_millis(100));
+        let mut throttle = LedThrottle::default();
+
+        assert!(throttle.allow(bus.headroom(start)));
+
+        // Heavy control traffic: 700 full frames within the window
+        for i in 0..700 {
+            bus.record_frame(start + Duration::from_micros(i * 100), 8);
+        }
+        let now = start + Duration::from_millis(70);
+        assert!(!throttle.allow(bus.headroom(now)));
+        assert!(!throttle.allow(bus.headroom(now)));
+        assert_eq!(throttle.skipped(), 2);
+
+        // Once the traffic leaves the window LED frames flow again
+        assert!(throttle.allow(bus.headroom(now + Duration::from_millis(200))));
+    }
+}
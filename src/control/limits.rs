@@ -0,0 +1,92 @@
+//! Physical speed calibration for motion planning
+
+use crate::command::MovementParams;
+use std::time::Duration;
+
+/// Speeds the robot reaches at full command (1.0) on each axis
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RobotLimits {
+    /// Forward speed at `vx = 1.0` in m/s
+    pub max_forward_speed: f32,
+    /// Sideways speed at `vy = 1.0` in m/s
+    pub max_strafe_speed: f32,
+    /// Rotation rate at `vz = 1.0` in rad/s
+    pub max_rotation_speed: f32,
+}
+
+impl Default for RobotLimits {
+    /// Published S1 chassis limits: 3.5 m/s forward, 2.8 m/s sideways, 600°/s
+    fn default() -> Self {
+        Self {
+            max_forward_speed: 3.5,
+            max_strafe_speed: 2.8,
+            max_rotation_speed: 600f32.to_radians(),
+        }
+    }
+}
+
+impl RobotLimits {
+    /// Estimate the displacement after holding `movement` for `duration`
+    ///
+    /// Returns `(dx, dy, dtheta)` in metres and radians, in the frame the
+    /// robot had when the movement started. Acceleration and wheel slip are
+    /// ignored, so the estimate is only as good as the calibration.
+    pub fn estimate_displacement(&self, movement: &MovementParams, duration: Duration) -> (f32, f32, f32) {
+        let t = duration.as_secs_f32();
+        let vx = movement.vx.clamp(-crate::MAX_SPEED, crate::MAX_SPEED) * self.max_forward_speed;
+        let vy = movement.vy.clamp(-crate::MAX_SPEED, crate::MAX_SPEED) * self.max_strafe_speed;
+        let omega = movement.vz.clamp(-crate::MAX_SPEED, crate::MAX_SPEED) * self.max_rotation_speed;
+        let dtheta = omega * t;
+
+        if omega == 0.0 {
+            return (vx * t, vy * t, 0.0);
+        }
+
+        // Integrate the body-frame velocity while the heading turns at omega
+        let along = dtheta.sin() / omega;
+        let across = (1.0 - dtheta.cos()) / omega;
+        (vx * along - vy * across, vx * across + vy * along, dtheta)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIMITS: RobotLimits = RobotLimits {
+        max_forward_speed: 2.0,
+        max_strafe_speed: 1.0,
+        max_rotation_speed: std::f32::consts::PI,
+    };
+
+    #[test]
+    fn test_straight_drive() {
+        let movement = MovementParams { vx: 0.5, vy: 0.0, vz: 0.0 };
+        let (dx, dy, dtheta) = LIMITS.estimate_displacement(&movement, Duration::from_secs(3));
+
+        assert!((dx - 3.0).abs() < 1e-6);
+        assert_eq!(dy, 0.0);
+        assert_eq!(dtheta, 0.0);
+    }
+
+    #[test]
+    fn test_rotation_in_place() {
+        let movement = MovementParams { vx: 0.0, vy: 0.0, vz: 0.5 };
+        let (dx, dy, dtheta) = LIMITS.estimate_displacement(&movement, Duration::from_secs(1));
+
+        assert_eq!((dx, dy), (0.0, 0.0));
+        assert!((dtheta - std::f32::consts::FRAC_PI_2).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_half_turn_arc_ends_beside_start() {
+        // Half a turn while driving forward traces a semicircle
+        let movement = MovementParams { vx: 1.0, vy: 0.0, vz: 1.0 };
+        let (dx, dy, dtheta) = LIMITS.estimate_displacement(&movement, Duration::from_secs(1));
+
+        let radius = LIMITS.max_forward_speed / LIMITS.max_rotation_speed;
+        assert!(dx.abs() < 1e-5);
+        assert!((dy - 2.0 * radius).abs() < 1e-5);
+        assert!((dtheta - std::f32::consts::PI).abs() < 1e-6);
+    }
+}
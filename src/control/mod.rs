@@ -1,32 +1,221 @@
 /// Control system module for RoboMaster robot
 /// This module provides high-level control APIs
 
-use crate::can::{CanInterface, CommandCounters, MessageSplitter};
-use crate::command::{CommandBuilder, MovementParams, GimbalParams, LedColor};
-use crate::error::RoboMasterError;
+pub mod blaster;
+pub mod blocking;
+pub mod boot;
+pub mod builder;
+pub mod channel;
+pub mod config;
+pub mod constraint;
+pub mod control_loop;
+pub mod counter_sync;
+pub mod demo;
+pub mod echo;
+pub mod fault;
+pub mod field;
+pub mod gimbal_limits;
+pub mod heartbeat;
+pub mod hold;
+pub mod filter;
+pub mod keepalive;
+pub mod lease;
+pub mod led_throttle;
+pub mod limits;
+pub mod odometry;
+pub mod overrun;
+pub mod profile;
+pub mod ramp;
+pub mod receive_policy;
+pub mod safe_mode;
+pub mod send_limit;
+pub mod settings;
+pub mod signal;
+pub mod slew;
+pub mod stop;
+pub mod stream;
+pub mod thermal;
+pub mod watchdog;
+pub mod yaw;
+
+use crate::can::bus_load::{max_cycle_rate, COMMAND_BUS_SHARE};
+use crate::can::{echo_counter_on, standard_id, AutoReconnect, BusState, CanInterface, CommandCounters, CommandHook, ConnectionState, MessageSplitter, TimestampedFrame, DEFAULT_CAN_TIMEOUT};
+use crate::command::{check_blaster_burst, verify_message, CommandBuilder, MovementParams, GimbalParams, LedColor, LedEffect, LedZone, LED_SEGMENT_COUNT, TwistEnable, ChassisMode, WheelSpeeds, MAX_WHEEL_RPM, MIN_GIMBAL_PITCH, MAX_GIMBAL_PITCH, MAX_GIMBAL_YAW};
+use crate::error::{RoboMasterError, ControlError, ProtocolError};
+use crate::telemetry::{self, BuiltinDecoder, TelemetryCsv, TelemetryDecoder, TelemetryKind, TelemetryLog, TelemetryReceiver};
 use anyhow::Result;
+use socketcan::{CanFrame, EmbeddedFrame};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
+use tokio::task::AbortHandle;
+
+pub use blaster::{BlasterStatus, FireRateLimiter};
+pub use builder::RoboMasterBuilder;
+pub use channel::{RobotCommand, DEFAULT_COMMAND_CHANNEL_CAPACITY};
+pub use config::RoboMasterConfig;
+pub use constraint::MotionConstraint;
+pub use control_loop::ControlLoop;
+pub use counter_sync::CounterSync;
+pub use demo::{DemoKind, DemoStep};
+pub use echo::EchoWatch;
+pub use fault::{FaultDetector, FaultKind};
+pub use field::field_to_robot;
+pub use gimbal_limits::GimbalLimits;
+pub use heartbeat::HeartbeatHandle;
+pub use hold::HoldHandle;
+pub use filter::MovementFilter;
+pub use keepalive::{KeepaliveDue, KeepaliveScheduler};
+pub use lease::{ControlLease, ControlLeases};
+pub use led_throttle::LedThrottle;
+pub use limits::RobotLimits;
+pub use odometry::{Odometry, Pose};
+pub use overrun::OverrunDetector;
+pub use profile::{AccelProfile, ProfiledDrive};
+pub use ramp::VelocityRamp;
+pub use receive_policy::{ReceiveErrorAction, ReceiveErrorPolicy};
+pub use safe_mode::SafeMode;
+pub use send_limit::SendLimiter;
+pub use settings::RobotSettings;
+pub use signal::termination_signal;
+pub use slew::SlewLimiter;
+pub use stop::StopMode;
+pub use stream::{TelemetryStream, DEFAULT_TELEMETRY_STREAM_CAPACITY};
+pub use thermal::ThermalLimit;
+pub use watchdog::Watchdog;
+pub use yaw::YawTracker;
+
+/// Maximum number of frames processed by one [`RoboMaster::poll_sensors`] call
+pub const MAX_POLL_FRAMES: usize = 64;
+
+/// Current (A) a wheel must draw before [`SensorData::wheel_anomaly`] flags it
+pub const WHEEL_ANOMALY_MIN_CURRENT: f32 = 0.5;
+
+/// Factor by which a flagged wheel's current exceeds the average of the others
+pub const WHEEL_ANOMALY_RATIO: f32 = 2.0;
+
+/// Upper bound of [`RoboMaster::recommended_command_rate`] in Hz
+pub const MAX_RECOMMENDED_COMMAND_RATE: u32 = 1000;
+
+/// LED color shown by [`RoboMaster::recover_to_idle`]
+pub const IDLE_LED_COLOR: LedColor = LedColor { red: 255, green: 255, blue: 255 };
+
+/// Interval between colors sent by [`RoboMaster::fade_led`]
+pub const LED_UPDATE_PERIOD: Duration = Duration::from_millis(50);
+
+/// Number of white flashes shown by [`RoboMaster::identify`]
+pub const IDENTIFY_FLASHES: u32 = 3;
+
+/// How long each identify flash stays on, and the gap after it
+pub const IDENTIFY_FLASH_PERIOD: Duration = Duration::from_millis(200);
+
+/// How long [`RoboMaster::serial_number`] waits for the robot to answer
+pub const SERIAL_NUMBER_TIMEOUT: Duration = Duration::from_secs(1);
 
 /// High-level RoboMaster robot controller
 pub struct RoboMaster {
-    can_interface: CanInterface,
+    can_interface: Arc<CanInterface>,
     command_builder: CommandBuilder,
-    command_counters: CommandCounters,
+    command_counters: Arc<Mutex<CommandCounters>>,
     is_initialized: bool,
+    boot_repeats: u32,
+    keepalive: KeepaliveScheduler,
+    last_movement: MovementParams,
+    last_command: MovementParams,
+    last_movement_at: Option<Instant>,
+    wheel_speeds: Option<WheelSpeeds>,
+    overrun: OverrunDetector,
+    echo_watch: EchoWatch,
+    counter_sync: CounterSync,
+    movement_filter: MovementFilter,
+    motion_constraint: MotionConstraint,
+    slew_limiter: Option<SlewLimiter>,
+    orientation_flipped: bool,
+    stop_mode: StopMode,
+    safe_mode: SafeMode,
+    speed_limit: f32,
+    thermal_limit: ThermalLimit,
+    watchdog: Option<Watchdog>,
+    active_holds: Arc<Mutex<Vec<AbortHandle>>>,
+    gimbal_follow: bool,
+    failsafe_timeout: Option<Duration>,
+    receive_error_policy: ReceiveErrorPolicy,
+    led_brightness: f32,
+    led_throttle: LedThrottle,
+    send_limiter: SendLimiter,
+    split_buffer: Vec<Vec<u8>>,
+    fire_rate: FireRateLimiter,
+    telemetry_decoder: Option<Arc<dyn TelemetryDecoder>>,
+    sensor_data: SensorData,
+    yaw_tracker: YawTracker,
+    odometry: Odometry,
+    odometry_updated_at: Option<Instant>,
+    telemetry_receiver: TelemetryReceiver,
+    telemetry_csv: Option<TelemetryCsv>,
+    control_leases: ControlLeases,
+    fault_detector: FaultDetector,
+    shutdown_signal: watch::Sender<bool>,
+    receive_timeout: Duration,
 }
 
 impl RoboMaster {
     /// Create a new RoboMaster controller
     pub async fn new(interface_name: &str) -> Result<Self, RoboMasterError> {
-        let can_interface = CanInterface::new(interface_name)?;
+        Ok(Self::with_interface(CanInterface::new(interface_name)?))
+    }
+
+    /// Create a controller on an already opened CAN interface
+    ///
+    /// Combined with [`CanInterface::with_backend`] this allows running the
+    /// controller against a [`MockCanBackend`](crate::can::MockCanBackend).
+    pub fn with_interface(can_interface: CanInterface) -> Self {
         let command_builder = CommandBuilder::new();
-        let command_counters = CommandCounters::default();
+        let command_counters = Arc::new(Mutex::new(CommandCounters::default()));
 
-        Ok(Self {
-            can_interface,
+        Self {
+            can_interface: Arc::new(can_interface),
             command_builder,
             command_counters,
             is_initialized: false,
-        })
+            boot_repeats: boot::DEFAULT_BOOT_REPEATS,
+            keepalive: KeepaliveScheduler::default(),
+            last_movement: MovementParams::stopped(),
+            last_command: MovementParams::stopped(),
+            last_movement_at: None,
+            wheel_speeds: None,
+            overrun: OverrunDetector::new(),
+            echo_watch: EchoWatch::default(),
+            counter_sync: CounterSync::default(),
+            movement_filter: MovementFilter::new(),
+            motion_constraint: MotionConstraint::default(),
+            slew_limiter: None,
+            orientation_flipped: false,
+            stop_mode: StopMode::default(),
+            safe_mode: SafeMode::default(),
+            speed_limit: crate::MAX_SPEED,
+            thermal_limit: ThermalLimit::default(),
+            watchdog: None,
+            active_holds: Arc::new(Mutex::new(Vec::new())),
+            gimbal_follow: true,
+            failsafe_timeout: None,
+            receive_error_policy: ReceiveErrorPolicy::default(),
+            led_brightness: 1.0,
+            led_throttle: LedThrottle::default(),
+            send_limiter: SendLimiter::default(),
+            split_buffer: Vec::new(),
+            fire_rate: FireRateLimiter::default(),
+            telemetry_decoder: None,
+            sensor_data: SensorData::default(),
+            yaw_tracker: YawTracker::new(),
+            odometry: Odometry::default(),
+            odometry_updated_at: None,
+            telemetry_receiver: TelemetryReceiver::new(),
+            telemetry_csv: None,
+            control_leases: ControlLeases::default(),
+            fault_detector: FaultDetector::default(),
+            shutdown_signal: watch::channel(false).0,
+            receive_timeout: DEFAULT_CAN_TIMEOUT,
+        }
     }
 
     /// Initialize the robot (boot sequence)
@@ -38,16 +227,47 @@ impl RoboMaster {
         println!("Initializing RoboMaster...");
         let boot_command = self.command_builder.build_boot_sequence()?;
         let can_messages = MessageSplitter::split_command(&boot_command);
-        self.can_interface.send_messages(&can_messages)?;
+        boot::send_repeated(self.boot_repeats, boot::BOOT_REPEAT_DELAY, || {
+            self.can_interface.send_messages(&can_messages)
+        })
+        .await?;
         
         // Wait for initialization to complete
-        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+        tokio::time::sleep(boot::BOOT_SETTLE_DELAY).await;
         
         self.is_initialized = true;
         println!("RoboMaster initialized successfully");
         Ok(())
     }
 
+    /// Set how many times `initialize` sends the boot sequence (at least once)
+    ///
+    /// Some units only accept commands after the boot sequence has been
+    /// sent two or three times.
+    pub fn set_boot_repeats(&mut self, repeats: u32) {
+        self.boot_repeats = repeats.max(1);
+    }
+
+    /// Get how many times the boot sequence is sent
+    pub fn boot_repeats(&self) -> u32 {
+        self.boot_repeats
+    }
+
+    /// Check whether the boot sequence has been sent
+    pub fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+
+    /// Set how long to wait for each received frame
+    pub fn set_receive_timeout(&mut self, timeout: Duration) {
+        self.receive_timeout = timeout;
+    }
+
+    /// Get how long to wait for each received frame
+    pub fn receive_timeout(&self) -> Duration {
+        self.receive_timeout
+    }
+
     /// Ensure the robot is initialized before executing commands
     async fn ensure_initialized(&mut self) -> Result<(), RoboMasterError> {
         if !self.is_initialized {
@@ -57,81 +277,1344 @@ impl RoboMaster {
     }
 
     /// Move the robot with specified parameters
+    ///
+    /// If a minimum axis change is set, movements that differ from the last
+    /// one sent by less than it are not resent.
+    ///
+    /// Fails with `MovementBlocked` while another controller holds the
+    /// [`ControlLease`]; the holder moves with
+    /// [`move_robot_with_lease`](Self::move_robot_with_lease) instead.
     pub async fn move_robot(&mut self, movement: MovementParams) -> Result<(), RoboMasterError> {
+        self.control_leases.check(None)?;
+        self.move_robot_unleased(movement).await
+    }
+
+    /// Move the robot as the holder of `lease`
+    pub async fn move_robot_with_lease(&mut self, lease: &ControlLease, movement: MovementParams) -> Result<(), RoboMasterError> {
+        self.control_leases.check(Some(lease))?;
+        self.move_robot_unleased(movement).await
+    }
+
+    /// Take exclusive control of movement until the lease is dropped
+    ///
+    /// Fails with `MovementBlocked` if another controller already holds it.
+    /// Stopping is never blocked.
+    pub fn acquire_control(&self) -> Result<ControlLease, RoboMasterError> {
+        Ok(self.control_leases.acquire()?)
+    }
+
+    /// Check whether a controller holds the movement lease
+    pub fn control_leased(&self) -> bool {
+        self.control_leases.is_held()
+    }
+
+    async fn move_robot_unleased(&mut self, movement: MovementParams) -> Result<(), RoboMasterError> {
+        self.check_can_move()?;
         self.ensure_initialized().await?;
-        
-        // Build twist command
-        let twist_cmd = self.command_builder.build_twist_command(movement, &self.command_counters)?;
-        let twist_messages = MessageSplitter::split_command(&twist_cmd);
-
-        // Build gimbal command (use rotation from movement for gimbal yaw)
-        let gimbal_params = GimbalParams {
-            ry: 0.0,
-            rz: movement.vz,
+
+        let _permit = self.send_limiter.acquire().await;
+        self.feed_and_commit_movement(movement)
+    }
+
+    /// Fail with `MovementBlocked` after a tip-over, cancelling any holds
+    fn check_can_move(&self) -> Result<(), RoboMasterError> {
+        let result = self.check_not_tipped_over();
+        if result.is_err() {
+            hold::cancel_holds(&self.active_holds);
+        }
+        result
+    }
+
+    /// Feed the watchdog and commit a movement
+    ///
+    /// Shared by the async and blocking movement paths so they stay in step.
+    /// The caller must hold a send permit.
+    fn feed_and_commit_movement(&mut self, movement: MovementParams) -> Result<(), RoboMasterError> {
+        self.feed_watchdog();
+        self.commit_movement(movement)
+    }
+
+    /// Record a movement command with the watchdog and notice any stop it sent
+    fn feed_watchdog(&mut self) {
+        if let Some(watchdog) = &self.watchdog {
+            watchdog.feed();
+        }
+        self.observe_watchdog_stop();
+    }
+
+    /// Treat a stop sent by the watchdog as the last movement sent
+    fn observe_watchdog_stop(&mut self) {
+        if !matches!(&self.watchdog, Some(watchdog) if watchdog.take_tripped()) {
+            return;
+        }
+        self.last_movement = MovementParams::stopped();
+        self.wheel_speeds = None;
+        if let Some(limiter) = &mut self.slew_limiter {
+            limiter.reset(MovementParams::stopped());
+        }
+    }
+
+    /// Shape a movement and send it if it differs enough from the last one sent
+    ///
+    /// The new movement replaces any held one, so active holds are
+    /// cancelled. The caller must hold a send permit.
+    fn commit_movement(&mut self, movement: MovementParams) -> Result<(), RoboMasterError> {
+        hold::cancel_holds(&self.active_holds);
+        let command = movement;
+        let mut movement = self.shape_movement(movement);
+        if let Some(limiter) = &mut self.slew_limiter {
+            movement = limiter.apply(movement, Instant::now());
+        }
+        let replaces_wheels = self.wheel_speeds.take().is_some();
+        let significant = self.last_movement_at.is_none()
+            || replaces_wheels
+            || self.movement_filter.is_significant_change(self.last_movement, movement);
+        if significant {
+            self.send_movement_now(movement)?;
+            self.last_movement = movement;
+            self.fault_detector.commanded(movement, Instant::now());
+        }
+
+        self.last_command = command;
+        self.last_movement_at = Some(Instant::now());
+        Ok(())
+    }
+
+    /// Apply the orientation, movement filter and motion constraint to a commanded movement
+    fn shape_movement(&self, movement: MovementParams) -> MovementParams {
+        let movement = if self.orientation_flipped {
+            MovementParams { vx: -movement.vx, vy: -movement.vy, vz: movement.vz }
+        } else {
+            movement
         };
-        let gimbal_cmd = self.command_builder.build_gimbal_command(gimbal_params, &self.command_counters)?;
-        let gimbal_messages = MessageSplitter::split_command(&gimbal_cmd);
+        self.motion_constraint.apply(self.movement_filter.apply(movement))
+    }
+
+    /// Drive with the robot's rear as its front
+    ///
+    /// When flipped, forward/back and left/right are negated for every
+    /// movement. Rotation is unchanged: turning the frame around does not
+    /// change which way the chassis spins seen from above.
+    pub fn set_orientation(&mut self, flipped: bool) {
+        self.orientation_flipped = flipped;
+    }
+
+    /// Check whether the control orientation is flipped
+    pub fn orientation_flipped(&self) -> bool {
+        self.orientation_flipped
+    }
+
+    /// Only resend movements when an axis changes by more than `threshold` (0.0 to 1.0)
+    pub fn set_min_axis_change(&mut self, threshold: f32) {
+        self.movement_filter.set_min_axis_change(threshold);
+    }
+
+    /// Raise any nonzero axis below `threshold` (0.0 to 1.0) up to it, keeping its sign
+    ///
+    /// Overcomes static friction so that small stick inputs still move the
+    /// robot. Axes zeroed by the deadzone stay at rest.
+    pub fn set_min_effective_speed(&mut self, threshold: f32) {
+        self.movement_filter.set_min_effective_speed(threshold);
+    }
+
+    /// Rotate the gimbal, applying the safe-mode ceiling
+    ///
+    /// Rates that would drive an axis further into a hardware limit reported
+    /// by gimbal telemetry are zeroed.
+    pub async fn move_gimbal(&mut self, gimbal: GimbalParams) -> Result<(), RoboMasterError> {
+        self.ensure_initialized().await?;
+
+        let gimbal = self.sensor_data.gimbal.limits.restrict(self.safe_mode.cap_gimbal(gimbal));
+        let _permit = self.send_limiter.acquire().await;
+        let mut counters = lock_counters(&self.command_counters);
+        let gimbal_cmd = self.command_builder.build_gimbal_command(gimbal, &counters)?;
+        send_split(&self.can_interface, &mut self.split_buffer, &gimbal_cmd)?;
+        counters.gimbal = counters.gimbal.wrapping_add(1);
+        Ok(())
+    }
+
+    /// Command each mecanum wheel directly, in RPM
+    ///
+    /// Bypasses the holonomic mixing of [`move_robot`](Self::move_robot), for
+    /// custom kinematics. Each wheel is clamped to
+    /// ±[`MAX_WHEEL_RPM`](crate::command::MAX_WHEEL_RPM) scaled by the speed
+    /// limit, the safe-mode ceiling and any thermal cooldown, and counts
+    /// towards the thermal duty cycle.
+    ///
+    /// The wheel speeds replace the last movement: active holds are
+    /// cancelled, the watchdog is fed, and
+    /// [`service_keepalive`](Self::service_keepalive) resends the wheel
+    /// command instead of a twist until the next movement or stop.
+    pub async fn set_wheel_speeds(&mut self, speeds: WheelSpeeds) -> Result<(), RoboMasterError> {
+        self.set_wheel_speeds_checked(None, speeds).await
+    }
+
+    /// Command each mecanum wheel directly as the holder of `lease`
+    pub async fn set_wheel_speeds_with_lease(&mut self, lease: &ControlLease, speeds: WheelSpeeds) -> Result<(), RoboMasterError> {
+        self.set_wheel_speeds_checked(Some(lease), speeds).await
+    }
+
+    async fn set_wheel_speeds_checked(&mut self, lease: Option<&ControlLease>, speeds: WheelSpeeds) -> Result<(), RoboMasterError> {
+        self.control_leases.check(lease)?;
+        self.check_can_move()?;
+        self.ensure_initialized().await?;
+
+        hold::cancel_holds(&self.active_holds);
+        let _permit = self.send_limiter.acquire().await;
+        self.feed_watchdog();
+
+        let speeds = self.cap_wheel_speeds(speeds, Instant::now());
+        self.send_wheel_speeds_now(speeds)?;
+        self.wheel_speeds = Some(speeds);
+        self.last_command = MovementParams::stopped();
+        self.last_movement = MovementParams::stopped();
+        self.last_movement_at = Some(Instant::now());
+        if let Some(limiter) = &mut self.slew_limiter {
+            limiter.reset(MovementParams::stopped());
+        }
+        Ok(())
+    }
+
+    /// Cap wheel speeds by the speed limit, safe-mode ceiling and thermal cooldown
+    fn cap_wheel_speeds(&self, speeds: WheelSpeeds, now: Instant) -> WheelSpeeds {
+        let ceiling = [self.safe_mode.ceiling(), self.thermal_limit.ceiling(now)]
+            .into_iter()
+            .flatten()
+            .fold(self.speed_limit, f32::min);
+        speeds.capped(ceiling * MAX_WHEEL_RPM)
+    }
+
+    /// Send already capped wheel speeds while holding a send permit
+    fn send_wheel_speeds_now(&mut self, speeds: WheelSpeeds) -> Result<(), RoboMasterError> {
+        let now = Instant::now();
+        self.thermal_limit.record_speed(speeds.max_abs() / MAX_WHEEL_RPM, now);
+        let mut counters = lock_counters(&self.command_counters);
+        let wheel_cmd = self.command_builder.build_wheel_command(speeds, &counters)?;
+        send_split(&self.can_interface, &mut self.split_buffer, &wheel_cmd)?;
+        counters.joy = counters.joy.wrapping_add(1);
+        drop(counters);
+        self.keepalive.mark_twist_sent(now);
+        Ok(())
+    }
 
-        // Send commands
-        self.can_interface.send_messages(&twist_messages)?;
-        self.can_interface.send_messages(&gimbal_messages)?;
+    /// Point the gimbal at an absolute pitch and yaw in degrees and hold it there
+    ///
+    /// Fails with `AngleOutOfRange` if pitch is outside
+    /// [`MIN_GIMBAL_PITCH`](crate::command::MIN_GIMBAL_PITCH) to
+    /// [`MAX_GIMBAL_PITCH`](crate::command::MAX_GIMBAL_PITCH) or yaw exceeds
+    /// ±[`MAX_GIMBAL_YAW`](crate::command::MAX_GIMBAL_YAW).
+    ///
+    /// An axis that gimbal telemetry reports at a hardware limit is not
+    /// pointed further into it; it holds its current angle instead.
+    pub async fn set_gimbal_angle(&mut self, pitch: f32, yaw: f32) -> Result<(), RoboMasterError> {
+        check_angle("pitch", pitch, MIN_GIMBAL_PITCH, MAX_GIMBAL_PITCH)?;
+        check_angle("yaw", yaw, -MAX_GIMBAL_YAW, MAX_GIMBAL_YAW)?;
+        self.ensure_initialized().await?;
 
-        // Update counters
-        self.command_counters.joy = self.command_counters.joy.wrapping_add(1);
-        self.command_counters.gimbal = self.command_counters.gimbal.wrapping_add(1);
+        let gimbal = self.sensor_data.gimbal;
+        let current = (gimbal.pitch.to_degrees(), gimbal.yaw.to_degrees());
+        let (pitch, yaw) = gimbal.limits.restrict_angles(current, (pitch, yaw));
 
+        let _permit = self.send_limiter.acquire().await;
+        let mut counters = lock_counters(&self.command_counters);
+        let gimbal_cmd = self.command_builder.build_gimbal_position_command(pitch, yaw, &counters)?;
+        send_split(&self.can_interface, &mut self.split_buffer, &gimbal_cmd)?;
+        counters.gimbal = counters.gimbal.wrapping_add(1);
         Ok(())
     }
 
+    /// Move using a field-relative translation
+    ///
+    /// The field vector is rotated into the robot frame using the IMU yaw
+    /// from processed telemetry. Fails with `SensorUnavailable` until the
+    /// first IMU push arrives or while the IMU reports a fault.
+    pub async fn move_field_relative(&mut self, field_vx: f32, field_vy: f32, vz: f32) -> Result<(), RoboMasterError> {
+        let yaw = self.sensor_data.yaw()?;
+        self.move_robot(field_to_robot(field_vx, field_vy, vz, yaw)).await
+    }
+
+    /// Estimate the `(dx, dy, dtheta)` covered by holding `movement` for `duration`
+    ///
+    /// See [`RobotLimits::estimate_displacement`] for the model used.
+    pub fn estimate_distance(movement: &MovementParams, duration: Duration, limits: &RobotLimits) -> (f32, f32, f32) {
+        limits.estimate_displacement(movement, duration)
+    }
+
+    /// Ramp the commanded velocity to `target` and resolve once it is reached
+    ///
+    /// `accel` is in speed units per second. One step is sent per twist
+    /// refresh period, starting from the last commanded movement.
+    pub async fn ramp_to(&mut self, target: MovementParams, accel: f32) -> Result<(), RoboMasterError> {
+        self.ramp_to_checked(None, target, accel).await
+    }
+
+    /// Ramp the commanded velocity to `target` as the holder of `lease`
+    pub async fn ramp_to_with_lease(&mut self, lease: &ControlLease, target: MovementParams, accel: f32) -> Result<(), RoboMasterError> {
+        self.ramp_to_checked(Some(lease), target, accel).await
+    }
+
+    async fn ramp_to_checked(&mut self, lease: Option<&ControlLease>, target: MovementParams, accel: f32) -> Result<(), RoboMasterError> {
+        self.control_leases.check(lease)?;
+        let period = self.keepalive.twist_period();
+        let ramp = VelocityRamp::new(self.last_command, target, accel, period)?;
+
+        let mut interval = tokio::time::interval(period);
+        for step in ramp {
+            interval.tick().await;
+            self.control_leases.check(lease)?;
+            self.move_robot_unleased(step).await?;
+        }
+        Ok(())
+    }
+
+    /// Drive at `movement` for `duration`, shaping the start and end with `profile`
+    ///
+    /// The velocity ramps up from zero over `ramp_time`, holds and ramps
+    /// back down so the robot is stopped when this resolves. One step is
+    /// sent per twist refresh period.
+    pub async fn drive_for(
+        &mut self,
+        movement: MovementParams,
+        duration: Duration,
+        ramp_time: Duration,
+        profile: AccelProfile,
+    ) -> Result<(), RoboMasterError> {
+        self.drive_for_checked(None, movement, duration, ramp_time, profile).await
+    }
+
+    /// Drive at `movement` for `duration` as the holder of `lease`
+    pub async fn drive_for_with_lease(
+        &mut self,
+        lease: &ControlLease,
+        movement: MovementParams,
+        duration: Duration,
+        ramp_time: Duration,
+        profile: AccelProfile,
+    ) -> Result<(), RoboMasterError> {
+        self.drive_for_checked(Some(lease), movement, duration, ramp_time, profile).await
+    }
+
+    async fn drive_for_checked(
+        &mut self,
+        lease: Option<&ControlLease>,
+        movement: MovementParams,
+        duration: Duration,
+        ramp_time: Duration,
+        profile: AccelProfile,
+    ) -> Result<(), RoboMasterError> {
+        self.control_leases.check(lease)?;
+        let period = self.keepalive.twist_period();
+        let drive = ProfiledDrive::new(movement, duration, ramp_time, profile, period)?;
+
+        let mut interval = tokio::time::interval(period);
+        for step in drive {
+            interval.tick().await;
+            self.control_leases.check(lease)?;
+            self.move_robot_unleased(step).await?;
+        }
+        Ok(())
+    }
+
+    /// Encode and send an already filtered movement, applying the safe-mode ceiling
+    async fn send_movement(&mut self, movement: MovementParams) -> Result<(), RoboMasterError> {
+        let _permit = self.send_limiter.acquire().await;
+        self.send_movement_now(movement)
+    }
+
+    /// Send an already filtered movement while holding a send permit
+    fn send_movement_now(&mut self, movement: MovementParams) -> Result<(), RoboMasterError> {
+        let (movement, gimbal) = self.output_for(movement);
+        self.advance_odometry(Instant::now());
+        self.odometry.set_velocity(movement);
+        self.thermal_limit.record(movement, Instant::now());
+
+        let send_started = Instant::now();
+        send_movement_frames(
+            &self.can_interface,
+            &self.command_builder,
+            &self.command_counters,
+            &mut self.split_buffer,
+            movement,
+            gimbal,
+        )?;
+        self.overrun.record_send(send_started, send_started.elapsed());
+        self.echo_watch.record_sent(send_started);
+
+        self.keepalive.mark_twist_sent(Instant::now());
+
+        self.echo_watch.verify_protocol(self.command_builder.crc16_init())?;
+        Ok(())
+    }
+
+    /// Cap a filtered movement and derive the matching gimbal command
+    ///
+    /// The gimbal command respects the reported gimbal limits.
+    fn output_for(&self, movement: MovementParams) -> (MovementParams, GimbalParams) {
+        self.output_caps().apply(movement, Instant::now())
+    }
+
+    /// Snapshot the stages capping a filtered movement
+    fn output_caps(&self) -> OutputCaps {
+        OutputCaps {
+            speed_limit: self.speed_limit,
+            safe_mode: self.safe_mode,
+            thermal_limit: self.thermal_limit,
+            gimbal_follow: self.gimbal_follow,
+            gimbal_limits: self.sensor_data.gimbal.limits,
+        }
+    }
+
+    /// Lock the shared command counters
+    fn counters(&self) -> MutexGuard<'_, CommandCounters> {
+        lock_counters(&self.command_counters)
+    }
+
     /// Control LED color
+    ///
+    /// The color is scaled by the brightness set with
+    /// [`set_led_brightness`](Self::set_led_brightness).
     pub async fn control_led(&mut self, color: LedColor) -> Result<(), RoboMasterError> {
-        let led_cmd = self.command_builder.build_led_command(color, &self.command_counters)?;
-        let led_messages = MessageSplitter::split_command(&led_cmd);
-        self.can_interface.send_messages(&led_messages)?;
+        self.set_led_effect(color, LedEffect::Solid).await
+    }
+
+    /// Play an LED effect such as breathing or flashing
+    ///
+    /// The firmware animates the effect on its own, so there is no need to
+    /// resend colors in a loop. The color is scaled by the brightness like
+    /// [`control_led`](Self::control_led).
+    pub async fn set_led_effect(&mut self, color: LedColor, effect: LedEffect) -> Result<(), RoboMasterError> {
+        let color = color.with_brightness(self.led_brightness);
+        self.send_led(|builder, counters| builder.build_led_effect_command(color, effect, counters)).await
+    }
+
+    /// Set the color of one LED segment, leaving the others unchanged
+    ///
+    /// Useful for directional indicators such as turn signals. The color is
+    /// scaled by the brightness like [`control_led`](Self::control_led).
+    pub async fn set_zone_led(&mut self, zone: LedZone, color: LedColor) -> Result<(), RoboMasterError> {
+        let color = color.with_brightness(self.led_brightness);
+        self.send_led(|builder, counters| builder.build_zone_led_command(zone, color, counters)).await
+    }
+
+    /// Set the color of every LED segment at once
+    ///
+    /// `colors` is in [`LedZone::SEGMENTS`] order. The LED command carries one
+    /// color and a segment mask, so the protocol cannot give segments
+    /// different colors in a single command. Uniform colors go out as one
+    /// all-segment command and advance the LED counter once. Otherwise each
+    /// distinct color takes its own command and counter value; the commands
+    /// are sent back to back under one send permit, which shortens tearing
+    /// between segments but does not make the change atomic.
+    pub async fn control_all_zones(&mut self, colors: [LedColor; LED_SEGMENT_COUNT]) -> Result<(), RoboMasterError> {
+        let colors = colors.map(|color| color.with_brightness(self.led_brightness));
+
+        let _permit = self.send_limiter.acquire().await;
+        let mut counters = lock_counters(&self.command_counters);
+        let commands = self.command_builder.build_segment_led_commands(colors, &counters)?;
+        for command in &commands {
+            send_split(&self.can_interface, &mut self.split_buffer, command)?;
+        }
+        counters.led = counters.led.wrapping_add(commands.len() as u16);
+        Ok(())
+    }
+
+    /// Build an LED command with the current counters, send it and advance the LED counter
+    async fn send_led<F>(&mut self, build: F) -> Result<(), RoboMasterError>
+    where
+        F: FnOnce(&CommandBuilder, &CommandCounters) -> Result<Vec<u8>, RoboMasterError>,
+    {
+        let _permit = self.send_limiter.acquire().await;
+        self.send_led_now(build)
+    }
+
+    /// Build and send an LED command while holding a send permit
+    fn send_led_now<F>(&mut self, build: F) -> Result<(), RoboMasterError>
+    where
+        F: FnOnce(&CommandBuilder, &CommandCounters) -> Result<Vec<u8>, RoboMasterError>,
+    {
+        let mut counters = lock_counters(&self.command_counters);
+        let led_cmd = build(&self.command_builder, &counters)?;
+        send_split(&self.can_interface, &mut self.split_buffer, &led_cmd)?;
         
         // Update counter
-        self.command_counters.led += 1;
+        counters.led = counters.led.wrapping_add(1);
         
         Ok(())
     }
 
+    /// Send an LED animation frame unless control traffic needs the bus
+    ///
+    /// Returns `false` if the frame was dropped because the bus headroom is
+    /// below the LED throttle's minimum. Use this for animations where a
+    /// missed frame is harmless, and [`control_led`](Self::control_led) for
+    /// colors that must arrive.
+    pub async fn animate_led(&mut self, color: LedColor) -> Result<bool, RoboMasterError> {
+        if !self.led_throttle.allow(self.can_interface.bus_headroom()) {
+            return Ok(false);
+        }
+        self.control_led(color).await?;
+        Ok(true)
+    }
+
+    /// Fade the LEDs from one color to another over `duration`
+    ///
+    /// Intermediate colors are sent every [`LED_UPDATE_PERIOD`] through
+    /// [`animate_led`](Self::animate_led), so they may be dropped when the
+    /// bus is busy. The final color is always sent.
+    pub async fn fade_led(&mut self, from: LedColor, to: LedColor, duration: Duration) -> Result<(), RoboMasterError> {
+        let start = tokio::time::Instant::now();
+        let mut interval = tokio::time::interval(LED_UPDATE_PERIOD);
+        loop {
+            interval.tick().await;
+            let elapsed = start.elapsed();
+            if elapsed >= duration {
+                return self.control_led(to).await;
+            }
+            self.animate_led(from.interpolate(to, elapsed.as_secs_f32() / duration.as_secs_f32())).await?;
+        }
+    }
+
+    /// Flash the LEDs white [`IDENTIFY_FLASHES`] times so the robot can be spotted
+    ///
+    /// The flashes ignore the LED brightness so they stay visible when it is
+    /// dimmed. The LEDs are left off afterwards.
+    pub async fn identify(&mut self) -> Result<(), RoboMasterError> {
+        let white = LedColor { red: 255, green: 255, blue: 255 };
+        let off = LedColor { red: 0, green: 0, blue: 0 };
+        for _ in 0..IDENTIFY_FLASHES {
+            for color in [white, off] {
+                self.send_led(|builder, counters| builder.build_led_effect_command(color, LedEffect::Solid, counters))
+                    .await?;
+                tokio::time::sleep(IDENTIFY_FLASH_PERIOD).await;
+            }
+        }
+        Ok(())
+    }
+
+    /// Limit how many commands may be sending at once
+    ///
+    /// Sends beyond the limit wait for an earlier one to finish rather than
+    /// flooding the TX queue. Movements already held by
+    /// [`hold`](Self::hold) keep the limiter they started with.
+    pub fn set_max_in_flight(&mut self, limit: usize) {
+        self.send_limiter = SendLimiter::new(limit);
+    }
+
+    /// Get the number of commands allowed to be sending at once
+    pub fn max_in_flight(&self) -> usize {
+        self.send_limiter.limit()
+    }
+
+    /// Set the bus headroom (0.0 to 1.0) required for LED animation frames
+    pub fn set_led_min_headroom(&mut self, min_headroom: f32) {
+        self.led_throttle.set_min_headroom(min_headroom);
+    }
+
+    /// Get the number of LED animation frames dropped to free the bus
+    pub fn skipped_led_frames(&self) -> u64 {
+        self.led_throttle.skipped()
+    }
+
+    /// Set the LED brightness (0.0 to 1.0) used by subsequent LED commands
+    ///
+    /// A `NaN` level means full brightness, as in [`LedColor::with_brightness`].
+    pub fn set_led_brightness(&mut self, level: f32) {
+        self.led_brightness = if level.is_nan() { 1.0 } else { level.clamp(0.0, 1.0) };
+    }
+
+    /// Get the current LED brightness
+    pub fn led_brightness(&self) -> f32 {
+        self.led_brightness
+    }
+
+    /// Register a telemetry decoder overriding the built-in one
+    ///
+    /// Every received message is also passed to `decoder`, and the readings
+    /// it returns replace the built-in ones in
+    /// [`sensor_data`](Self::sensor_data). `NaN` readings and `None` blaster
+    /// fields are left as the built-in decoding set them.
+    pub fn set_telemetry_decoder(&mut self, decoder: impl TelemetryDecoder + 'static) {
+        self.telemetry_decoder = Some(Arc::new(decoder));
+    }
+
+    /// Restore the built-in telemetry decoder
+    pub fn reset_telemetry_decoder(&mut self) {
+        self.telemetry_decoder = None;
+    }
+
+    /// Decode a reassembled telemetry message with the registered decoder
+    pub fn decode_sensor_data(&self, frame: &[u8]) -> Option<SensorData> {
+        match &self.telemetry_decoder {
+            Some(decoder) => decoder.decode(frame),
+            None => BuiltinDecoder.decode(frame),
+        }
+    }
+
+    /// Route a reassembled telemetry message to its decoder
+    ///
+    /// Updates the fields of [`sensor_data`](Self::sensor_data) carried by
+    /// the message and returns its type, or `None` for unknown messages and
+    /// messages failing their CRC checks.
+    pub fn process_telemetry(&mut self, message: &[u8]) -> Option<TelemetryKind> {
+        if let Err(error) = verify_message(message, self.command_builder.crc16_init()) {
+            tracing::warn!("ignoring corrupted telemetry message: {}", error);
+            return None;
+        }
+        self.process_telemetry_at(message, Instant::now())
+    }
+
+    /// Process a telemetry message whose last frame arrived at `received_at`
+    fn process_telemetry_at(&mut self, message: &[u8], received_at: Instant) -> Option<TelemetryKind> {
+        let kind = route_telemetry(self.telemetry_decoder.as_deref(), message, &mut self.sensor_data)?;
+        if kind == TelemetryKind::Imu {
+            self.yaw_tracker.update(self.sensor_data.imu.orientation[2]);
+            self.odometry.use_imu_heading(true);
+            self.advance_odometry(received_at);
+        }
+        if matches!(kind, TelemetryKind::Imu | TelemetryKind::ChassisStatus)
+            && self.fault_detector.observe_imu(&self.sensor_data.imu, received_at) == Some(FaultKind::TipOver)
+        {
+            hold::cancel_holds(&self.active_holds);
+        }
+        Some(kind)
+    }
+
+    /// Get the chassis fault detected from telemetry, if any
+    ///
+    /// A detected tip-over stops the robot and blocks movement until
+    /// [`clear_fault`](Self::clear_fault) or
+    /// [`recover_to_idle`](Self::recover_to_idle).
+    pub fn fault_state(&self) -> Option<FaultKind> {
+        self.fault_detector.fault()
+    }
+
+    /// Forget a detected chassis fault
+    pub fn clear_fault(&mut self) {
+        self.fault_detector.clear();
+    }
+
+    fn check_not_tipped_over(&self) -> Result<(), RoboMasterError> {
+        if self.fault_detector.fault() == Some(FaultKind::TipOver) {
+            return Err(ControlError::MovementBlocked {
+                reason: "chassis has tipped over".to_string(),
+            }
+            .into());
+        }
+        Ok(())
+    }
+
+    /// Check the blaster interlock against the latest blaster telemetry
+    ///
+    /// Returns `BlasterInterlocked` unless the blaster is armed and the
+    /// gimbal is ready, so fire commands the robot would silently ignore
+    /// are rejected up front.
+    pub fn check_blaster_interlock(&self) -> Result<(), RoboMasterError> {
+        Ok(self.sensor_data.blaster.check_fire()?)
+    }
+
+    /// Fire `count` shots from the blaster
+    ///
+    /// Fails with `InvalidParameter` if `count` is zero or above
+    /// [`MAX_BLASTER_BURST`](crate::command::MAX_BLASTER_BURST), and with
+    /// `BlasterInterlocked` unless the blaster is armed and the gimbal is
+    /// ready. If the previous burst is still being fired, waits until the
+    /// blaster accepts another command.
+    pub async fn fire_blaster(&mut self, count: u8) -> Result<(), RoboMasterError> {
+        check_blaster_burst(count)?;
+        self.check_blaster_interlock()?;
+        self.ensure_initialized().await?;
+
+        tokio::time::sleep(self.fire_rate.delay(Instant::now())).await;
+
+        let _permit = self.send_limiter.acquire().await;
+        {
+            let mut counters = lock_counters(&self.command_counters);
+            let fire_cmd = self.command_builder.build_blaster_command(count, &counters)?;
+            send_split(&self.can_interface, &mut self.split_buffer, &fire_cmd)?;
+            counters.joy = counters.joy.wrapping_add(1);
+        }
+        self.fire_rate.record_fire(Instant::now(), count);
+        Ok(())
+    }
+
+    /// Total rotation in radians measured by the IMU since the last reset
+    pub fn accumulated_yaw(&self) -> f32 {
+        self.yaw_tracker.accumulated()
+    }
+
+    /// Reset the accumulated rotation to zero
+    pub fn reset_accumulated_yaw(&mut self) {
+        self.yaw_tracker.reset();
+    }
+
+    /// Get the pose estimated from commanded movements and IMU yaw
+    ///
+    /// See [`Odometry`] for how the estimate is made and how it drifts.
+    pub fn pose(&self) -> Pose {
+        let mut odometry = self.odometry.clone();
+        if let Some(updated_at) = self.odometry_updated_at {
+            odometry.update(&self.sensor_data, updated_at.elapsed());
+        }
+        odometry.pose()
+    }
+
+    /// Make the robot's current position and heading the pose origin
+    pub fn reset_pose(&mut self) {
+        self.advance_odometry(Instant::now());
+        self.odometry.reset();
+    }
+
+    /// Set the speeds the pose estimate assumes at full command
+    pub fn set_odometry_limits(&mut self, limits: RobotLimits) {
+        self.odometry.set_limits(limits);
+    }
+
+    /// Integrate the pose up to `now` at the velocity sent last
+    ///
+    /// A `now` earlier than the last update, such as the receive time of a
+    /// frame processed after a send, only applies the new readings.
+    fn advance_odometry(&mut self, now: Instant) {
+        let updated_at = match self.odometry_updated_at {
+            Some(updated_at) => {
+                self.odometry.update(&self.sensor_data, now.saturating_duration_since(updated_at));
+                updated_at.max(now)
+            }
+            None => now,
+        };
+        self.odometry_updated_at = Some(updated_at);
+    }
+
+    /// Get the sensor data accumulated from processed telemetry
+    pub fn sensor_data(&self) -> &SensorData {
+        &self.sensor_data
+    }
+
     /// Send touch command
     pub async fn send_touch(&mut self) -> Result<(), RoboMasterError> {
-        let touch_messages = self.command_builder.build_touch_command(&self.command_counters)?;
-        self.can_interface.send_messages(&touch_messages)?;
-        
-        // Update counter
-        self.command_counters.joy += 1;
+        {
+            let _permit = self.send_limiter.acquire().await;
+            send_touch_frames(&self.can_interface, &self.command_builder, &self.command_counters)?;
+        }
+        self.keepalive.mark_touch_sent(Instant::now());
         
         Ok(())
     }
 
+    /// Set the touch keep-alive rate in Hz, independent of the movement refresh rate
+    pub fn set_keepalive_rate(&mut self, hz: u32) -> Result<(), RoboMasterError> {
+        self.keepalive.set_touch_rate(hz)
+    }
+
+    /// Set the rate in Hz at which the last movement is refreshed while idle
+    pub fn set_movement_refresh_rate(&mut self, hz: u32) -> Result<(), RoboMasterError> {
+        self.keepalive.set_twist_rate(hz)
+    }
+
+    /// Highest control loop frequency in Hz that the bus can sustain
+    ///
+    /// Each control tick sends a twist and a gimbal command. The rate is
+    /// chosen so those frames use at most [`COMMAND_BUS_SHARE`] of the
+    /// interface bitrate, capped at [`MAX_RECOMMENDED_COMMAND_RATE`]. Use it
+    /// in place of the fixed [`CONTROL_FREQUENCY`](crate::CONTROL_FREQUENCY)
+    /// on slower buses.
+    pub fn recommended_command_rate(&self) -> Result<u32, RoboMasterError> {
+        let counters = self.counters().clone();
+        let twist = self.command_builder.build_twist_command(MovementParams::stopped(), &counters)?;
+        let gimbal = self.command_builder.build_gimbal_command(GimbalParams::neutral(), &counters)?;
+        let frame_lens: Vec<usize> = [twist, gimbal]
+            .iter()
+            .flat_map(|command| MessageSplitter::split_command(command))
+            .map(|frame| frame.len())
+            .collect();
+
+        let rate = max_cycle_rate(self.can_interface.bitrate(), &frame_lens, COMMAND_BUS_SHARE);
+        Ok(rate.clamp(1, MAX_RECOMMENDED_COMMAND_RATE))
+    }
+
+    /// Send any touch or movement keep-alives that are due
+    ///
+    /// Call this from the control loop at least as often as the faster of
+    /// the two configured rates. If a failsafe timeout is configured and no
+    /// movement has been commanded within it, the refreshed movement is a stop.
+    pub async fn service_keepalive(&mut self) -> Result<KeepaliveDue, RoboMasterError> {
+        let now = Instant::now();
+        if let (Some(timeout), Some(last)) = (self.failsafe_timeout, self.last_movement_at) {
+            if now.saturating_duration_since(last) > timeout {
+                self.last_movement = MovementParams::stopped();
+                self.wheel_speeds = None;
+            }
+        }
+        self.observe_watchdog_stop();
+
+        let due = self.keepalive.poll(now);
+        if due.touch {
+            self.send_touch().await?;
+        }
+        if due.twist {
+            match self.wheel_speeds {
+                Some(speeds) => {
+                    let _permit = self.send_limiter.acquire().await;
+                    let speeds = self.cap_wheel_speeds(speeds, Instant::now());
+                    self.send_wheel_speeds_now(speeds)?;
+                }
+                None => self.send_movement(self.last_movement).await?,
+            }
+        }
+        Ok(due)
+    }
+
+    /// Apply a complete set of runtime settings at once
+    ///
+    /// The movement filter, gimbal follow mode and failsafe timeout take
+    /// effect for the next command. The status LED, if set, is sent immediately.
+    pub async fn apply_settings(&mut self, settings: &RobotSettings) -> Result<(), RoboMasterError> {
+        self.movement_filter = settings.movement_filter();
+        self.gimbal_follow = settings.gimbal_follow;
+        self.failsafe_timeout = settings.failsafe_timeout();
+
+        if let Some(color) = settings.status_led {
+            self.control_led(color).await?;
+        }
+        Ok(())
+    }
+
+    /// Get the movement filter applied to every movement command
+    pub fn movement_filter(&self) -> &MovementFilter {
+        &self.movement_filter
+    }
+
+    /// Set how simultaneous strafe and rotation commands are combined
+    pub fn set_motion_constraint(&mut self, constraint: MotionConstraint) {
+        self.motion_constraint = constraint;
+    }
+
+    /// Hard-cap every chassis axis and gimbal rate to `max_speed`
+    ///
+    /// The ceiling can only be lowered while safe mode is enabled; call
+    /// [`disable_safe_mode`](Self::disable_safe_mode) to raise it. Active
+    /// [`hold`](Self::hold)s were shaped without the ceiling and are cancelled.
+    pub fn enable_safe_mode(&mut self, max_speed: f32) {
+        hold::cancel_holds(&self.active_holds);
+        self.safe_mode.enable(max_speed);
+    }
+
+    /// Remove the safe-mode ceiling
+    pub fn disable_safe_mode(&mut self) {
+        self.safe_mode.disable();
+    }
+
+    /// Get the safe-mode ceiling, if safe mode is enabled
+    pub fn safe_mode_ceiling(&self) -> Option<f32> {
+        self.safe_mode.ceiling()
+    }
+
+    /// Clamp every chassis axis of every later movement to `max`
+    ///
+    /// Applies whatever the movement came from, just before the commands are
+    /// built. Unlike safe mode, the limit can be raised again at any time.
+    /// Fails with `SpeedOutOfRange` unless `max` is between 0 and
+    /// [`MAX_SPEED`](crate::MAX_SPEED).
+    pub fn set_speed_limit(&mut self, max: f32) -> Result<(), RoboMasterError> {
+        if !(0.0..=crate::MAX_SPEED).contains(&max) {
+            return Err(ControlError::SpeedOutOfRange {
+                value: max,
+                min: 0.0,
+                max: crate::MAX_SPEED,
+            }
+            .into());
+        }
+        self.speed_limit = max;
+        Ok(())
+    }
+
+    /// Get the speed limit applied to every movement
+    pub fn speed_limit(&self) -> f32 {
+        self.speed_limit
+    }
+
+    /// Reduce speed for `cooldown` after `max_continuous` of sustained high speed
+    ///
+    /// Protects the motors from overheating during long runs; see
+    /// [`ThermalLimit`] for what counts as high speed and the reduced speed.
+    pub fn set_thermal_duty_cycle(&mut self, max_continuous: Duration, cooldown: Duration) {
+        self.thermal_limit.set_duty_cycle(max_continuous, cooldown);
+    }
+
+    /// Stop limiting continuous high-speed run time
+    pub fn clear_thermal_duty_cycle(&mut self) {
+        self.thermal_limit.disable();
+    }
+
+    /// Check whether movements are currently slowed to let the motors cool
+    pub fn thermal_cooling(&self) -> bool {
+        self.thermal_limit.is_cooling(Instant::now())
+    }
+
+    /// Get the current motion constraint
+    pub fn motion_constraint(&self) -> MotionConstraint {
+        self.motion_constraint
+    }
+
+    /// Limit how fast each movement axis may change, in speed units per second
+    ///
+    /// Movements passed to [`move_robot`](Self::move_robot) are slewed from
+    /// the last one sent, so a joystick snapped to full deflection ramps up
+    /// instead of lurching. [`stop`](Self::stop) is never slewed.
+    pub fn set_slew_limit(&mut self, rate: f32) -> Result<(), RoboMasterError> {
+        let mut limiter = SlewLimiter::new(rate)?;
+        limiter.reset(self.last_movement);
+        self.slew_limiter = Some(limiter);
+        Ok(())
+    }
+
+    /// Send movements unslewed again
+    pub fn clear_slew_limit(&mut self) {
+        self.slew_limiter = None;
+    }
+
+    /// Get the slew limit in speed units per second, if one is set
+    pub fn slew_limit(&self) -> Option<f32> {
+        self.slew_limiter.as_ref().map(SlewLimiter::rate)
+    }
+
+    /// Run one of the built-in demo routines
+    ///
+    /// Each step's movement is refreshed at the movement refresh rate for the
+    /// duration of the step. The robot is stopped at the end of the demo.
+    pub async fn run_demo(&mut self, demo: DemoKind) -> Result<(), RoboMasterError> {
+        for step in demo.steps() {
+            if let Some(color) = step.led {
+                self.control_led(color).await?;
+            }
+            self.move_robot(step.movement).await?;
+
+            let deadline = tokio::time::Instant::now() + step.duration;
+            while tokio::time::Instant::now() < deadline {
+                let remaining = deadline - tokio::time::Instant::now();
+                tokio::time::sleep(remaining.min(self.keepalive.twist_period())).await;
+                self.move_robot(step.movement).await?;
+            }
+        }
+
+        self.stop().await
+    }
+
+    /// Send a raw command and wait for the first frame matching a predicate
+    ///
+    /// This is the building block for query commands whose answer arrives as
+    /// a separate message.
+    pub async fn request_response<F>(
+        &mut self,
+        command: &[u8],
+        matches: F,
+        timeout: Duration,
+    ) -> Result<CanFrame, RoboMasterError>
+    where
+        F: Fn(&CanFrame) -> bool,
+    {
+        self.can_interface.request_response(command, matches, timeout).await
+    }
+
+    /// Query the robot's serial number
+    ///
+    /// Sends [`build_serial_number_query`](CommandBuilder::build_serial_number_query)
+    /// and waits up to [`SERIAL_NUMBER_TIMEOUT`] for the answer, processing
+    /// any telemetry that arrives meanwhile. Fails with `Timeout` if the
+    /// robot does not answer, which is expected on firmware that does not
+    /// expose its serial over CAN, and with `UnsupportedCommand` if the
+    /// answer carries an error code or no readable serial.
+    pub async fn serial_number(&mut self) -> Result<String, RoboMasterError> {
+        self.ensure_initialized().await?;
+
+        let permit = self.send_limiter.acquire().await;
+        let query = {
+            let mut counters = self.counters();
+            let query = self.command_builder.build_serial_number_query(&counters)?;
+            counters.joy = counters.joy.wrapping_add(1);
+            query
+        };
+        send_split(&self.can_interface, &mut self.split_buffer, &query)?;
+        // Other senders such as the watchdog must not wait for the answer
+        drop(permit);
+
+        let deadline = tokio::time::Instant::now() + SERIAL_NUMBER_TIMEOUT;
+        loop {
+            let remaining = deadline.saturating_duration_since(tokio::time::Instant::now());
+            let received = if remaining.is_zero() {
+                None
+            } else {
+                self.can_interface.receive_timestamped(remaining).await?
+            };
+            let Some(received) = received else {
+                return Err(RoboMasterError::Timeout {
+                    timeout_ms: SERIAL_NUMBER_TIMEOUT.as_millis() as u64,
+                });
+            };
+
+            let message = match self.handle_received(received).await {
+                Ok(Some(message)) => message,
+                Ok(None) => continue,
+                Err(error) => {
+                    tracing::warn!("skipping frame while waiting for the serial number: {}", error);
+                    continue;
+                }
+            };
+            if telemetry::is_serial_number_response(&message) {
+                return telemetry::decode_serial_number(&message).ok_or_else(|| {
+                    ProtocolError::UnsupportedCommand { command: "serial number query".to_string() }.into()
+                });
+            }
+        }
+    }
+
     /// Receive messages and update internal state
+    ///
+    /// Receive errors are handled according to the configured
+    /// [`ReceiveErrorPolicy`].
     pub async fn receive_messages(&mut self) -> Result<(), RoboMasterError> {
-        self.can_interface.receive_and_process(&mut self.command_counters).await
+        self.receive_next().await.map(|_| ())
+    }
+
+    /// Process pending frames and return the latest sensor data
+    ///
+    /// Frames are received until none arrives within the receive timeout, or at
+    /// most [`MAX_POLL_FRAMES`] frames. Battery, IMU, gimbal and blaster
+    /// messages update the matching fields; other frames leave them untouched.
+    pub async fn poll_sensors(&mut self) -> Result<SensorData, RoboMasterError> {
+        for _ in 0..MAX_POLL_FRAMES {
+            if !self.receive_next().await? {
+                break;
+            }
+        }
+        Ok(self.sensor_data.clone())
+    }
+
+    /// Receive one frame, applying the receive error policy
+    ///
+    /// Returns `false` if no frame arrived within the receive timeout.
+    async fn receive_next(&mut self) -> Result<bool, RoboMasterError> {
+        let error = match self.receive_frame().await {
+            Ok(received) => {
+                self.echo_watch.check(Instant::now(), self.command_builder.crc16_init());
+                return Ok(received);
+            }
+            Err(error) => error,
+        };
+
+        match self.receive_error_policy.handle(error)? {
+            ReceiveErrorAction::Continue => Ok(true),
+            ReceiveErrorAction::Reconnect => self.reconnect().await.map(|_| true),
+        }
+    }
+
+    /// Receive one frame, sync the counter from echoes and process telemetry
+    async fn receive_frame(&mut self) -> Result<bool, RoboMasterError> {
+        let Some(received) = self.can_interface.receive_timestamped(self.receive_timeout).await? else {
+            return Ok(false);
+        };
+        self.handle_received(received).await?;
+        Ok(true)
+    }
+
+    /// Process one received frame, returning the message it completed, if any
+    ///
+    /// Counter echoes resync the joy counter, and completed telemetry
+    /// messages update the sensors and the CSV log. A newly detected
+    /// tip-over stops the robot.
+    async fn handle_received(&mut self, received: TimestampedFrame) -> Result<Option<Vec<u8>>, RoboMasterError> {
+        let TimestampedFrame { frame, received_at } = received;
+        if self.can_interface.skip_extended(&frame) {
+            return Ok(None);
+        }
+
+        if let Some(counter) = echo_counter_on(&frame, self.can_interface.can_id()) {
+            let mut counters = lock_counters(&self.command_counters);
+            self.counter_sync.observe(&mut counters.joy, counter);
+            drop(counters);
+            self.echo_watch.record_echo();
+        }
+
+        let Some(id) = standard_id(&frame) else {
+            return Ok(None);
+        };
+        let Some(message) = self.telemetry_receiver.handle_frame(id, frame.data())? else {
+            return Ok(None);
+        };
+        let tipped_over = self.fault_detector.fault() == Some(FaultKind::TipOver);
+        let decoded = self.process_telemetry_at(&message, received_at).is_some();
+        if let (true, Some(csv)) = (decoded, &mut self.telemetry_csv) {
+            csv.write_row(&self.sensor_data)?;
+        }
+        // Stop once when the tip-over is first detected, whatever is driving
+        if !tipped_over && self.fault_detector.fault() == Some(FaultKind::TipOver) {
+            self.stop().await?;
+        }
+        Ok(Some(message))
+    }
+
+    /// Log every received frame to `path` while it is processed live
+    ///
+    /// The log uses the `candump -l` format. Any previous log is closed.
+    pub fn set_telemetry_log(&mut self, path: impl AsRef<std::path::Path>) -> Result<(), RoboMasterError> {
+        let log = TelemetryLog::create(path, self.can_interface.interface_name())?;
+        self.telemetry_receiver.clear_log()?;
+        self.telemetry_receiver.set_log(log);
+        Ok(())
+    }
+
+    /// Stop logging received frames
+    pub fn clear_telemetry_log(&mut self) -> Result<(), RoboMasterError> {
+        Ok(self.telemetry_receiver.clear_log()?)
+    }
+
+    /// Write the decoded sensor readings to a CSV file at `path`
+    ///
+    /// A row is appended after every decoded telemetry message. Any previous
+    /// CSV log is closed.
+    pub fn set_telemetry_csv(&mut self, path: impl AsRef<std::path::Path>) -> Result<(), RoboMasterError> {
+        let csv = TelemetryCsv::create(path)?;
+        self.clear_telemetry_csv()?;
+        self.telemetry_csv = Some(csv);
+        Ok(())
+    }
+
+    /// Stop writing decoded sensor readings as CSV
+    pub fn clear_telemetry_csv(&mut self) -> Result<(), RoboMasterError> {
+        match self.telemetry_csv.take() {
+            Some(mut csv) => Ok(csv.flush()?),
+            None => Ok(()),
+        }
+    }
+
+    /// Set which twist axes the chassis obeys, e.g. translation-only driving
+    pub fn set_twist_enable(&mut self, twist_enable: TwistEnable) {
+        self.command_builder.set_twist_enable(twist_enable);
+    }
+
+    /// Get which twist axes the chassis obeys
+    pub fn twist_enable(&self) -> TwistEnable {
+        self.command_builder.twist_enable()
+    }
+
+    /// Set how chassis rotation and the gimbal yaw are coupled
+    ///
+    /// Sets both the [`twist_enable`](Self::twist_enable) flags and whether
+    /// movements turn the gimbal; in [`ChassisMode::Free`] the gimbal gets
+    /// neutral commands and can be aimed on its own.
+    pub fn set_chassis_mode(&mut self, mode: ChassisMode) {
+        self.command_builder.set_twist_enable(mode.twist_enable());
+        self.gimbal_follow = mode.gimbal_follows();
+    }
+
+    /// Get the chassis mode, or `None` if the twist flags or gimbal coupling
+    /// were set to a combination no mode uses
+    pub fn chassis_mode(&self) -> Option<ChassisMode> {
+        ChassisMode::from_parts(self.command_builder.twist_enable(), self.gimbal_follow)
+    }
+
+    /// Set the CRC16 init value used for outgoing commands and received telemetry
+    ///
+    /// The init value is firmware-specific. If the robot stops echoing the
+    /// command counter, a warning suggests checking this value.
+    pub fn set_crc16_init(&mut self, crc16_init: u16) {
+        self.command_builder.set_crc16_init(crc16_init);
+        self.telemetry_receiver.set_crc16_init(crc16_init);
+    }
+
+    /// Set how far the local command counter may drift from the echoed one before resyncing
+    pub fn set_max_counter_drift(&mut self, max_drift: u16) {
+        self.counter_sync.set_max_drift(max_drift);
+    }
+
+    /// Get the number of times the command counter has been resynced
+    pub fn counter_resyncs(&self) -> u64 {
+        self.counter_sync.resyncs()
+    }
+
+    /// Have the CAN socket loop back transmitted frames so sends can be confirmed
+    pub fn enable_tx_confirmation(&self) -> Result<(), RoboMasterError> {
+        self.can_interface.enable_tx_confirmation()
+    }
+
+    /// Call `handler` with every received extended frame
+    ///
+    /// See [`CanInterface::set_extended_frame_handler`]. The handler is kept
+    /// across [`reconnect`](Self::reconnect).
+    pub fn set_extended_frame_handler(&self, handler: impl Fn(&CanFrame) + Send + Sync + 'static) {
+        self.can_interface.set_extended_frame_handler(handler);
+    }
+
+    /// Run `hook` around every command sent from now on
+    ///
+    /// Commands sent from [`hold`](Self::hold) and
+    /// [`start_heartbeat`](Self::start_heartbeat) tasks run the hook too.
+    /// Hooks are kept across [`reconnect`](Self::reconnect).
+    pub fn add_command_hook(&self, hook: Arc<dyn CommandHook>) {
+        self.can_interface.add_command_hook(hook);
+    }
+
+    /// Reopen the CAN backend automatically when a send or receive fails
+    ///
+    /// See [`CanInterface::set_auto_reconnect`]. The setting is kept across
+    /// [`reconnect`](Self::reconnect).
+    pub fn set_auto_reconnect(&self, policy: Option<AutoReconnect>) {
+        self.can_interface.set_auto_reconnect(policy);
+    }
+
+    /// Get whether the CAN bus is currently reachable
+    pub fn connection_state(&self) -> ConnectionState {
+        self.can_interface.connection_state()
+    }
+
+    /// Get the CAN controller error state
+    ///
+    /// See [`CanInterface::bus_state`].
+    pub fn bus_state(&self) -> BusState {
+        self.can_interface.bus_state()
+    }
+
+    /// Get the number of received extended frames, which are never decoded
+    pub fn skipped_extended_frames(&self) -> u64 {
+        self.can_interface.skipped_extended_frames()
+    }
+
+    /// Check whether everything sent so far actually left the bus
+    ///
+    /// A successful send only means the frames were queued. Returns `false`
+    /// if transmit confirmation is not enabled or could not be read.
+    pub fn last_send_confirmed(&self) -> bool {
+        self.can_interface.confirm_tx().unwrap_or(false)
+    }
+
+    /// Check whether recent commands have gone unacknowledged by the robot
+    pub fn commands_unacknowledged(&self) -> bool {
+        self.echo_watch.is_rejecting(Instant::now())
+    }
+
+    /// Fail movement commands with [`NoCounterEcho`](crate::error::ProtocolError::NoCounterEcho) once
+    /// `commands` have been sent without the robot ever echoing the counter
+    ///
+    /// A robot that never echoes is most likely running firmware with a
+    /// different protocol version or CRC16 init. Echoes are only seen while
+    /// frames are received, for example with
+    /// [`poll_sensors`](Self::poll_sensors), so only enable this when the
+    /// application receives. `None`, the default, disables the check.
+    pub fn set_max_unechoed_commands(&mut self, commands: Option<u32>) {
+        self.echo_watch.set_max_unanswered(commands);
+    }
+
+    /// Set how receive errors are handled
+    pub fn set_receive_error_policy(&mut self, policy: ReceiveErrorPolicy) {
+        self.receive_error_policy = policy;
+    }
+
+    /// Get the current receive error policy
+    pub fn receive_error_policy(&self) -> ReceiveErrorPolicy {
+        self.receive_error_policy
+    }
+
+    /// Reopen the CAN interface and rerun the boot sequence
+    ///
+    /// The installed backend is reopened through
+    /// [`CanInterface::reconnect`], so hooks, handlers and background tasks
+    /// keep using the same interface.
+    pub async fn reconnect(&mut self) -> Result<(), RoboMasterError> {
+        self.can_interface.reconnect()?;
+        self.is_initialized = false;
+        self.initialize().await
+    }
+
+    /// Bring the robot back to a clean idle state after a fault
+    ///
+    /// Resets the command counters, resync count and detected fault, stops
+    /// the chassis, stops the gimbal and shows [`IDLE_LED_COLOR`], in that
+    /// order. The gimbal is sent a neutral rate command rather than
+    /// recentered, since the absolute-angle encoding of
+    /// [`set_gimbal_angle`](Self::set_gimbal_angle) is not yet confirmed.
+    pub async fn recover_to_idle(&mut self) -> Result<(), RoboMasterError> {
+        *self.counters() = CommandCounters::default();
+        self.counter_sync.reset();
+        self.overrun.reset();
+        self.fault_detector.clear();
+
+        self.stop().await?;
+        self.move_gimbal(GimbalParams::neutral()).await?;
+        self.control_led(IDLE_LED_COLOR).await
     }
 
     /// Stop the robot (send zero movement)
+    ///
+    /// A neutral gimbal command is sent alongside the zero twist unless the
+    /// stop mode is [`StopMode::TwistOnly`].
     pub async fn stop(&mut self) -> Result<(), RoboMasterError> {
-        let stop_movement = MovementParams {
-            vx: 0.0,
-            vy: 0.0,
-            vz: 0.0,
-        };
-        self.move_robot(stop_movement).await
+        self.ensure_initialized().await?;
+
+        let _permit = self.send_limiter.acquire().await;
+        self.stop_now()
+    }
+
+    /// Send the stop commands while holding a send permit
+    fn stop_now(&mut self) -> Result<(), RoboMasterError> {
+        hold::cancel_holds(&self.active_holds);
+        self.advance_odometry(Instant::now());
+        self.odometry.set_velocity(MovementParams::stopped());
+        let send_started = Instant::now();
+        send_stop_frames(
+            &self.can_interface,
+            &self.command_builder,
+            &self.command_counters,
+            &mut self.split_buffer,
+            self.stop_mode,
+        )?;
+        self.echo_watch.record_sent(send_started);
+        self.keepalive.mark_twist_sent(Instant::now());
+
+        self.last_command = MovementParams::stopped();
+        self.last_movement = MovementParams::stopped();
+        self.last_movement_at = Some(Instant::now());
+        self.wheel_speeds = None;
+        if let Some(limiter) = &mut self.slew_limiter {
+            limiter.reset(MovementParams::stopped());
+        }
+        self.echo_watch.verify_protocol(self.command_builder.crc16_init())?;
+        Ok(())
+    }
+
+    /// Set which commands [`stop`](Self::stop) sends
+    pub fn set_stop_mode(&mut self, mode: StopMode) {
+        self.stop_mode = mode;
+    }
+
+    /// Get the current stop mode
+    pub fn stop_mode(&self) -> StopMode {
+        self.stop_mode
     }
 
     /// Shutdown the robot controller
+    ///
+    /// Any [`telemetry_stream`](Self::telemetry_stream) ends and any
+    /// [`start_heartbeat`](Self::start_heartbeat) task stops sending, after
+    /// which the robot drops out of remote control.
     pub async fn shutdown(self) -> Result<(), RoboMasterError> {
         // Stop movement before shutdown
         // Note: We need to take ownership here, so we can't call self.stop()
+        self.shutdown_signal.send_replace(true);
         self.can_interface.shutdown();
         Ok(())
     }
 
-    /// Get current command counters
-    pub fn get_counters(&self) -> &CommandCounters {
-        &self.command_counters
+    /// Get a copy of the current command counters
+    ///
+    /// The counters are shared with background tasks such as holds and the
+    /// heartbeat, so this returns a snapshot by value; earlier versions
+    /// returned `&CommandCounters`.
+    pub fn get_counters(&self) -> CommandCounters {
+        self.counters().clone()
+    }
+
+    /// Get the number of movement commands that took longer to send than the interval between them
+    pub fn overrun_count(&self) -> u64 {
+        self.overrun.overrun_count()
     }
 
     /// Get CAN interface name
@@ -140,6 +1623,158 @@ impl RoboMaster {
     }
 }
 
+/// Decode a telemetry message into `sensors` with the built-in decoders,
+/// letting a registered decoder override the readings it returns
+fn route_telemetry(decoder: Option<&dyn TelemetryDecoder>, message: &[u8], sensors: &mut SensorData) -> Option<TelemetryKind> {
+    let kind = telemetry::dispatch(message, sensors);
+    match decoder.and_then(|decoder| decoder.decode(message)) {
+        Some(decoded) => {
+            sensors.update_from(&decoded);
+            Some(kind.unwrap_or(TelemetryKind::Custom))
+        }
+        None => kind,
+    }
+}
+
+/// Lock command counters, recovering them if a holder panicked
+fn lock_counters(counters: &Mutex<CommandCounters>) -> MutexGuard<'_, CommandCounters> {
+    counters.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Split a command into `split_buffer` and send the frames
+fn send_split(can_interface: &CanInterface, split_buffer: &mut Vec<Vec<u8>>, command: &[u8]) -> Result<(), RoboMasterError> {
+    MessageSplitter::split_command_into(command, split_buffer);
+    can_interface.send_messages(split_buffer)
+}
+
+/// The stages capping a filtered movement, copied so a background task can apply them
+#[derive(Debug, Clone, Copy)]
+struct OutputCaps {
+    speed_limit: f32,
+    safe_mode: SafeMode,
+    thermal_limit: ThermalLimit,
+    gimbal_follow: bool,
+    gimbal_limits: GimbalLimits,
+}
+
+impl OutputCaps {
+    /// Cap a filtered movement and derive the matching gimbal command at `now`
+    fn apply(&self, movement: MovementParams, now: Instant) -> (MovementParams, GimbalParams) {
+        let limit = |value: f32| value.clamp(-self.speed_limit, self.speed_limit);
+        let movement = MovementParams {
+            vx: limit(movement.vx),
+            vy: limit(movement.vy),
+            vz: limit(movement.vz),
+        };
+        let movement = self.safe_mode.cap_movement(movement);
+        let movement = self.thermal_limit.cap_movement(movement, now);
+
+        // Use rotation from movement for gimbal yaw
+        let gimbal = if self.gimbal_follow {
+            GimbalParams { ry: 0.0, rz: movement.vz }
+        } else {
+            GimbalParams::neutral()
+        };
+
+        (movement, self.gimbal_limits.restrict(self.safe_mode.cap_gimbal(gimbal)))
+    }
+}
+
+/// Build and send the twist and gimbal commands for one movement
+fn send_movement_frames(
+    can_interface: &CanInterface,
+    command_builder: &CommandBuilder,
+    command_counters: &Mutex<CommandCounters>,
+    split_buffer: &mut Vec<Vec<u8>>,
+    movement: MovementParams,
+    gimbal: GimbalParams,
+) -> Result<(), RoboMasterError> {
+    let mut counters = lock_counters(command_counters);
+
+    let twist_cmd = command_builder.build_twist_command(movement, &counters)?;
+    let gimbal_cmd = command_builder.build_gimbal_command(gimbal, &counters)?;
+
+    send_split(can_interface, split_buffer, &twist_cmd)?;
+    send_split(can_interface, split_buffer, &gimbal_cmd)?;
+
+    counters.joy = counters.joy.wrapping_add(1);
+    counters.gimbal = counters.gimbal.wrapping_add(1);
+    Ok(())
+}
+
+/// Build and send the commands for a stop
+fn send_stop_frames(
+    can_interface: &CanInterface,
+    command_builder: &CommandBuilder,
+    command_counters: &Mutex<CommandCounters>,
+    split_buffer: &mut Vec<Vec<u8>>,
+    stop_mode: StopMode,
+) -> Result<(), RoboMasterError> {
+    let mut counters = lock_counters(command_counters);
+    for command in stop_mode.build_commands(command_builder, &counters)? {
+        send_split(can_interface, split_buffer, &command)?;
+    }
+    counters.joy = counters.joy.wrapping_add(1);
+    if stop_mode.sends_gimbal() {
+        counters.gimbal = counters.gimbal.wrapping_add(1);
+    }
+    Ok(())
+}
+
+/// Build a touch command with the current counters, send it and advance the joy counter
+fn send_touch_frames(
+    can_interface: &CanInterface,
+    command_builder: &CommandBuilder,
+    command_counters: &Mutex<CommandCounters>,
+) -> Result<(), RoboMasterError> {
+    let mut counters = lock_counters(command_counters);
+    let touch_messages = command_builder.build_touch_command(&counters)?;
+    can_interface.send_messages(&touch_messages)?;
+    counters.joy = counters.joy.wrapping_add(1);
+    Ok(())
+}
+
+/// One of the eight compass directions relative to the robot's heading
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompassDirection {
+    /// Straight ahead
+    Forward,
+    /// Ahead and to the right
+    ForwardRight,
+    /// Straight right
+    Right,
+    /// Behind and to the right
+    BackwardRight,
+    /// Straight back
+    Backward,
+    /// Behind and to the left
+    BackwardLeft,
+    /// Straight left
+    Left,
+    /// Ahead and to the left
+    ForwardLeft,
+}
+
+impl CompassDirection {
+    /// Unit `(vx, vy)` vector for the direction
+    ///
+    /// Diagonals split the speed equally between the axes, so their
+    /// magnitude is 1.0 like the straight directions.
+    pub fn unit_vector(self) -> (f32, f32) {
+        use std::f32::consts::FRAC_1_SQRT_2 as D;
+        match self {
+            Self::Forward => (1.0, 0.0),
+            Self::ForwardRight => (D, D),
+            Self::Right => (0.0, 1.0),
+            Self::BackwardRight => (-D, D),
+            Self::Backward => (-1.0, 0.0),
+            Self::BackwardLeft => (-D, -D),
+            Self::Left => (0.0, -1.0),
+            Self::ForwardLeft => (D, -D),
+        }
+    }
+}
+
 /// Movement command builder for ergonomic API
 #[derive(Debug, Clone, Copy, Default)]
 pub struct MovementCommand {
@@ -170,6 +1805,33 @@ impl MovementCommand {
         self
     }
 
+    /// Move in a compass direction at `speed` (0.0 to 1.0), without rotating
+    pub fn toward(direction: CompassDirection, speed: f32) -> Self {
+        let speed = speed.clamp(0.0, 1.0);
+        let (vx, vy) = direction.unit_vector();
+        Self::new().forward(vx * speed).strafe_right(vy * speed)
+    }
+
+    /// Move diagonally forward and right at `speed` (0.0 to 1.0)
+    pub fn diagonal_forward_right(speed: f32) -> Self {
+        Self::toward(CompassDirection::ForwardRight, speed)
+    }
+
+    /// Move diagonally forward and left at `speed` (0.0 to 1.0)
+    pub fn diagonal_forward_left(speed: f32) -> Self {
+        Self::toward(CompassDirection::ForwardLeft, speed)
+    }
+
+    /// Move diagonally backward and right at `speed` (0.0 to 1.0)
+    pub fn diagonal_backward_right(speed: f32) -> Self {
+        Self::toward(CompassDirection::BackwardRight, speed)
+    }
+
+    /// Move diagonally backward and left at `speed` (0.0 to 1.0)
+    pub fn diagonal_backward_left(speed: f32) -> Self {
+        Self::toward(CompassDirection::BackwardLeft, speed)
+    }
+
     /// Convert to movement parameters
     pub fn into_params(self) -> MovementParams {
         self.params
@@ -226,8 +1888,11 @@ impl LedCommand {
     }
 }
 
-/// Sensor data structure (placeholder for future implementation)
-#[derive(Debug, Clone, Default)]
+/// Sensor data structure
+///
+/// Readings from faulted or disconnected sensors are `NaN`, as are readings
+/// that have not been received yet.
+#[derive(Debug, Clone)]
 pub struct SensorData {
     /// Battery voltage (V)
     pub battery_voltage: f32,
@@ -237,10 +1902,185 @@ pub struct SensorData {
     pub temperature: f32,
     /// IMU data placeholder
     pub imu: ImuData,
+    /// Gimbal angles
+    pub gimbal: GimbalAngles,
+    /// Blaster interlock state
+    pub blaster: BlasterStatus,
+    /// Wheel motor currents (A), in [`WheelSpeeds`] order
+    pub motor_currents: [f32; 4],
+    /// Wheel motor temperatures (°C), in [`WheelSpeeds`] order
+    pub motor_temperatures: [f32; 4],
+    /// Connection quality reported by the robot (%)
+    pub link_quality: f32,
+}
+
+impl Default for SensorData {
+    fn default() -> Self {
+        Self {
+            battery_voltage: f32::NAN,
+            current: f32::NAN,
+            temperature: f32::NAN,
+            imu: ImuData::default(),
+            gimbal: GimbalAngles::default(),
+            blaster: BlasterStatus::default(),
+            motor_currents: [f32::NAN; 4],
+            motor_temperatures: [f32::NAN; 4],
+            link_quality: f32::NAN,
+        }
+    }
+}
+
+impl SensorData {
+    /// Take every reading present in `other`, keeping the rest
+    ///
+    /// `NaN` readings and `None` blaster fields count as absent. The gimbal
+    /// limit flags are taken along with the gimbal angles.
+    pub fn update_from(&mut self, other: &SensorData) {
+        fn take(target: &mut f32, value: f32) {
+            if !value.is_nan() {
+                *target = value;
+            }
+        }
+
+        take(&mut self.battery_voltage, other.battery_voltage);
+        take(&mut self.current, other.current);
+        take(&mut self.temperature, other.temperature);
+        take(&mut self.link_quality, other.link_quality);
+        let readings = self.imu.acceleration.iter_mut().zip(other.imu.acceleration)
+            .chain(self.imu.angular_velocity.iter_mut().zip(other.imu.angular_velocity))
+            .chain(self.imu.orientation.iter_mut().zip(other.imu.orientation))
+            .chain(self.motor_currents.iter_mut().zip(other.motor_currents))
+            .chain(self.motor_temperatures.iter_mut().zip(other.motor_temperatures));
+        for (target, value) in readings {
+            take(target, value);
+        }
+        if !other.gimbal.pitch.is_nan() || !other.gimbal.yaw.is_nan() {
+            take(&mut self.gimbal.pitch, other.gimbal.pitch);
+            take(&mut self.gimbal.yaw, other.gimbal.yaw);
+            self.gimbal.limits = other.gimbal.limits;
+        }
+        self.blaster.armed = other.blaster.armed.or(self.blaster.armed);
+        self.blaster.gimbal_ready = other.blaster.gimbal_ready.or(self.blaster.gimbal_ready);
+    }
+
+    /// Get the battery voltage, or `SensorUnavailable` if no battery telemetry has been decoded
+    pub fn battery_voltage(&self) -> Result<f32, ControlError> {
+        available("battery_voltage", self.battery_voltage)
+    }
+
+    /// Get the temperature, or `SensorUnavailable` if the sensor reported a fault
+    pub fn temperature(&self) -> Result<f32, ControlError> {
+        available("temperature", self.temperature)
+    }
+
+    /// Get the link quality in percent, or `SensorUnavailable` if the robot has not reported it
+    pub fn link_quality(&self) -> Result<f32, ControlError> {
+        available("link_quality", self.link_quality)
+    }
+
+    /// Get the IMU yaw in radians, or `SensorUnavailable` if the IMU reported a fault or has not reported yet
+    pub fn yaw(&self) -> Result<f32, ControlError> {
+        available("imu_yaw", self.imu.orientation[2])
+    }
+
+    /// Find a wheel drawing abnormally high current, such as one that is dragging
+    ///
+    /// Returns the index, in [`WheelSpeeds`] order, of a wheel drawing at
+    /// least [`WHEEL_ANOMALY_MIN_CURRENT`] and [`WHEEL_ANOMALY_RATIO`] times
+    /// the average of the other three. Returns `None` while any current is
+    /// unavailable.
+    pub fn wheel_anomaly(&self) -> Option<usize> {
+        if self.motor_currents.iter().any(|current| current.is_nan()) {
+            return None;
+        }
+        let magnitudes = self.motor_currents.map(f32::abs);
+        let (wheel, &highest) = magnitudes
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))?;
+        let others = (magnitudes.iter().sum::<f32>() - highest) / 3.0;
+        (highest >= WHEEL_ANOMALY_MIN_CURRENT && highest >= others * WHEEL_ANOMALY_RATIO).then_some(wheel)
+    }
+}
+
+/// Multi-line report of the readings, with `n/a` for unavailable ones
+impl std::fmt::Display for SensorData {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let reading = |value: f32, precision: usize, unit: &str| {
+            if value.is_nan() {
+                "n/a".to_string()
+            } else {
+                format!("{:.*} {}", precision, value, unit)
+            }
+        };
+        let axes = |values: [f32; 3], unit: &str| {
+            if values.iter().any(|value| value.is_nan()) {
+                "n/a".to_string()
+            } else {
+                format!("[{:.2}, {:.2}, {:.2}] {}", values[0], values[1], values[2], unit)
+            }
+        };
+
+        writeln!(f, "Battery:     {}", reading(self.battery_voltage, 2, "V"))?;
+        writeln!(f, "Current:     {}", reading(self.current, 2, "A"))?;
+        writeln!(f, "Temperature: {}", reading(self.temperature, 1, "°C"))?;
+        writeln!(f, "Accel:       {}", axes(self.imu.acceleration, "m/s²"))?;
+        writeln!(f, "Gyro:        {}", axes(self.imu.angular_velocity, "rad/s"))?;
+        writeln!(f, "Yaw:         {}", reading(self.imu.orientation[2].to_degrees(), 1, "°"))?;
+        write!(f, "Link:        {}", reading(self.link_quality, 0, "%"))
+    }
+}
+
+fn check_angle(axis: &str, value: f32, min: f32, max: f32) -> Result<(), ControlError> {
+    if (min..=max).contains(&value) {
+        Ok(())
+    } else {
+        Err(ControlError::AngleOutOfRange {
+            axis: axis.to_string(),
+            value,
+            min,
+            max,
+        })
+    }
+}
+
+fn available(sensor: &str, value: f32) -> Result<f32, ControlError> {
+    if value.is_nan() {
+        Err(ControlError::SensorUnavailable {
+            sensor: sensor.to_string(),
+        })
+    } else {
+        Ok(value)
+    }
+}
+
+/// Gimbal angles reported by the gimbal telemetry
+///
+/// The angles are `NaN` until gimbal telemetry arrives.
+#[derive(Debug, Clone, Copy)]
+pub struct GimbalAngles {
+    /// Pitch in radians
+    pub pitch: f32,
+    /// Yaw in radians
+    pub yaw: f32,
+    /// Axes held at a hardware limit
+    pub limits: GimbalLimits,
+}
+
+impl Default for GimbalAngles {
+    fn default() -> Self {
+        Self {
+            pitch: f32::NAN,
+            yaw: f32::NAN,
+            limits: GimbalLimits::default(),
+        }
+    }
 }
 
 /// IMU data structure (placeholder)
-#[derive(Debug, Clone, Default)]
+///
+/// Every reading is `NaN` until IMU telemetry arrives.
+#[derive(Debug, Clone)]
 pub struct ImuData {
     /// Acceleration in m/s²
     pub acceleration: [f32; 3],
@@ -250,6 +2090,16 @@ pub struct ImuData {
     pub orientation: [f32; 3],
 }
 
+impl Default for ImuData {
+    fn default() -> Self {
+        Self {
+            acceleration: [f32::NAN; 3],
+            angular_velocity: [f32::NAN; 3],
+            orientation: [f32::NAN; 3],
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -267,6 +2117,22 @@ mod tests {
         assert_eq!(params.vz, -0.3);
     }
 
+    #[test]
+    fn test_diagonal_movement_is_normalized() {
+        let params = MovementCommand::diagonal_forward_right(1.0).into_params();
+        assert_eq!(params.vx, params.vy);
+        assert!((params.vx - 0.707).abs() < 1e-3);
+        assert!((params.vx.hypot(params.vy) - 1.0).abs() < 1e-6);
+        assert_eq!(params.vz, 0.0);
+
+        let params = MovementCommand::diagonal_backward_left(2.0).into_params();
+        assert!((params.vx.hypot(params.vy) - 1.0).abs() < 1e-6, "speed is clamped to 1.0");
+        assert!(params.vx < 0.0 && params.vy < 0.0);
+
+        let params = MovementCommand::toward(CompassDirection::Left, 0.5).into_params();
+        assert_eq!((params.vx, params.vy), (0.0, -0.5));
+    }
+
     #[test]
     fn test_movement_command_clamping() {
         let cmd = MovementCommand::new()
@@ -297,4 +2163,138 @@ mod tests {
         assert_eq!(color.green, 64);
         assert_eq!(color.blue, 192);
     }
+
+    #[test]
+    fn test_estimate_distance_uses_limits() {
+        let limits = RobotLimits::default();
+        let forward = MovementParams { vx: 1.0, vy: 0.0, vz: 0.0 };
+        let (dx, dy, _) = RoboMaster::estimate_distance(&forward, Duration::from_secs(2), &limits);
+        assert!((dx - 2.0 * limits.max_forward_speed).abs() < 1e-5);
+        assert_eq!(dy, 0.0);
+
+        let spin = MovementParams { vx: 0.0, vy: 0.0, vz: -1.0 };
+        let (_, _, dtheta) = RoboMaster::estimate_distance(&spin, Duration::from_millis(500), &limits);
+        assert!((dtheta + 0.5 * limits.max_rotation_speed).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_sensor_report_marks_missing_readings() {
+        let sensors = SensorData { current: 1.5, ..SensorData::default() };
+
+        let report = sensors.to_string();
+        assert!(report.contains("Battery:     n/a"));
+        assert!(report.contains("Current:     1.50 A"));
+        assert!(report.contains("Temperature: n/a"));
+        assert!(report.contains("Accel:       n/a"));
+        assert!(report.contains("Gyro:        n/a"));
+        assert!(report.contains("Link:        n/a"));
+
+        // One faulted component marks the whole vector unavailable
+        let mut sensors = SensorData::default();
+        sensors.imu.acceleration = [0.1, f32::NAN, 9.81];
+        sensors.imu.angular_velocity = [0.0, 0.5, -0.25];
+        let report = sensors.to_string();
+        assert!(report.contains("Accel:       n/a"));
+        assert!(report.contains("Gyro:        [0.00, 0.50, -0.25] rad/s"));
+    }
+
+    #[test]
+    fn test_gimbal_angle_range_check() {
+        assert!(check_angle("pitch", 35.0, MIN_GIMBAL_PITCH, MAX_GIMBAL_PITCH).is_ok());
+        assert!(matches!(
+            check_angle("pitch", -25.0, MIN_GIMBAL_PITCH, MAX_GIMBAL_PITCH),
+            Err(ControlError::AngleOutOfRange { axis, min, .. }) if axis == "pitch" && min == MIN_GIMBAL_PITCH
+        ));
+        assert!(check_angle("yaw", f32::NAN, -MAX_GIMBAL_YAW, MAX_GIMBAL_YAW).is_err());
+    }
+
+    #[test]
+    fn test_battery_voltage_unavailable_before_telemetry() {
+        let sensors = SensorData::default();
+        assert!(matches!(
+            sensors.battery_voltage(),
+            Err(ControlError::SensorUnavailable { sensor }) if sensor == "battery_voltage"
+        ));
+    }
+
+    #[test]
+    fn test_default_sensor_data_has_no_readings() {
+        let sensors = SensorData::default();
+        let imu = &sensors.imu;
+        let readings = [sensors.battery_voltage, sensors.current, sensors.temperature, sensors.link_quality]
+            .into_iter()
+            .chain(imu.acceleration)
+            .chain(imu.angular_velocity)
+            .chain(imu.orientation)
+            .chain([sensors.gimbal.pitch, sensors.gimbal.yaw])
+            .chain(sensors.motor_currents)
+            .chain(sensors.motor_temperatures);
+        assert!(readings.into_iter().all(f32::is_nan));
+        assert!(sensors.yaw().is_err());
+        assert!(sensors.to_string().contains("Yaw:         n/a"));
+    }
+
+    #[test]
+    fn test_wheel_anomaly_flags_high_current_wheel() {
+        let mut sensors = SensorData::default();
+        assert_eq!(sensors.wheel_anomaly(), None, "No motor telemetry yet");
+
+        sensors.motor_currents = [0.8, -0.7, 0.9, 0.75];
+        assert_eq!(sensors.wheel_anomaly(), None);
+
+        sensors.motor_currents = [0.8, -2.4, 0.9, 0.75];
+        assert_eq!(sensors.wheel_anomaly(), Some(1));
+
+        sensors.motor_currents = [0.01, 0.3, 0.0, 0.02];
+        assert_eq!(sensors.wheel_anomaly(), None, "Idle currents are not anomalies");
+    }
+
+    #[test]
+    fn test_recommended_command_rate_drops_with_bitrate() {
+        use crate::can::MockCanBackend;
+
+        let rate_at = |bitrate: u32| {
+            let mut can_interface = CanInterface::with_backend(MockCanBackend::new());
+            can_interface.set_bitrate(bitrate);
+            RoboMaster::with_interface(can_interface).recommended_command_rate().unwrap()
+        };
+
+        let fast = rate_at(1_000_000);
+        let slow = rate_at(125_000);
+        assert!(slow < fast, "{slow} Hz at 125 kbit/s vs {fast} Hz at 1 Mbit/s");
+        assert!(fast > crate::CONTROL_FREQUENCY);
+        assert!(slow >= 1);
+        assert_eq!(rate_at(1), 1);
+    }
+
+    #[tokio::test]
+    async fn test_counters_wrap_past_u16_max() {
+        use crate::can::MockCanBackend;
+
+        let mut robot = RoboMaster::with_interface(CanInterface::with_backend(MockCanBackend::new()));
+        robot.is_initialized = true;
+        {
+            let mut counters = robot.counters();
+            counters.joy = u16::MAX;
+            counters.led = u16::MAX - 1;
+            counters.gimbal = u16::MAX;
+        }
+
+        for _ in 0..3 {
+            robot.control_led(LedColor { red: 255, green: 0, blue: 0 }).await.unwrap();
+        }
+        robot.move_robot(MovementParams { vx: 0.5, vy: 0.0, vz: 0.0 }).await.unwrap();
+
+        let counters = robot.get_counters();
+        assert_eq!((counters.joy, counters.led, counters.gimbal), (0, 1, 0));
+    }
+
+    #[test]
+    fn test_battery_voltage_available_after_telemetry() {
+        let sensors = SensorData {
+            battery_voltage: 11.8,
+            ..SensorData::default()
+        };
+        assert_eq!(sensors.battery_voltage().unwrap(), 11.8);
+    }
 }
@@ -0,0 +1,168 @@
+//! Dead-reckoning pose estimate from commanded motion and IMU yaw
+
+use super::yaw::wrap_angle;
+use super::{RobotLimits, SensorData};
+use crate::command::MovementParams;
+use std::time::Duration;
+
+/// Estimated position and heading relative to the odometry origin
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Pose {
+    /// Distance along the initial forward direction in metres
+    pub x: f32,
+    /// Distance along the initial sideways direction in metres
+    pub y: f32,
+    /// Heading in radians, wrapped to -π..π
+    pub heading: f32,
+}
+
+/// Integrates the chassis velocity into a [`Pose`]
+///
+/// The S1 does not report chassis velocity, so the velocity is the last
+/// commanded movement converted with [`RobotLimits`]. Once IMU yaw is
+/// enabled with [`use_imu_heading`](Self::use_imu_heading) it replaces the
+/// integrated rotation, which removes most of the heading drift. Position
+/// still drifts with wheel slip and calibration.
+#[derive(Debug, Clone, Default)]
+pub struct Odometry {
+    limits: RobotLimits,
+    velocity: MovementParams,
+    pose: Pose,
+    imu_heading: bool,
+    yaw_origin: Option<f32>,
+}
+
+impl Odometry {
+    /// Create an estimator at the origin using `limits` to convert commands to speeds
+    pub fn new(limits: RobotLimits) -> Self {
+        Self {
+            limits,
+            ..Self::default()
+        }
+    }
+
+    /// Set the speeds reached at full command
+    pub fn set_limits(&mut self, limits: RobotLimits) {
+        self.limits = limits;
+    }
+
+    /// Take the heading from the IMU yaw instead of the commanded rotation
+    ///
+    /// Only enable this once IMU telemetry is arriving, since the yaw reads
+    /// zero until then.
+    pub fn use_imu_heading(&mut self, enabled: bool) {
+        self.imu_heading = enabled;
+    }
+
+    /// Set the movement the robot is executing from now on
+    pub fn set_velocity(&mut self, movement: MovementParams) {
+        self.velocity = movement;
+    }
+
+    /// Advance the estimate by `dt` at the current velocity
+    ///
+    /// With IMU heading enabled the heading follows the yaw in `sensor_data`
+    /// unless the IMU reported a fault; otherwise it is integrated from the
+    /// commanded rotation.
+    pub fn update(&mut self, sensor_data: &SensorData, dt: Duration) {
+        let t = dt.as_secs_f32();
+        let start = self.pose.heading;
+        let yaw = sensor_data.yaw().ok().filter(|_| self.imu_heading);
+        let heading = match yaw {
+            Some(yaw) => {
+                // Line the IMU up with the current estimate the first time it reports
+                let origin = *self.yaw_origin.get_or_insert(yaw - start);
+                wrap_angle(yaw - origin)
+            }
+            None => {
+                let omega = self.velocity.vz.clamp(-crate::MAX_SPEED, crate::MAX_SPEED) * self.limits.max_rotation_speed;
+                wrap_angle(start + omega * t)
+            }
+        };
+
+        // Drive along the average heading over the step
+        let mid = start + wrap_angle(heading - start) / 2.0;
+        let vx = self.velocity.vx.clamp(-crate::MAX_SPEED, crate::MAX_SPEED) * self.limits.max_forward_speed;
+        let vy = self.velocity.vy.clamp(-crate::MAX_SPEED, crate::MAX_SPEED) * self.limits.max_strafe_speed;
+        self.pose.x += (vx * mid.cos() - vy * mid.sin()) * t;
+        self.pose.y += (vx * mid.sin() + vy * mid.cos()) * t;
+        self.pose.heading = heading;
+    }
+
+    /// Get the current estimate
+    pub fn pose(&self) -> Pose {
+        self.pose
+    }
+
+    /// Make the current position and heading the new origin
+    pub fn reset(&mut self) {
+        self.pose = Pose::default();
+        self.yaw_origin = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::PI;
+
+    const LIMITS: RobotLimits = RobotLimits {
+        max_forward_speed: 2.0,
+        max_strafe_speed: 1.0,
+        max_rotation_speed: PI,
+    };
+
+    #[test]
+    fn test_commanded_forward_then_strafe() {
+        let mut odometry = Odometry::new(LIMITS);
+        let sensors = SensorData::default();
+
+        odometry.set_velocity(MovementParams { vx: 0.5, vy: 0.0, vz: 0.0 });
+        odometry.update(&sensors, Duration::from_secs(2));
+        odometry.set_velocity(MovementParams { vx: 0.0, vy: 1.0, vz: 0.0 });
+        odometry.update(&sensors, Duration::from_millis(500));
+
+        let pose = odometry.pose();
+        assert!((pose.x - 2.0).abs() < 1e-5);
+        assert!((pose.y - 0.5).abs() < 1e-5);
+        assert_eq!(pose.heading, 0.0);
+    }
+
+    #[test]
+    fn test_heading_wraps_at_pi() {
+        let mut odometry = Odometry::new(LIMITS);
+        let sensors = SensorData::default();
+
+        // Half a turn per second for 1.5 s ends three quarters round
+        odometry.set_velocity(MovementParams { vx: 0.0, vy: 0.0, vz: 1.0 });
+        for _ in 0..15 {
+            odometry.update(&sensors, Duration::from_millis(100));
+        }
+        assert!((odometry.pose().heading + PI / 2.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_imu_yaw_overrides_commanded_rotation() {
+        let mut odometry = Odometry::new(LIMITS);
+        let mut sensors = SensorData::default();
+        odometry.use_imu_heading(true);
+        odometry.set_velocity(MovementParams { vx: 0.5, vy: 0.0, vz: 1.0 });
+
+        sensors.imu.orientation[2] = 1.0;
+        odometry.update(&sensors, Duration::ZERO);
+        assert_eq!(odometry.pose().heading, 0.0, "first reading becomes the origin");
+
+        sensors.imu.orientation[2] = 1.0 + PI / 2.0;
+        odometry.update(&sensors, Duration::from_secs(1));
+        let pose = odometry.pose();
+        assert!((pose.heading - PI / 2.0).abs() < 1e-5);
+        // One metre along the average heading of π/4
+        assert!((pose.x - pose.y).abs() < 1e-5);
+        assert!((pose.x - std::f32::consts::FRAC_1_SQRT_2).abs() < 1e-5);
+
+        odometry.reset();
+        assert_eq!(odometry.pose(), Pose::default());
+        odometry.update(&sensors, Duration::ZERO);
+        assert_eq!(odometry.pose().heading, 0.0);
+    }
+}
@@ -0,0 +1,123 @@
+//! Detection of movement commands issued faster than the bus can send them
+
+use std::time::{Duration, Instant};
+
+/// Minimum interval between two overrun warnings
+pub const OVERRUN_WARNING_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Tracks sends that take longer than the interval between commands
+///
+/// When a send takes longer than the gap since the previous command, the
+/// caller is producing commands faster than the CAN bus can drain them and
+/// the TX queue grows.
+#[derive(Debug, Clone)]
+pub struct OverrunDetector {
+    overrun_count: u64,
+    last_command: Option<Instant>,
+    last_warning: Option<Instant>,
+    warning_interval: Duration,
+}
+
+impl OverrunDetector {
+    /// Create a new overrun detector
+    pub fn new() -> Self {
+        Self {
+            overrun_count: 0,
+            last_command: None,
+            last_warning: None,
+            warning_interval: OVERRUN_WARNING_INTERVAL,
+        }
+    }
+
+    /// Record a send that started at `started` and took `send_duration`
+    ///
+    /// Returns `true` if the send overran the command interval.
+    pub fn record_send(&mut self, started: Instant, send_duration: Duration) -> bool {
+        let command_interval = self.last_command.map(|last| started.saturating_duration_since(last));
+        self.last_command = Some(started);
+
+        let overrun = matches!(command_interval, Some(interval) if send_duration > interval);
+        if !overrun {
+            return false;
+        }
+
+        self.overrun_count += 1;
+
+        let warn = match self.last_warning {
+            Some(last) => started.saturating_duration_since(last) >= self.warning_interval,
+            None => true,
+        };
+        if warn {
+            self.last_warning = Some(started);
+            tracing::warn!(
+                overrun_count = self.overrun_count,
+                send_us = send_duration.as_micros() as u64,
+                interval_us = command_interval.unwrap_or_default().as_micros() as u64,
+                "movement commands are issued faster than the CAN bus can send them"
+            );
+        }
+
+        true
+    }
+
+    /// Get the number of overruns detected so far
+    pub fn overrun_count(&self) -> u64 {
+        self.overrun_count
+    }
+
+    /// Reset the overrun counter
+    pub fn reset(&mut self) {
+        self.overrun_count = 0;
+        self.last_command = None;
+        self.last_warning = None;
+    }
+}
+
+impl Default for OverrunDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slow_sends_count_as_overruns() {
+        let mut detector = OverrunDetector::new();
+        let start = Instant::now();
+
+        // Commands every 10ms, each send taking 15ms
+        for i in 0..5 {
+            detector.record_send(start + Duration::from_millis(i * 10), Duration::from_millis(15));
+        }
+
+        // The first send has no previous command to compare against
+        assert_eq!(detector.overrun_count(), 4);
+    }
+
+    #[test]
+    fn test_fast_sends_do_not_overrun() {
+        let mut detector = OverrunDetector::new();
+        let start = Instant::now();
+
+        for i in 0..5 {
+            assert!(!detector.record_send(start + Duration::from_millis(i * 10), Duration::from_millis(1)));
+        }
+        assert_eq!(detector.overrun_count(), 0);
+    }
+
+    #[test]
+    fn test_overrun_reset() {
+        let mut detector = OverrunDetector::new();
+        let start = Instant::now();
+
+        detector.record_send(start, Duration::from_millis(5));
+        detector.record_send(start + Duration::from_millis(1), Duration::from_millis(5));
+        assert_eq!(detector.overrun_count(), 1);
+
+        detector.reset();
+        assert_eq!(detector.overrun_count(), 0);
+    }
+}
@@ -0,0 +1,165 @@
+//! Acceleration profiles shaping timed drives
+
+use crate::command::MovementParams;
+use crate::error::RoboMasterError;
+use std::time::Duration;
+
+/// Shape of the ramp up to and down from the target velocity
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AccelProfile {
+    /// Constant acceleration, so speed changes linearly during the ramps
+    #[default]
+    Trapezoidal,
+    /// Acceleration that eases in and out, for less jerk at the start and
+    /// end of each ramp
+    SCurve,
+}
+
+impl AccelProfile {
+    /// Map the linear progress `x` (0..=1) through a ramp to a speed fraction
+    pub fn shape(self, x: f32) -> f32 {
+        let x = x.clamp(0.0, 1.0);
+        match self {
+            Self::Trapezoidal => x,
+            Self::SCurve => x * x * (3.0 - 2.0 * x),
+        }
+    }
+}
+
+/// Velocities to command for a timed drive shaped by an [`AccelProfile`]
+///
+/// Yields one velocity per `period`, ramping up to `target` over
+/// `ramp_time`, holding it and ramping back down so that the last velocity,
+/// at `duration`, is zero. A ramp time longer than half the duration is
+/// shortened to fit.
+#[derive(Debug, Clone)]
+pub struct ProfiledDrive {
+    target: MovementParams,
+    profile: AccelProfile,
+    duration: f32,
+    ramp_time: f32,
+    period: f32,
+    steps: u32,
+    step: u32,
+}
+
+impl ProfiledDrive {
+    /// Create the steps of a drive toward `target` lasting `duration`
+    pub fn new(
+        target: MovementParams,
+        duration: Duration,
+        ramp_time: Duration,
+        profile: AccelProfile,
+        period: Duration,
+    ) -> Result<Self, RoboMasterError> {
+        if period.is_zero() {
+            return Err(RoboMasterError::InvalidParameter {
+                parameter: "period".to_string(),
+                value: format!("{:?}", period),
+            });
+        }
+
+        let duration = duration.as_secs_f32();
+        let period = period.as_secs_f32();
+        Ok(Self {
+            target,
+            profile,
+            duration,
+            ramp_time: ramp_time.as_secs_f32().min(duration / 2.0),
+            period,
+            steps: (duration / period).ceil() as u32,
+            step: 0,
+        })
+    }
+
+    /// Speed fraction at `elapsed` seconds into the drive
+    fn fraction(&self, elapsed: f32) -> f32 {
+        let remaining = self.duration - elapsed;
+        let progress = if self.ramp_time > 0.0 {
+            (elapsed.min(remaining) / self.ramp_time).min(1.0)
+        } else if remaining > 0.0 {
+            1.0
+        } else {
+            0.0
+        };
+        self.profile.shape(progress)
+    }
+}
+
+impl Iterator for ProfiledDrive {
+    type Item = MovementParams;
+
+    fn next(&mut self) -> Option<MovementParams> {
+        if self.step >= self.steps {
+            return None;
+        }
+
+        self.step += 1;
+        let fraction = self.fraction((self.step as f32 * self.period).min(self.duration));
+        Some(MovementParams {
+            vx: self.target.vx * fraction,
+            vy: self.target.vy * fraction,
+            vz: self.target.vz * fraction,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PERIOD: Duration = Duration::from_millis(100);
+    const TARGET: MovementParams = MovementParams { vx: 1.0, vy: 0.0, vz: 0.0 };
+
+    fn speeds(profile: AccelProfile) -> Vec<f32> {
+        ProfiledDrive::new(TARGET, Duration::from_secs(2), Duration::from_millis(500), profile, PERIOD)
+            .unwrap()
+            .map(|step| step.vx)
+            .collect()
+    }
+
+    #[test]
+    fn test_trapezoidal_vs_s_curve() {
+        let trapezoidal = speeds(AccelProfile::Trapezoidal);
+        let s_curve = speeds(AccelProfile::SCurve);
+        let close = |a: f32, b: f32| (a - b).abs() < 1e-5;
+
+        assert_eq!(trapezoidal.len(), 20);
+        assert_eq!(s_curve.len(), 20);
+        for (expected, speed) in [0.2, 0.4, 0.6, 0.8, 1.0].into_iter().zip(&trapezoidal) {
+            assert!(close(*speed, expected), "{:?}", trapezoidal);
+        }
+        for (expected, speed) in [0.104, 0.352, 0.648, 0.896, 1.0].into_iter().zip(&s_curve) {
+            assert!(close(*speed, expected), "{:?}", s_curve);
+        }
+
+        // Same plateau and a symmetric ramp down to a stop
+        assert_eq!(trapezoidal[4..15], s_curve[4..15]);
+        for profile in [&trapezoidal, &s_curve] {
+            assert_eq!(*profile.last().unwrap(), 0.0);
+            assert!(close(profile[0], profile[18]));
+        }
+
+        // The s-curve starts gentler but peaks at a higher acceleration
+        let max_change = |speeds: &[f32]| speeds.windows(2).map(|w| (w[1] - w[0]).abs()).fold(0.0, f32::max);
+        assert!(s_curve[0] < trapezoidal[0]);
+        assert!(max_change(&s_curve) > max_change(&trapezoidal));
+    }
+
+    #[test]
+    fn test_short_drive_shortens_ramps() {
+        let steps: Vec<f32> = ProfiledDrive::new(TARGET, Duration::from_millis(400), Duration::from_secs(1), AccelProfile::Trapezoidal, PERIOD)
+            .unwrap()
+            .map(|step| step.vx)
+            .collect();
+        assert_eq!(steps.len(), 4);
+        assert!((steps[1] - 1.0).abs() < 1e-5);
+        assert_eq!(steps[3], 0.0);
+    }
+
+    #[test]
+    fn test_zero_period_rejected() {
+        let result = ProfiledDrive::new(TARGET, Duration::from_secs(1), Duration::ZERO, AccelProfile::SCurve, Duration::ZERO);
+        assert!(result.is_err());
+    }
+}
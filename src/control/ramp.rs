@@ -0,0 +1,130 @@
+//! Velocity ramping toward a target movement
+
+use crate::command::MovementParams;
+use crate::error::RoboMasterError;
+use std::time::Duration;
+
+/// Steps a commanded velocity toward a target at a bounded acceleration
+///
+/// Each step moves every axis toward the target by at most
+/// `accel * period`. Iterating yields the velocity to command at each step
+/// and ends once the target has been reached.
+#[derive(Debug, Clone)]
+pub struct VelocityRamp {
+    current: MovementParams,
+    target: MovementParams,
+    max_step: f32,
+}
+
+impl VelocityRamp {
+    /// Create a ramp from `start` to `target`
+    ///
+    /// `accel` is in speed units per second and `period` is the interval
+    /// between steps.
+    pub fn new(
+        start: MovementParams,
+        target: MovementParams,
+        accel: f32,
+        period: Duration,
+    ) -> Result<Self, RoboMasterError> {
+        let max_step = accel * period.as_secs_f32();
+        if !max_step.is_finite() || max_step <= 0.0 {
+            return Err(RoboMasterError::InvalidParameter {
+                parameter: "accel".to_string(),
+                value: accel.to_string(),
+            });
+        }
+
+        Ok(Self {
+            current: start,
+            target,
+            max_step,
+        })
+    }
+
+    /// Get the most recently stepped velocity
+    pub fn current(&self) -> MovementParams {
+        self.current
+    }
+
+    /// Check whether the target has been reached
+    pub fn is_complete(&self) -> bool {
+        self.current.vx == self.target.vx
+            && self.current.vy == self.target.vy
+            && self.current.vz == self.target.vz
+    }
+
+    fn step_axis(&self, current: f32, target: f32) -> f32 {
+        let delta = target - current;
+        if delta.abs() <= self.max_step {
+            target
+        } else {
+            current + self.max_step.copysign(delta)
+        }
+    }
+}
+
+impl Iterator for VelocityRamp {
+    type Item = MovementParams;
+
+    fn next(&mut self) -> Option<MovementParams> {
+        if self.is_complete() {
+            return None;
+        }
+
+        self.current = MovementParams {
+            vx: self.step_axis(self.current.vx, self.target.vx),
+            vy: self.step_axis(self.current.vy, self.target.vy),
+            vz: self.step_axis(self.current.vz, self.target.vz),
+        };
+        Some(self.current)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PERIOD: Duration = Duration::from_millis(50);
+
+    #[test]
+    fn test_ramp_reaches_target_in_expected_steps() {
+        let target = MovementParams { vx: 1.0, vy: 0.0, vz: 0.0 };
+        let ramp = VelocityRamp::new(MovementParams::default(), target, 2.0, PERIOD).unwrap();
+
+        let steps: Vec<_> = ramp.collect();
+        assert_eq!(steps.len(), 10);
+        assert_eq!(steps.last().unwrap().vx, 1.0);
+        assert!(steps.windows(2).all(|w| w[1].vx > w[0].vx));
+    }
+
+    #[test]
+    fn test_ramp_axes_finish_independently() {
+        let start = MovementParams { vx: 0.0, vy: 0.5, vz: 0.0 };
+        let target = MovementParams { vx: 0.5, vy: 0.0, vz: -0.1 };
+        let mut ramp = VelocityRamp::new(start, target, 5.0, PERIOD).unwrap();
+
+        let first = ramp.next().unwrap();
+        assert_eq!(first.vx, 0.25);
+        assert_eq!(first.vy, 0.25);
+        assert_eq!(first.vz, -0.1);
+
+        assert_eq!(ramp.count(), 1);
+    }
+
+    #[test]
+    fn test_ramp_at_target_is_complete() {
+        let target = MovementParams { vx: 0.3, vy: 0.0, vz: 0.0 };
+        let mut ramp = VelocityRamp::new(target, target, 1.0, PERIOD).unwrap();
+        assert!(ramp.is_complete());
+        assert!(ramp.next().is_none());
+    }
+
+    #[test]
+    fn test_invalid_acceleration_rejected() {
+        let target = MovementParams::default();
+        assert!(VelocityRamp::new(target, target, 0.0, PERIOD).is_err());
+        assert!(VelocityRamp::new(target, target, -1.0, PERIOD).is_err());
+        assert!(VelocityRamp::new(target, target, f32::NAN, PERIOD).is_err());
+    }
+}
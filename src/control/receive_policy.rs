@@ -0,0 +1,80 @@
+//! Policy for handling errors on the receive path
+
+use crate::error::RoboMasterError;
+
+/// How [`RoboMaster::receive_messages`](crate::control::RoboMaster::receive_messages)
+/// reacts to a receive error
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ReceiveErrorPolicy {
+    /// Return the error to the caller
+    #[default]
+    Abort,
+    /// Log the error and carry on as if nothing was received
+    LogAndIgnore,
+    /// Log the error and reopen the CAN interface
+    Reconnect,
+}
+
+/// Action the controller takes after a receive error was handled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReceiveErrorAction {
+    /// Continue with the next receive
+    Continue,
+    /// Reopen the CAN interface before continuing
+    Reconnect,
+}
+
+impl ReceiveErrorPolicy {
+    /// Decide what to do with a receive error
+    ///
+    /// Returns the error itself when the policy is [`Abort`](Self::Abort).
+    pub fn handle(self, error: RoboMasterError) -> Result<ReceiveErrorAction, RoboMasterError> {
+        match self {
+            Self::Abort => Err(error),
+            Self::LogAndIgnore => {
+                tracing::warn!(category = error.category(), "ignoring receive error: {}", error);
+                Ok(ReceiveErrorAction::Continue)
+            }
+            Self::Reconnect => {
+                tracing::warn!(category = error.category(), "reconnecting after receive error: {}", error);
+                Ok(ReceiveErrorAction::Reconnect)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::CanError;
+
+    fn receive_failure() -> RoboMasterError {
+        RoboMasterError::CanInterface(CanError::ReceiveFailed(std::io::Error::new(
+            std::io::ErrorKind::BrokenPipe,
+            "simulated receive failure",
+        )))
+    }
+
+    #[test]
+    fn test_abort_returns_error() {
+        let result = ReceiveErrorPolicy::Abort.handle(receive_failure());
+        assert!(matches!(result, Err(RoboMasterError::CanInterface(CanError::ReceiveFailed(_)))));
+    }
+
+    #[test]
+    fn test_log_and_ignore_continues() {
+        let result = ReceiveErrorPolicy::LogAndIgnore.handle(receive_failure());
+        assert_eq!(result.unwrap(), ReceiveErrorAction::Continue);
+    }
+
+    #[test]
+    fn test_reconnect_requests_reconnection() {
+        let result = ReceiveErrorPolicy::Reconnect.handle(receive_failure());
+        assert_eq!(result.unwrap(), ReceiveErrorAction::Reconnect);
+    }
+
+    #[test]
+    fn test_default_policy_is_abort() {
+        assert_eq!(ReceiveErrorPolicy::default(), ReceiveErrorPolicy::Abort);
+    }
+}
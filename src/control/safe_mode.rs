@@ -0,0 +1,101 @@
+//! Hard output ceiling for supervised environments
+
+use crate::command::{GimbalParams, MovementParams};
+
+/// Speed ceiling applied after every other movement stage
+///
+/// While enabled, every chassis axis and gimbal rate is clamped to the
+/// ceiling. Enabling again can only lower the ceiling; raising it requires
+/// disabling safe mode first.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SafeMode {
+    ceiling: Option<f32>,
+}
+
+impl SafeMode {
+    /// Enable safe mode, or lower the ceiling if it is already enabled
+    pub fn enable(&mut self, max_speed: f32) {
+        let max_speed = if max_speed.is_nan() { 0.0 } else { max_speed.clamp(0.0, crate::MAX_SPEED) };
+        self.ceiling = Some(match self.ceiling {
+            Some(current) if current < max_speed => {
+                tracing::warn!(current, requested = max_speed, "safe mode ceiling can only be lowered");
+                current
+            }
+            _ => max_speed,
+        });
+    }
+
+    /// Disable safe mode
+    pub fn disable(&mut self) {
+        self.ceiling = None;
+    }
+
+    /// Get the active ceiling, if safe mode is enabled
+    pub fn ceiling(&self) -> Option<f32> {
+        self.ceiling
+    }
+
+    /// Cap a chassis movement
+    pub fn cap_movement(&self, movement: MovementParams) -> MovementParams {
+        MovementParams {
+            vx: self.cap(movement.vx),
+            vy: self.cap(movement.vy),
+            vz: self.cap(movement.vz),
+        }
+    }
+
+    /// Cap a gimbal command
+    pub fn cap_gimbal(&self, gimbal: GimbalParams) -> GimbalParams {
+        GimbalParams {
+            ry: self.cap(gimbal.ry),
+            rz: self.cap(gimbal.rz),
+        }
+    }
+
+    fn cap(&self, value: f32) -> f32 {
+        match self.ceiling {
+            Some(ceiling) => value.clamp(-ceiling, ceiling),
+            None => value,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FULL_SPEED: MovementParams = MovementParams { vx: 1.0, vy: -1.0, vz: 1.0 };
+
+    #[test]
+    fn test_full_speed_is_capped() {
+        let mut safe_mode = SafeMode::default();
+        safe_mode.enable(0.3);
+
+        let movement = safe_mode.cap_movement(FULL_SPEED);
+        assert_eq!((movement.vx, movement.vy, movement.vz), (0.3, -0.3, 0.3));
+
+        let gimbal = safe_mode.cap_gimbal(GimbalParams { ry: -5.0, rz: 5.0 });
+        assert_eq!((gimbal.ry, gimbal.rz), (-0.3, 0.3));
+    }
+
+    #[test]
+    fn test_ceiling_cannot_be_raised_while_enabled() {
+        let mut safe_mode = SafeMode::default();
+        safe_mode.enable(0.3);
+        safe_mode.enable(0.8);
+        assert_eq!(safe_mode.ceiling(), Some(0.3));
+
+        safe_mode.enable(0.1);
+        assert_eq!(safe_mode.ceiling(), Some(0.1));
+
+        safe_mode.disable();
+        safe_mode.enable(0.8);
+        assert_eq!(safe_mode.ceiling(), Some(0.8));
+    }
+
+    #[test]
+    fn test_disabled_passes_through() {
+        let movement = SafeMode::default().cap_movement(FULL_SPEED);
+        assert_eq!(movement.vx, 1.0);
+    }
+}
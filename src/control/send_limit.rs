@@ -0,0 +1,105 @@
+//! Backpressure on concurrent command sends
+
+use std::sync::Arc;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// Default number of commands allowed to be sending at once
+pub const DEFAULT_MAX_IN_FLIGHT: usize = 4;
+
+/// Limits how many commands may be written to the bus at the same time
+///
+/// Every send holds a permit for the whole command, so a multi-frame command
+/// is never interleaved with more than `limit - 1` others. Senders beyond the
+/// limit wait for a permit instead of flooding the TX queue. Clones share
+/// the same permits.
+#[derive(Debug, Clone)]
+pub struct SendLimiter {
+    semaphore: Arc<Semaphore>,
+    limit: usize,
+}
+
+impl SendLimiter {
+    /// Create a limiter allowing `limit` concurrent sends (at least one)
+    pub fn new(limit: usize) -> Self {
+        let limit = limit.max(1);
+        Self {
+            semaphore: Arc::new(Semaphore::new(limit)),
+            limit,
+        }
+    }
+
+    /// Wait until a send is allowed
+    ///
+    /// The send slot is released when the permit is dropped.
+    pub async fn acquire(&self) -> OwnedSemaphorePermit {
+        Arc::clone(&self.semaphore)
+            .acquire_owned()
+            .await
+            .expect("send limiter semaphore is never closed")
+    }
+
+    /// Block the calling thread until a send is allowed
+    ///
+    /// Meant for callers without an async runtime. Calling it from inside an
+    /// async task blocks that task's worker thread.
+    pub fn acquire_blocking(&self) -> OwnedSemaphorePermit {
+        futures::executor::block_on(self.acquire())
+    }
+
+    /// Get the number of concurrent sends allowed
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Get the number of sends that could start right now
+    pub fn available(&self) -> usize {
+        self.semaphore.available_permits()
+    }
+}
+
+impl Default for SendLimiter {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_IN_FLIGHT)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    #[test]
+    fn test_limit_is_at_least_one() {
+        assert_eq!(SendLimiter::new(0).limit(), 1);
+        assert_eq!(SendLimiter::default().available(), DEFAULT_MAX_IN_FLIGHT);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_excess_senders_wait_for_a_permit() {
+        let limiter = SendLimiter::new(2);
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+
+        let tasks: Vec<_> = (0..10)
+            .map(|_| {
+                let limiter = limiter.clone();
+                let in_flight = Arc::clone(&in_flight);
+                let peak = Arc::clone(&peak);
+                tokio::spawn(async move {
+                    let _permit = limiter.acquire().await;
+                    let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(5)).await;
+                    in_flight.fetch_sub(1, Ordering::SeqCst);
+                })
+            })
+            .collect();
+        for task in tasks {
+            task.await.unwrap();
+        }
+
+        assert!(peak.load(Ordering::SeqCst) <= 2);
+        assert_eq!(limiter.available(), 2);
+    }
+}
@@ -0,0 +1,137 @@
+//! Robot settings that can be applied in a single call
+
+use crate::command::LedColor;
+use crate::control::MovementFilter;
+use crate::error::ConfigError;
+use serde::Deserialize;
+use std::time::Duration;
+
+/// Runtime settings for a [`RoboMaster`](crate::control::RoboMaster)
+///
+/// All fields are optional in TOML; missing fields keep their defaults.
+///
+/// ```toml
+/// deadzone = 0.08
+/// max_speed = 0.5
+/// min_axis_change = 0.003
+/// min_effective_speed = 0.05
+/// gimbal_follow = false
+/// failsafe_timeout_ms = 500
+/// status_led = { red = 0, green = 255, blue = 0 }
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct RobotSettings {
+    /// Movement inputs below this magnitude are treated as zero (0.0 to 1.0)
+    pub deadzone: f32,
+    /// Multiplier applied to every movement (0.0 to 1.0)
+    pub max_speed: f32,
+    /// Movements are only resent when an axis changes by more than this (0.0 to 1.0)
+    pub min_axis_change: f32,
+    /// Nonzero movement axes are raised to at least this speed (0.0 to 1.0)
+    pub min_effective_speed: f32,
+    /// Whether the gimbal yaw follows chassis rotation
+    pub gimbal_follow: bool,
+    /// Stop the robot if no movement is commanded for this many milliseconds
+    pub failsafe_timeout_ms: Option<u64>,
+    /// LED color shown once the settings are applied
+    pub status_led: Option<LedColor>,
+}
+
+impl Default for RobotSettings {
+    fn default() -> Self {
+        Self {
+            deadzone: 0.0,
+            max_speed: crate::MAX_SPEED,
+            min_axis_change: 0.0,
+            min_effective_speed: 0.0,
+            gimbal_follow: true,
+            failsafe_timeout_ms: None,
+            status_led: None,
+        }
+    }
+}
+
+impl RobotSettings {
+    /// Parse settings from a TOML string
+    pub fn from_toml_str(content: &str) -> Result<Self, ConfigError> {
+        Ok(toml::from_str(content)?)
+    }
+
+    /// Get the failsafe timeout as a duration
+    pub fn failsafe_timeout(&self) -> Option<Duration> {
+        self.failsafe_timeout_ms.map(Duration::from_millis)
+    }
+
+    /// Build the movement filter described by these settings
+    pub fn movement_filter(&self) -> MovementFilter {
+        let mut filter = MovementFilter::new();
+        filter.set_deadzone(self.deadzone);
+        filter.set_max_speed(self.max_speed);
+        filter.set_min_axis_change(self.min_axis_change);
+        filter.set_min_effective_speed(self.min_effective_speed);
+        filter
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::MovementParams;
+
+    #[test]
+    fn test_settings_from_toml() {
+        let settings = RobotSettings::from_toml_str(
+            r#"
+            deadzone = 0.1
+            max_speed = 0.5
+            min_axis_change = 0.01
+            min_effective_speed = 0.05
+            gimbal_follow = false
+            failsafe_timeout_ms = 250
+            status_led = { red = 0, green = 255, blue = 0 }
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(settings.deadzone, 0.1);
+        assert_eq!(settings.max_speed, 0.5);
+        assert_eq!(settings.movement_filter().min_axis_change(), 0.01);
+        assert_eq!(settings.movement_filter().min_effective_speed(), 0.05);
+        assert!(!settings.gimbal_follow);
+        assert_eq!(settings.failsafe_timeout(), Some(Duration::from_millis(250)));
+        assert_eq!(settings.status_led, Some(LedColor { red: 0, green: 255, blue: 0 }));
+    }
+
+    #[test]
+    fn test_missing_fields_use_defaults() {
+        let settings = RobotSettings::from_toml_str("max_speed = 0.3").unwrap();
+        assert_eq!(settings.max_speed, 0.3);
+        assert_eq!(settings.deadzone, RobotSettings::default().deadzone);
+        assert!(settings.gimbal_follow);
+        assert_eq!(settings.failsafe_timeout(), None);
+    }
+
+    #[test]
+    fn test_invalid_toml_is_config_error() {
+        assert!(matches!(
+            RobotSettings::from_toml_str("max_speed = \"fast\""),
+            Err(ConfigError::ParseFailed(_))
+        ));
+    }
+
+    #[test]
+    fn test_settings_filter_respects_max_speed() {
+        let settings = RobotSettings {
+            max_speed: 0.5,
+            deadzone: 0.1,
+            ..Default::default()
+        };
+        let filtered = settings
+            .movement_filter()
+            .apply(MovementParams { vx: 1.0, vy: 0.05, vz: 0.0 });
+
+        assert_eq!(filtered.vx, 0.5);
+        assert_eq!(filtered.vy, 0.0);
+    }
+}
@@ -0,0 +1,59 @@
+//! Clean shutdown on termination signals
+
+use super::RoboMaster;
+use crate::error::RoboMasterError;
+use std::future::Future;
+use std::io;
+
+/// Wait until the process is asked to terminate
+///
+/// Resolves on SIGINT (Ctrl-C) or, on Unix, SIGTERM, which is what
+/// service managers such as systemd send when stopping a unit.
+pub async fn termination_signal() -> io::Result<()> {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let mut terminate = signal(SignalKind::terminate())?;
+        tokio::select! {
+            result = tokio::signal::ctrl_c() => result,
+            _ = terminate.recv() => Ok(()),
+        }
+    }
+
+    #[cfg(not(unix))]
+    {
+        tokio::signal::ctrl_c().await
+    }
+}
+
+impl RoboMaster {
+    /// Stop the chassis if the robot was initialized, then shut down
+    pub async fn stop_and_shutdown(mut self) -> Result<(), RoboMasterError> {
+        if self.is_initialized {
+            self.stop().await?;
+        }
+        self.shutdown().await
+    }
+
+    /// Wait for `signal`, then stop and shut down
+    ///
+    /// See [`shutdown_on_signal`](Self::shutdown_on_signal) for the usual
+    /// termination signals.
+    pub async fn shutdown_after<F>(self, signal: F) -> Result<(), RoboMasterError>
+    where
+        F: Future<Output = io::Result<()>>,
+    {
+        signal.await?;
+        self.stop_and_shutdown().await
+    }
+
+    /// Stop and shut down once SIGINT or SIGTERM is received
+    ///
+    /// To keep driving the robot until then, select on
+    /// [`termination_signal`] in the control loop and call
+    /// [`stop_and_shutdown`](Self::stop_and_shutdown) afterwards.
+    pub async fn shutdown_on_signal(self) -> Result<(), RoboMasterError> {
+        self.shutdown_after(termination_signal()).await
+    }
+}
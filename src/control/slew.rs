@@ -0,0 +1,118 @@
+//! Slew-rate limiting of commanded movements
+
+use crate::command::MovementParams;
+use crate::error::RoboMasterError;
+use std::time::{Duration, Instant};
+
+/// Longest gap between updates that still counts toward the allowed change
+///
+/// Without a cap, a movement sent after a long pause could jump straight
+/// to its target.
+pub const MAX_SLEW_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Caps how fast each axis of a movement may change
+///
+/// Unlike [`VelocityRamp`](super::VelocityRamp), which steps toward a fixed
+/// target, the limiter follows a target that may change on every call, so
+/// raw joystick values can be fed through it each control tick.
+#[derive(Debug, Clone)]
+pub struct SlewLimiter {
+    rate: f32,
+    output: MovementParams,
+    last_update: Option<Instant>,
+}
+
+impl SlewLimiter {
+    /// Create a limiter allowing `rate` speed units per second on each axis
+    pub fn new(rate: f32) -> Result<Self, RoboMasterError> {
+        if !rate.is_finite() || rate <= 0.0 {
+            return Err(RoboMasterError::InvalidParameter {
+                parameter: "rate".to_string(),
+                value: rate.to_string(),
+            });
+        }
+
+        Ok(Self {
+            rate,
+            output: MovementParams::stopped(),
+            last_update: None,
+        })
+    }
+
+    /// Get the allowed change per second
+    pub fn rate(&self) -> f32 {
+        self.rate
+    }
+
+    /// Get the most recent limited movement
+    pub fn output(&self) -> MovementParams {
+        self.output
+    }
+
+    /// Step toward `target` by at most the change allowed since the last call
+    ///
+    /// The first call after creation or [`reset`](Self::reset) only starts
+    /// the clock and returns the current output.
+    pub fn apply(&mut self, target: MovementParams, now: Instant) -> MovementParams {
+        let elapsed = self
+            .last_update
+            .map_or(Duration::ZERO, |last| now.saturating_duration_since(last))
+            .min(MAX_SLEW_INTERVAL);
+        self.last_update = Some(now);
+
+        let max_step = self.rate * elapsed.as_secs_f32();
+        let step = |current: f32, target: f32| current + (target - current).clamp(-max_step, max_step);
+        self.output = MovementParams {
+            vx: step(self.output.vx, target.vx),
+            vy: step(self.output.vy, target.vy),
+            vz: step(self.output.vz, target.vz),
+        };
+        self.output
+    }
+
+    /// Restart from `output`, e.g. after the robot was stopped directly
+    pub fn reset(&mut self, output: MovementParams) {
+        self.output = output;
+        self.last_update = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_step_input_reaches_target_over_expected_ticks() {
+        let mut limiter = SlewLimiter::new(2.0).unwrap();
+        let target = MovementParams { vx: 1.0, vy: -0.5, vz: 0.0 };
+        let tick = Duration::from_millis(100);
+        let start = Instant::now();
+
+        assert_eq!(limiter.apply(target, start), MovementParams::stopped());
+        let outputs: Vec<MovementParams> = (1..=5).map(|i| limiter.apply(target, start + tick * i)).collect();
+
+        // 2.0 units/s over 100 ms ticks allows 0.2 per tick
+        assert!((outputs[0].vx - 0.2).abs() < 1e-6);
+        assert!((outputs[1].vy + 0.4).abs() < 1e-6);
+        assert!((outputs[3].vx - 0.8).abs() < 1e-6);
+        assert_eq!(outputs[2].vy, -0.5, "vy reaches its smaller target sooner");
+        assert!((outputs[4].vx - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_long_pause_does_not_allow_jump() {
+        let mut limiter = SlewLimiter::new(1.0).unwrap();
+        let start = Instant::now();
+        let target = MovementParams { vx: 1.0, vy: 0.0, vz: 0.0 };
+
+        limiter.apply(target, start);
+        let output = limiter.apply(target, start + Duration::from_secs(5));
+        assert!((output.vx - 0.1).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_invalid_rate_rejected() {
+        assert!(SlewLimiter::new(0.0).is_err());
+        assert!(SlewLimiter::new(f32::NAN).is_err());
+    }
+}
@@ -0,0 +1,57 @@
+//! Frames sent to bring the robot to a stop
+
+use crate::can::CommandCounters;
+use crate::command::{CommandBuilder, GimbalParams, MovementParams};
+use crate::error::RoboMasterError;
+
+/// Which commands [`RoboMaster::stop`](crate::control::RoboMaster::stop) sends
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StopMode {
+    /// Send a zero twist together with a neutral gimbal command
+    #[default]
+    TwistAndGimbal,
+    /// Send only the zero twist, leaving the gimbal and its counter untouched
+    TwistOnly,
+}
+
+impl StopMode {
+    /// Check whether this mode sends a gimbal command
+    pub fn sends_gimbal(self) -> bool {
+        matches!(self, Self::TwistAndGimbal)
+    }
+
+    /// Build the commands for a stop, twist first
+    pub fn build_commands(self, builder: &CommandBuilder, counters: &CommandCounters) -> Result<Vec<Vec<u8>>, RoboMasterError> {
+        let mut commands = vec![builder.build_twist_command(MovementParams::stopped(), counters)?];
+        if self.sends_gimbal() {
+            commands.push(builder.build_gimbal_command(GimbalParams::neutral(), counters)?);
+        }
+        Ok(commands)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_twist_only_emits_single_twist_frame() {
+        let builder = CommandBuilder::new();
+        let counters = CommandCounters::default();
+
+        let commands = StopMode::TwistOnly.build_commands(&builder, &counters).unwrap();
+        let twist = builder.build_twist_command(MovementParams::stopped(), &counters).unwrap();
+        assert_eq!(commands, vec![twist]);
+    }
+
+    #[test]
+    fn test_default_stop_includes_neutral_gimbal() {
+        let builder = CommandBuilder::new();
+        let counters = CommandCounters::default();
+
+        let commands = StopMode::default().build_commands(&builder, &counters).unwrap();
+        let gimbal = builder.build_gimbal_command(GimbalParams::neutral(), &counters).unwrap();
+        assert_eq!(commands.len(), 2);
+        assert_eq!(commands[1], gimbal);
+    }
+}
@@ -0,0 +1,133 @@
+//! Streaming decoded telemetry from a background task
+
+use super::{route_telemetry, RoboMaster, SensorData};
+use crate::can::{standard_id, CanInterface, MAX_DRAIN_FRAMES};
+use crate::telemetry::{TelemetryDecoder, TelemetryReceiver};
+use futures::Stream;
+use socketcan::EmbeddedFrame;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::sync::{mpsc, watch};
+use tokio::task::JoinHandle;
+
+/// Default number of sensor updates buffered for a slow subscriber
+pub const DEFAULT_TELEMETRY_STREAM_CAPACITY: usize = 16;
+
+/// Pause before polling again when the backend reports no pending frame
+///
+/// Frames are polled without blocking, so an idle bus never ties up a
+/// runtime worker or the backend lock and shutdown is noticed promptly.
+const IDLE_POLL_INTERVAL: Duration = Duration::from_millis(5);
+
+/// Sensor updates pushed by [`RoboMaster::telemetry_stream`]
+///
+/// Yields the full sensor readings after every decoded telemetry message.
+/// The stream ends when the robot is shut down or dropped, or when
+/// receiving fails; dropping the stream stops the background task.
+#[derive(Debug)]
+pub struct TelemetryStream {
+    receiver: mpsc::Receiver<SensorData>,
+    task: JoinHandle<()>,
+}
+
+impl TelemetryStream {
+    /// Wait for the next sensor update, or `None` once the stream has ended
+    pub async fn next_update(&mut self) -> Option<SensorData> {
+        self.receiver.recv().await
+    }
+
+    /// Check whether the background task is still receiving
+    pub fn is_active(&self) -> bool {
+        !self.task.is_finished()
+    }
+}
+
+impl Stream for TelemetryStream {
+    type Item = SensorData;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<SensorData>> {
+        self.receiver.poll_recv(cx)
+    }
+}
+
+impl Drop for TelemetryStream {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+impl RoboMaster {
+    /// Subscribe to sensor updates decoded by a background task
+    ///
+    /// The task polls the CAN interface for pending frames, decodes them like
+    /// [`receive_messages`](Self::receive_messages), including any decoder
+    /// registered with [`set_telemetry_decoder`](Self::set_telemetry_decoder)
+    /// at subscription time, and pushes the updated readings, starting from
+    /// the current [`sensor_data`](Self::sensor_data). Up to `capacity`
+    /// updates are buffered; while the buffer is full the task waits.
+    ///
+    /// The task consumes the frames it reads, so do not also call
+    /// [`receive_messages`](Self::receive_messages) or
+    /// [`poll_sensors`](Self::poll_sensors) while a stream is running.
+    pub fn telemetry_stream(&self, capacity: usize) -> TelemetryStream {
+        let (sender, receiver) = mpsc::channel(capacity.max(1));
+        let task = tokio::spawn(stream_telemetry(
+            Arc::clone(&self.can_interface),
+            self.sensor_data.clone(),
+            self.command_builder.crc16_init(),
+            self.telemetry_decoder.clone(),
+            self.shutdown_signal.subscribe(),
+            sender,
+        ));
+        TelemetryStream { receiver, task }
+    }
+}
+
+async fn stream_telemetry(
+    can_interface: Arc<CanInterface>,
+    mut sensors: SensorData,
+    crc16_init: u16,
+    decoder: Option<Arc<dyn TelemetryDecoder>>,
+    mut shutdown: watch::Receiver<bool>,
+    sender: mpsc::Sender<SensorData>,
+) {
+    let mut receiver = TelemetryReceiver::with_crc16_init(crc16_init);
+    // Either shutdown was requested or the robot was dropped
+    while !shutdown.has_changed().unwrap_or(true) {
+        let frames = match can_interface.drain_frames(MAX_DRAIN_FRAMES) {
+            Ok(frames) => frames,
+            Err(error) => {
+                tracing::warn!("telemetry stream stopped: {}", error);
+                break;
+            }
+        };
+        if frames.is_empty() {
+            tokio::select! {
+                _ = shutdown.changed() => break,
+                _ = tokio::time::sleep(IDLE_POLL_INTERVAL) => continue,
+            }
+        }
+
+        for frame in frames {
+            if can_interface.skip_extended(&frame) {
+                continue;
+            }
+            let Some(id) = standard_id(&frame) else { continue };
+            let message = match receiver.handle_frame(id, frame.data()) {
+                Ok(Some(message)) => message,
+                Ok(None) => continue,
+                Err(error) => {
+                    tracing::warn!("telemetry stream stopped: {}", error);
+                    return;
+                }
+            };
+
+            let decoded = route_telemetry(decoder.as_deref(), &message, &mut sensors).is_some();
+            if decoded && sender.send(sensors.clone()).await.is_err() {
+                return;
+            }
+        }
+    }
+}
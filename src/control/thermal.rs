@@ -0,0 +1,140 @@
+//! Duty-cycle limit protecting the chassis motors from overheating
+
+use crate::command::MovementParams;
+use std::time::{Duration, Instant};
+
+/// Axis speed above which the motors count as running hot
+pub const THERMAL_HIGH_SPEED: f32 = 0.7;
+
+/// Axis speed cap while the motors cool down
+pub const THERMAL_COOLDOWN_SPEED: f32 = 0.3;
+
+/// Caps the speed for a cooldown after sustained high-speed driving
+///
+/// Once sent movements have had an axis above [`THERMAL_HIGH_SPEED`] for
+/// the maximum continuous time, every axis is capped at
+/// [`THERMAL_COOLDOWN_SPEED`] for the cooldown time. Any slower movement
+/// in between restarts the count. Disabled by default.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ThermalLimit {
+    duty_cycle: Option<(Duration, Duration)>,
+    high_since: Option<Instant>,
+    cooling_until: Option<Instant>,
+}
+
+impl ThermalLimit {
+    /// Allow `max_continuous` of high speed before a `cooldown` at reduced speed
+    pub fn set_duty_cycle(&mut self, max_continuous: Duration, cooldown: Duration) {
+        *self = Self {
+            duty_cycle: Some((max_continuous, cooldown)),
+            ..Self::default()
+        };
+    }
+
+    /// Stop limiting the duty cycle and end any cooldown
+    pub fn disable(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Check whether the speed is capped for a cooldown at `now`
+    pub fn is_cooling(&self, now: Instant) -> bool {
+        matches!(self.cooling_until, Some(until) if now < until)
+    }
+
+    /// Get the axis speed cap in force at `now`, if cooling down
+    pub fn ceiling(&self, now: Instant) -> Option<f32> {
+        self.is_cooling(now).then_some(THERMAL_COOLDOWN_SPEED)
+    }
+
+    /// Cap a chassis movement while cooling down
+    pub fn cap_movement(&self, movement: MovementParams, now: Instant) -> MovementParams {
+        if !self.is_cooling(now) {
+            return movement;
+        }
+        let cap = |value: f32| value.clamp(-THERMAL_COOLDOWN_SPEED, THERMAL_COOLDOWN_SPEED);
+        MovementParams {
+            vx: cap(movement.vx),
+            vy: cap(movement.vy),
+            vz: cap(movement.vz),
+        }
+    }
+
+    /// Record a movement sent at `now`, starting a cooldown once high speed
+    /// has been sustained for too long
+    pub fn record(&mut self, movement: MovementParams, now: Instant) {
+        let speed = [movement.vx, movement.vy, movement.vz]
+            .iter()
+            .fold(0.0_f32, |max, value| max.max(value.abs()));
+        self.record_speed(speed, now);
+    }
+
+    /// Record a command sent at `now` whose fastest axis or wheel ran at
+    /// `speed`, as a fraction of full speed
+    pub fn record_speed(&mut self, speed: f32, now: Instant) {
+        let Some((max_continuous, cooldown)) = self.duty_cycle else {
+            return;
+        };
+        if self.is_cooling(now) {
+            return;
+        }
+
+        if speed <= THERMAL_HIGH_SPEED {
+            self.high_since = None;
+            return;
+        }
+
+        let since = *self.high_since.get_or_insert(now);
+        if now.saturating_duration_since(since) >= max_continuous {
+            tracing::warn!(?max_continuous, ?cooldown, "sustained high speed, reducing speed to let the motors cool");
+            self.high_since = None;
+            self.cooling_until = Some(now + cooldown);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FULL: MovementParams = MovementParams { vx: 1.0, vy: 0.0, vz: -0.9 };
+
+    #[test]
+    fn test_throttles_after_continuous_limit() {
+        let start = Instant::now();
+        let at = |secs: u64| start + Duration::from_secs(secs);
+        let mut limit = ThermalLimit::default();
+        limit.set_duty_cycle(Duration::from_secs(60), Duration::from_secs(20));
+
+        for secs in 0..60 {
+            assert_eq!(limit.cap_movement(FULL, at(secs)), FULL);
+            limit.record(FULL, at(secs));
+        }
+        limit.record(FULL, at(60));
+
+        let capped = limit.cap_movement(FULL, at(61));
+        assert_eq!(capped, MovementParams { vx: THERMAL_COOLDOWN_SPEED, vy: 0.0, vz: -THERMAL_COOLDOWN_SPEED });
+        limit.record(capped, at(61));
+        assert!(limit.is_cooling(at(79)));
+
+        // Full speed again once cooled down
+        assert_eq!(limit.cap_movement(FULL, at(80)), FULL);
+    }
+
+    #[test]
+    fn test_slow_movement_restarts_count() {
+        let start = Instant::now();
+        let at = |secs: u64| start + Duration::from_secs(secs);
+        let mut limit = ThermalLimit::default();
+        limit.set_duty_cycle(Duration::from_secs(10), Duration::from_secs(5));
+
+        limit.record(FULL, at(0));
+        limit.record(MovementParams { vx: 0.5, vy: 0.0, vz: 0.0 }, at(8));
+        limit.record(FULL, at(9));
+        limit.record(FULL, at(15));
+        assert!(!limit.is_cooling(at(15)));
+
+        limit.disable();
+        limit.record(FULL, at(100));
+        assert!(!limit.is_cooling(at(100)));
+    }
+}
@@ -0,0 +1,131 @@
+//! Stopping the robot when movement commands stop arriving
+
+use super::{hold::cancel_holds, send_stop_frames, RoboMaster};
+use crate::error::RoboMasterError;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+
+/// Deadman switch started by [`RoboMaster::enable_watchdog`]
+///
+/// Every [`move_robot`](RoboMaster::move_robot) call feeds the watchdog,
+/// including one commanding zero velocity, so holding still on purpose keeps
+/// it fed. Starting a [`hold`](RoboMaster::hold) feeds it once. If no
+/// movement arrives within the timeout, a background task cancels any holds,
+/// sends the stop commands once and waits for the next movement.
+#[derive(Debug)]
+pub struct Watchdog {
+    timeout: Duration,
+    fed_at: watch::Sender<Instant>,
+    tripped: Arc<AtomicBool>,
+    task: JoinHandle<()>,
+}
+
+impl Watchdog {
+    /// Record that a movement command arrived
+    pub fn feed(&self) {
+        self.fed_at.send_replace(Instant::now());
+    }
+
+    /// Get the time allowed between movement commands
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// Check whether the background task is still watching
+    pub fn is_active(&self) -> bool {
+        !self.task.is_finished()
+    }
+
+    /// Check whether the watchdog stopped the robot since the last call
+    pub(super) fn take_tripped(&self) -> bool {
+        self.tripped.swap(false, Ordering::Relaxed)
+    }
+}
+
+impl Drop for Watchdog {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+impl RoboMaster {
+    /// Stop the robot if no movement is commanded within `timeout`
+    ///
+    /// Guards against a control application that crashes or stalls. The
+    /// stop uses the [`StopMode`](super::StopMode) set when the watchdog is
+    /// enabled. The watchdog starts fed, replaces any previous one and ends when it is
+    /// disabled or the robot is shut down. A later
+    /// [`service_keepalive`](Self::service_keepalive) refreshes the stop
+    /// rather than the last movement.
+    pub fn enable_watchdog(&mut self, timeout: Duration) -> Result<(), RoboMasterError> {
+        if timeout.is_zero() {
+            return Err(RoboMasterError::InvalidParameter {
+                parameter: "timeout".to_string(),
+                value: format!("{:?}", timeout),
+            });
+        }
+
+        let can_interface = Arc::clone(&self.can_interface);
+        let command_builder = self.command_builder.clone();
+        let command_counters = Arc::clone(&self.command_counters);
+        let send_limiter = self.send_limiter.clone();
+        let stop_mode = self.stop_mode;
+        let active_holds = Arc::clone(&self.active_holds);
+        let mut shutdown = self.shutdown_signal.subscribe();
+        let (fed_at, mut fed) = watch::channel(Instant::now());
+        let tripped = Arc::new(AtomicBool::new(false));
+        let trip = Arc::clone(&tripped);
+
+        let task = tokio::spawn(async move {
+            let mut split_buffer = Vec::new();
+            loop {
+                let deadline = *fed.borrow_and_update() + timeout;
+                tokio::select! {
+                    _ = shutdown.changed() => break,
+                    changed = fed.changed() => match changed {
+                        Ok(()) => continue,
+                        Err(_) => break,
+                    },
+                    _ = tokio::time::sleep_until(deadline) => {}
+                }
+
+                tracing::warn!(?timeout, "no movement commanded within the watchdog timeout, stopping");
+                cancel_holds(&active_holds);
+                let result = {
+                    let _permit = send_limiter.acquire().await;
+                    send_stop_frames(&can_interface, &command_builder, &command_counters, &mut split_buffer, stop_mode)
+                };
+                if let Err(error) = result {
+                    tracing::warn!("stopped watchdog: {}", error);
+                    break;
+                }
+                trip.store(true, Ordering::Relaxed);
+
+                // Stop once, then wait for the next movement
+                tokio::select! {
+                    _ = shutdown.changed() => break,
+                    changed = fed.changed() => if changed.is_err() {
+                        break;
+                    },
+                }
+            }
+        });
+
+        self.watchdog = Some(Watchdog { timeout, fed_at, tripped, task });
+        Ok(())
+    }
+
+    /// Stop watching for missing movement commands
+    pub fn disable_watchdog(&mut self) {
+        self.watchdog = None;
+    }
+
+    /// Get the watchdog, if one is enabled
+    pub fn watchdog(&self) -> Option<&Watchdog> {
+        self.watchdog.as_ref()
+    }
+}
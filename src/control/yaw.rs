@@ -0,0 +1,78 @@
+//! Accumulated rotation tracking from IMU yaw
+
+use std::f32::consts::{PI, TAU};
+
+/// Accumulates total rotation from wrapped IMU yaw readings
+///
+/// IMU yaw wraps at ±π, so consecutive readings are unwrapped by taking
+/// the shortest angular difference. Readings must arrive often enough that
+/// the robot turns less than half a revolution between them.
+#[derive(Debug, Clone, Default)]
+pub struct YawTracker {
+    last_yaw: Option<f32>,
+    accumulated: f32,
+}
+
+impl YawTracker {
+    /// Create a tracker with no accumulated rotation
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed a yaw reading in radians; `NaN` readings are ignored
+    pub fn update(&mut self, yaw: f32) {
+        if yaw.is_nan() {
+            return;
+        }
+        if let Some(last) = self.last_yaw {
+            self.accumulated += wrap_angle(yaw - last);
+        }
+        self.last_yaw = Some(yaw);
+    }
+
+    /// Total rotation in radians since the last reset, positive clockwise
+    pub fn accumulated(&self) -> f32 {
+        self.accumulated
+    }
+
+    /// Clear the accumulated rotation, keeping the last reading as reference
+    pub fn reset(&mut self) {
+        self.accumulated = 0.0;
+    }
+}
+
+/// Wrap an angle into the range -π..π
+pub(crate) fn wrap_angle(angle: f32) -> f32 {
+    (angle + PI).rem_euclid(TAU) - PI
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_accumulates_across_wrap() {
+        let mut tracker = YawTracker::new();
+        // Simulated feedback turning clockwise through the ±π wrap
+        for step in 0..40 {
+            let yaw = wrap_angle(step as f32 * 0.2);
+            tracker.update(yaw);
+        }
+        assert!((tracker.accumulated() - 39.0 * 0.2).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_counter_clockwise_is_negative() {
+        let mut tracker = YawTracker::new();
+        tracker.update(0.1);
+        tracker.update(-0.3);
+        tracker.update(f32::NAN);
+        tracker.update(-0.5);
+        assert!((tracker.accumulated() + 0.6).abs() < 1e-6);
+
+        tracker.reset();
+        assert_eq!(tracker.accumulated(), 0.0);
+        tracker.update(-0.4);
+        assert!((tracker.accumulated() - 0.1).abs() < 1e-6);
+    }
+}
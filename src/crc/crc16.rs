@@ -2,6 +2,8 @@
 //!
 //! This implementation is compatible with the Python version in the original codebase.
 
+use crate::error::ProtocolError;
+
 /// CRC16 initial value (matches Python implementation)
 pub const CRC16_INIT: u16 = 13970;
 
@@ -41,6 +43,46 @@ const CRC16_TABLE: [u16; 256] = [
     0x7bc7, 0x6a4e, 0x58d5, 0x495c, 0x3de3, 0x2c6a, 0x1ef1, 0x0f78,
 ];
 
+/// Incremental CRC16 calculation
+///
+/// Feeding the data in pieces gives the same checksum as
+/// [`calculate_crc16`] over the concatenated bytes, so a message split
+/// across buffers does not have to be copied or re-scanned.
+///
+/// # Examples
+/// ```rust
+/// use robomaster_rust::crc::{calculate_crc16, Crc16, CRC16_INIT};
+///
+/// let mut crc = Crc16::new(CRC16_INIT);
+/// crc.update(&[0x55, 0x1b]);
+/// crc.update(&[0x04, 0xa2]);
+/// assert_eq!(crc.finalize(), calculate_crc16(&[0x55, 0x1b, 0x04, 0xa2], CRC16_INIT));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Crc16 {
+    crc: u16,
+}
+
+impl Crc16 {
+    /// Start a checksum from `init_value` (usually CRC16_INIT)
+    pub const fn new(init_value: u16) -> Self {
+        Self { crc: init_value }
+    }
+
+    /// Add `data` to the checksum
+    pub fn update(&mut self, data: &[u8]) {
+        for &byte in data {
+            let table_index = ((self.crc ^ (byte as u16)) & 0xFF) as usize;
+            self.crc = (self.crc >> 8) ^ CRC16_TABLE[table_index];
+        }
+    }
+
+    /// Get the checksum of all data added so far
+    pub fn finalize(self) -> u16 {
+        self.crc
+    }
+}
+
 /// Calculate CRC16 checksum for the given data
 ///
 /// # Arguments
@@ -59,14 +101,9 @@ const CRC16_TABLE: [u16; 256] = [
 /// println!("CRC16: 0x{:04x}", crc);
 /// ```
 pub fn calculate_crc16(data: &[u8], init_value: u16) -> u16 {
-    let mut crc = init_value;
-    
-    for &byte in data {
-        let table_index = ((crc ^ (byte as u16)) & 0xFF) as usize;
-        crc = (crc >> 8) ^ CRC16_TABLE[table_index];
-    }
-    
-    crc
+    let mut crc = Crc16::new(init_value);
+    crc.update(data);
+    crc.finalize()
 }
 
 /// Append CRC16 checksum to the given data vector (little-endian)
@@ -108,15 +145,43 @@ pub fn append_crc16_checksum(data: &mut Vec<u8>, init_value: u16) {
 /// assert!(verify_crc16_checksum(&data, CRC16_INIT));
 /// ```
 pub fn verify_crc16_checksum(data: &[u8], init_value: u16) -> bool {
+    verify_crc16_detailed(data, init_value).is_ok()
+}
+
+/// Verify CRC16 checksum of the given data, reporting why it failed
+///
+/// # Errors
+/// * `MessageTooShort` if `data` cannot hold a CRC16
+/// * `CrcMismatch` with the CRC16 carried by `data` as `expected` and the
+///   calculated one as `actual`
+///
+/// # Examples
+/// ```rust
+/// use robomaster_rust::crc::{verify_crc16_detailed, CRC16_INIT};
+/// use robomaster_rust::error::ProtocolError;
+///
+/// let data = vec![0x55, 0x1b, 0x04, 0xa2, 0x00, 0x00];
+/// match verify_crc16_detailed(&data, CRC16_INIT) {
+///     Err(ProtocolError::CrcMismatch { expected, actual }) => {
+///         println!("CRC16 expected 0x{:04x}, got 0x{:04x}", expected, actual);
+///     }
+///     other => panic!("unexpected result: {:?}", other),
+/// }
+/// ```
+pub fn verify_crc16_detailed(data: &[u8], init_value: u16) -> Result<(), ProtocolError> {
     if data.len() < 2 {
-        return false;
+        return Err(ProtocolError::MessageTooShort { expected: 2, actual: data.len() });
     }
-    
+
     let (payload, crc_bytes) = data.split_at(data.len() - 2);
-    let expected_crc = (crc_bytes[0] as u16) | ((crc_bytes[1] as u16) << 8);
-    let calculated_crc = calculate_crc16(payload, init_value);
-    
-    calculated_crc == expected_crc
+    let expected = u16::from_le_bytes([crc_bytes[0], crc_bytes[1]]);
+    let actual = calculate_crc16(payload, init_value);
+
+    if actual == expected {
+        Ok(())
+    } else {
+        Err(ProtocolError::CrcMismatch { expected, actual })
+    }
 }
 
 /// Get CRC16 checksum from data (alternative interface)
@@ -168,6 +233,26 @@ mod tests {
         assert!(!verify_crc16_checksum(&corrupted_data, CRC16_INIT));
     }
 
+    #[test]
+    fn test_crc16_detailed_reports_expected_and_actual() {
+        let mut data = vec![0x55, 0x1b, 0x04, 0xa2];
+        let crc = calculate_crc16(&data, CRC16_INIT);
+        append_crc16_checksum(&mut data, CRC16_INIT);
+        assert!(verify_crc16_detailed(&data, CRC16_INIT).is_ok());
+
+        let len = data.len();
+        data[len - 2..].copy_from_slice(&0x1234u16.to_le_bytes());
+        assert!(matches!(
+            verify_crc16_detailed(&data, CRC16_INIT),
+            Err(ProtocolError::CrcMismatch { expected: 0x1234, actual }) if actual == crc
+        ));
+
+        assert!(matches!(
+            verify_crc16_detailed(&[0x55], CRC16_INIT),
+            Err(ProtocolError::MessageTooShort { expected: 2, actual: 1 })
+        ));
+    }
+
     #[test]
     fn test_crc16_empty_data() {
         let empty_data = vec![];
@@ -198,4 +283,32 @@ mod tests {
             assert_eq!(result, expected, "CRC16 mismatch for data: {:?}", data);
         }
     }
+
+    #[test]
+    fn test_streaming_matches_one_shot_for_python_vectors() {
+        let vectors: Vec<(Vec<u8>, u16)> = vec![
+            (vec![0x55, 0x1b, 0x04, 0xa2, 0x09, 0x04, 0x00, 0x00, 0x40, 0x04, 0x4c, 0x00, 0x00], 0x2065),
+            (vec![0x40, 0x04, 0x4c, 0x00, 0x00], 0x3fee),
+            (vec![0x40], 0xf5a9),
+        ];
+
+        for (data, expected) in vectors {
+            for split in 0..=data.len() {
+                let (head, tail) = data.split_at(split);
+                let mut crc = Crc16::new(CRC16_INIT);
+                crc.update(head);
+                crc.update(tail);
+                assert_eq!(crc.finalize(), expected, "split at {} of {:?}", split, data);
+            }
+
+            let mut crc = Crc16::new(CRC16_INIT);
+            data.chunks(1).for_each(|byte| crc.update(byte));
+            assert_eq!(crc.finalize(), calculate_crc16(&data, CRC16_INIT));
+        }
+    }
+
+    #[test]
+    fn test_streaming_without_data_is_init_value() {
+        assert_eq!(Crc16::new(CRC16_INIT).finalize(), CRC16_INIT);
+    }
 }
@@ -7,7 +7,7 @@ pub mod crc8;
 pub mod crc16;
 
 pub use crc8::{calculate_crc8, append_crc8_checksum, verify_crc8_checksum};
-pub use crc16::{calculate_crc16, append_crc16_checksum, verify_crc16_checksum, CRC16_INIT};
+pub use crc16::{calculate_crc16, append_crc16_checksum, verify_crc16_checksum, verify_crc16_detailed, Crc16, CRC16_INIT};
 
 #[cfg(test)]
 mod tests {
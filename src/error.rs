@@ -73,6 +73,10 @@ pub enum CanError {
     #[error("Failed to receive CAN message: {0}")]
     ReceiveFailed(std::io::Error),
 
+    /// Failed to configure the CAN socket
+    #[error("Failed to configure CAN socket: {0}")]
+    ConfigureFailed(std::io::Error),
+
     /// Invalid CAN message data length
     #[error("Invalid CAN data length: {length} bytes (max: {max_length})")]
     InvalidDataLength { length: usize, max_length: usize },
@@ -88,6 +92,14 @@ pub enum CanError {
     /// CAN interface not available
     #[error("CAN interface '{interface}' not available")]
     InterfaceNotAvailable { interface: String },
+
+    /// The CAN controller went bus-off after too many errors
+    #[error("CAN bus-off: too many bus errors, check the cabling and termination")]
+    BusOff,
+
+    /// The backend is being reopened and the call should be retried later
+    #[error("CAN interface is reconnecting, retry in {retry_after_ms}ms")]
+    Reconnecting { retry_after_ms: u64 },
 }
 
 /// Protocol parsing and generation errors
@@ -124,6 +136,10 @@ pub enum ProtocolError {
     /// Command not found
     #[error("Command not found: {command_id}")]
     CommandNotFound { command_id: usize },
+
+    /// The robot never echoed the command counter
+    #[error("No counter echo after {commands} commands: the firmware may use a different protocol version or CRC16 init (current 0x{crc16_init:04X})")]
+    NoCounterEcho { commands: u32, crc16_init: u16 },
 }
 
 /// Control system errors
@@ -133,6 +149,10 @@ pub enum ControlError {
     #[error("Speed out of range: {value} (valid range: {min} to {max})")]
     SpeedOutOfRange { value: f32, min: f32, max: f32 },
 
+    /// Gimbal angle out of range
+    #[error("Gimbal {axis} angle out of range: {value} (valid range: {min} to {max})")]
+    AngleOutOfRange { axis: String, value: f32, min: f32, max: f32 },
+
     /// Invalid LED color value
     #[error("LED color out of range: {component}={value} (valid range: 0-255)")]
     LedColorOutOfRange { component: String, value: i32 },
@@ -145,6 +165,10 @@ pub enum ControlError {
     #[error("Sensor data unavailable: {sensor}")]
     SensorUnavailable { sensor: String },
 
+    /// Blaster firing blocked by the safety interlock
+    #[error("Blaster interlocked: {reason}")]
+    BlasterInterlocked { reason: String },
+
     /// Control loop error
     #[error("Control loop error: {0}")]
     ControlLoop(String),
@@ -209,14 +233,19 @@ impl RoboMasterError {
             Self::CanInterface(CanError::SendFailed(_))
             | Self::CanInterface(CanError::ReceiveFailed(_))
             | Self::CanInterface(CanError::InvalidMessage { .. })
+            | Self::CanInterface(CanError::Reconnecting { .. })
             | Self::Timeout { .. } => true,
             Self::CanInterface(CanError::OpenFailed { .. })
             | Self::CanInterface(CanError::InvalidDataLength { .. })
             | Self::CanInterface(CanError::FrameCreation(_))
-            | Self::CanInterface(CanError::InterfaceNotAvailable { .. }) => false,
+            | Self::CanInterface(CanError::InterfaceNotAvailable { .. })
+            | Self::CanInterface(CanError::ConfigureFailed(_)) => false,
+            // Retrying cannot help until the cabling is fixed and the controller restarts
+            Self::CanInterface(CanError::BusOff) => false,
             Self::NotInitialized | Self::AlreadyInitialized => false,
             Self::Protocol(_) => false,
-            Self::Control(ControlError::SensorUnavailable { .. }) => true,
+            Self::Control(ControlError::SensorUnavailable { .. })
+            | Self::Control(ControlError::BlasterInterlocked { .. }) => true,
             Self::Control(_) => false,
             #[cfg(feature = "cli")]
             Self::Joystick(JoystickError::ReadFailed(_)) => true,
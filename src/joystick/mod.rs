@@ -3,7 +3,9 @@
 
 use crate::command::MovementParams;
 use crate::error::RoboMasterError;
+use crate::error::JoystickError;
 use anyhow::Result;
+use gilrs::{Axis, Button, Event, EventType, GamepadId, Gilrs};
 use std::time::{Duration, Instant};
 
 /// Controller input structure
@@ -39,10 +41,93 @@ pub struct ControllerInput {
     pub select_pressed: bool,
 }
 
-/// Joystick manager for handling controller input
+impl ControllerInput {
+    /// Get the speed multiplier selected by the right trigger
+    ///
+    /// A released trigger gives `base_speed` and a fully pressed trigger
+    /// gives `boost_speed`, interpolating linearly in between.
+    pub fn boost_speed(&self, base_speed: f32, boost_speed: f32) -> f32 {
+        let pressure = self.right_trigger.clamp(0.0, 1.0);
+        base_speed + (boost_speed - base_speed) * pressure
+    }
+
+    /// Convert the sticks to a movement, scaled by the right trigger boost
+    ///
+    /// Left stick Y drives forward, left stick X strafes and right stick X
+    /// rotates, matching [`JoystickController::process_input`].
+    pub fn to_movement(&self, base_speed: f32, boost_speed: f32) -> MovementParams {
+        let speed = self.boost_speed(base_speed, boost_speed);
+        MovementParams {
+            vx: (self.left_stick_y * speed).clamp(-1.0, 1.0),
+            vy: (self.left_stick_x * speed).clamp(-1.0, 1.0),
+            vz: (self.right_stick_x * speed).clamp(-1.0, 1.0),
+        }
+    }
+
+    /// Record a gilrs axis value; stick Y axes are positive when pushed up
+    fn apply_axis(&mut self, axis: Axis, value: f32) {
+        let value = value.clamp(-1.0, 1.0);
+        match axis {
+            Axis::LeftStickX => self.left_stick_x = value,
+            Axis::LeftStickY => self.left_stick_y = value,
+            Axis::RightStickX => self.right_stick_x = value,
+            Axis::RightStickY => self.right_stick_y = value,
+            Axis::LeftZ => self.left_trigger = value.max(0.0),
+            Axis::RightZ => self.right_trigger = value.max(0.0),
+            _ => {}
+        }
+    }
+
+    /// Record a gilrs button value, where `0.0` is released and `1.0` fully pressed
+    fn apply_button(&mut self, button: Button, value: f32) {
+        let value = value.clamp(0.0, 1.0);
+        let pressed = value > 0.5;
+        match button {
+            Button::LeftTrigger2 => self.left_trigger = value,
+            Button::RightTrigger2 => self.right_trigger = value,
+            Button::North => self.face_button_north = pressed,
+            Button::South => self.face_button_south = pressed,
+            Button::East => self.face_button_east = pressed,
+            Button::West => self.face_button_west = pressed,
+            Button::LeftTrigger => self.left_shoulder = pressed,
+            Button::RightTrigger => self.right_shoulder = pressed,
+            Button::DPadUp => self.dpad_up = pressed,
+            Button::DPadDown => self.dpad_down = pressed,
+            Button::DPadLeft => self.dpad_left = pressed,
+            Button::DPadRight => self.dpad_right = pressed,
+            Button::Start => self.start_pressed = pressed,
+            Button::Select => self.select_pressed = pressed,
+            _ => {}
+        }
+    }
+
+    /// Copy of this input with analog values inside `deadzone` zeroed
+    fn with_deadzone(&self, deadzone: f32) -> Self {
+        let filter = |value: f32| if value.abs() < deadzone { 0.0 } else { value };
+        Self {
+            left_stick_x: filter(self.left_stick_x),
+            left_stick_y: filter(self.left_stick_y),
+            right_stick_x: filter(self.right_stick_x),
+            right_stick_y: filter(self.right_stick_y),
+            left_trigger: filter(self.left_trigger),
+            right_trigger: filter(self.right_trigger),
+            ..*self
+        }
+    }
+}
+
+/// Joystick manager reading controller input through gilrs
+///
+/// The first gamepad that is connected or sends an event becomes the
+/// active one. Its state is tracked from gilrs events, so sticks held
+/// still keep their last reported position.
 pub struct JoystickManager {
-    /// Current controller input state
-    current_input: Option<ControllerInput>,
+    /// Gamepad event source
+    gilrs: Gilrs,
+    /// Gamepad whose input is reported
+    active_gamepad: Option<GamepadId>,
+    /// Raw input state of the active gamepad, before the deadzone
+    state: ControllerInput,
     /// Deadzone for analog inputs
     deadzone: f32,
     /// Input timeout
@@ -54,8 +139,18 @@ pub struct JoystickManager {
 impl JoystickManager {
     /// Create a new joystick manager
     pub async fn new() -> Result<Self, RoboMasterError> {
+        let gilrs = Gilrs::new().map_err(|e| {
+            JoystickError::ReadFailed(std::io::Error::other(format!("failed to initialize gilrs: {}", e)))
+        })?;
+        let active_gamepad = gilrs
+            .gamepads()
+            .find(|(_, gamepad)| gamepad.is_connected())
+            .map(|(id, _)| id);
+
         Ok(Self {
-            current_input: None,
+            gilrs,
+            active_gamepad,
+            state: ControllerInput::default(),
             deadzone: 0.1,
             timeout: Duration::from_millis(100),
             last_input: Instant::now(),
@@ -63,17 +158,42 @@ impl JoystickManager {
     }
 
     /// Get current controller input
+    ///
+    /// Processes all pending gamepad events and returns the active
+    /// gamepad's state with the deadzone applied, or `None` when no
+    /// gamepad is connected.
     pub async fn get_input(&mut self) -> Result<Option<ControllerInput>, RoboMasterError> {
-        // For now, return mock input for testing
-        // In a real implementation, this would read from a gamepad library
-        let now = Instant::now();
-        if now.duration_since(self.last_input) > self.timeout {
-            // Simulate no controller input
-            Ok(None)
-        } else {
-            // Simulate some basic input
-            Ok(Some(ControllerInput::default()))
+        while let Some(Event { id, event, .. }) = self.gilrs.next_event() {
+            match event {
+                EventType::Disconnected => {
+                    if self.active_gamepad == Some(id) {
+                        tracing::warn!(gamepad = %id, "active gamepad disconnected");
+                        self.active_gamepad = None;
+                        self.state = ControllerInput::default();
+                    }
+                    continue;
+                }
+                _ if self.active_gamepad.is_none() => self.active_gamepad = Some(id),
+                _ if self.active_gamepad != Some(id) => continue,
+                _ => {}
+            }
+
+            self.last_input = Instant::now();
+            match event {
+                EventType::AxisChanged(axis, value, _) => self.state.apply_axis(axis, value),
+                EventType::ButtonChanged(button, value, _) => self.state.apply_button(button, value),
+                EventType::ButtonPressed(button, _) => self.state.apply_button(button, 1.0),
+                EventType::ButtonReleased(button, _) => self.state.apply_button(button, 0.0),
+                _ => {}
+            }
         }
+
+        Ok(self.active_gamepad.map(|_| self.state.with_deadzone(self.deadzone)))
+    }
+
+    /// Check if no input has arrived from the active gamepad within the timeout
+    pub fn has_input_timeout(&self) -> bool {
+        self.last_input.elapsed() > self.timeout
     }
 
     /// Set deadzone for analog inputs
@@ -87,6 +207,37 @@ impl JoystickManager {
     }
 }
 
+/// Response curve applied to stick deflection
+///
+/// Curves map a magnitude in 0.0 to 1.0 onto 0.0 to 1.0; the sign of the
+/// input is kept. Steeper curves give finer control near center.
+#[derive(Debug, Clone, Copy, Default)]
+pub enum InputCurve {
+    /// Output equals input
+    #[default]
+    Linear,
+    /// Output is the input squared
+    Quadratic,
+    /// Output is the input cubed
+    Cubic,
+    /// Output is computed by a custom function
+    Custom(fn(f32) -> f32),
+}
+
+impl InputCurve {
+    /// Apply the curve to a signed axis value, clamping the result to -1.0 to 1.0
+    pub fn apply(&self, value: f32) -> f32 {
+        let magnitude = value.abs().min(1.0);
+        let shaped = match self {
+            Self::Linear => magnitude,
+            Self::Quadratic => magnitude * magnitude,
+            Self::Cubic => magnitude * magnitude * magnitude,
+            Self::Custom(curve) => curve(magnitude),
+        };
+        (shaped * value.signum()).clamp(-1.0, 1.0)
+    }
+}
+
 /// Joystick controller for robot input processing
 #[derive(Debug, Clone)]
 pub struct JoystickController {
@@ -94,6 +245,8 @@ pub struct JoystickController {
     deadzone: f32,
     /// Maximum speed multiplier
     max_speed: f32,
+    /// Response curve applied after the deadzone
+    curve: InputCurve,
     /// Last input timestamp
     last_input: Instant,
     /// Input timeout
@@ -112,6 +265,7 @@ impl JoystickController {
         Self {
             deadzone: 0.1,
             max_speed: 1.0,
+            curve: InputCurve::Linear,
             last_input: Instant::now(),
             timeout: Duration::from_millis(500),
         }
@@ -135,6 +289,12 @@ impl JoystickController {
         self
     }
 
+    /// Set the response curve applied to each axis after the deadzone
+    pub fn with_curve(mut self, curve: InputCurve) -> Self {
+        self.curve = curve;
+        self
+    }
+
     /// Process raw joystick input and convert to robot movement
     pub fn process_input(&mut self, x: f32, y: f32, rotation: f32) -> Result<MovementParams, RoboMasterError> {
         self.last_input = Instant::now();
@@ -144,10 +304,10 @@ impl JoystickController {
         let y_filtered = if y.abs() < self.deadzone { 0.0 } else { y };
         let rotation_filtered = if rotation.abs() < self.deadzone { 0.0 } else { rotation };
 
-        // Scale by maximum speed
-        let vx = (y_filtered * self.max_speed).clamp(-1.0, 1.0);
-        let vy = (x_filtered * self.max_speed).clamp(-1.0, 1.0);
-        let vz = (rotation_filtered * self.max_speed).clamp(-1.0, 1.0);
+        // Shape by the response curve, then scale by maximum speed
+        let vx = (self.curve.apply(y_filtered) * self.max_speed).clamp(-1.0, 1.0);
+        let vy = (self.curve.apply(x_filtered) * self.max_speed).clamp(-1.0, 1.0);
+        let vz = (self.curve.apply(rotation_filtered) * self.max_speed).clamp(-1.0, 1.0);
 
         Ok(MovementParams { vx, vy, vz })
     }
@@ -171,6 +331,11 @@ impl JoystickController {
     pub fn timeout(&self) -> Duration {
         self.timeout
     }
+
+    /// Get the response curve
+    pub fn curve(&self) -> InputCurve {
+        self.curve
+    }
 }
 
 /// Configuration options for joystick controller
@@ -311,6 +476,26 @@ mod tests {
         assert!(result.vz.abs() <= 0.5);
     }
 
+    #[test]
+    fn test_quadratic_curve_halves_half_deflection() {
+        let mut linear = JoystickController::new();
+        let mut quadratic = JoystickController::new().with_curve(InputCurve::Quadratic);
+
+        let linear = linear.process_input(0.5, -0.5, 0.5).unwrap();
+        let shaped = quadratic.process_input(0.5, -0.5, 0.5).unwrap();
+        assert_eq!(shaped.vx, linear.vx / 2.0);
+        assert_eq!(shaped.vy, linear.vy / 2.0);
+        assert_eq!(shaped.vx, -0.25, "sign is preserved");
+    }
+
+    #[test]
+    fn test_curves_keep_output_in_range() {
+        assert_eq!(InputCurve::Cubic.apply(0.5), 0.125);
+        assert_eq!(InputCurve::Cubic.apply(-2.0), -1.0);
+        assert_eq!(InputCurve::Custom(|x| x * 3.0).apply(0.5), 1.0);
+        assert_eq!(InputCurve::Custom(f32::sqrt).apply(-0.25), -0.5);
+    }
+
     #[test]
     fn test_input_clamping() {
         let mut controller = JoystickController::new();
@@ -330,6 +515,55 @@ mod tests {
         assert!(!input.start_pressed);
     }
 
+    #[test]
+    fn test_half_trigger_applies_half_boost() {
+        let input = ControllerInput {
+            left_stick_y: 1.0,
+            left_stick_x: -0.5,
+            right_trigger: 0.5,
+            ..Default::default()
+        };
+
+        assert!((input.boost_speed(0.4, 1.0) - 0.7).abs() < 1e-6);
+        let movement = input.to_movement(0.4, 1.0);
+        assert!((movement.vx - 0.7).abs() < 1e-6);
+        assert!((movement.vy + 0.35).abs() < 1e-6);
+        assert_eq!(movement.vz, 0.0);
+    }
+
+    #[test]
+    fn test_released_trigger_uses_base_speed() {
+        let input = ControllerInput {
+            left_stick_y: 1.0,
+            ..Default::default()
+        };
+        assert_eq!(input.to_movement(0.4, 1.0).vx, 0.4);
+
+        let full = ControllerInput { right_trigger: 1.5, ..input };
+        assert_eq!(full.to_movement(0.4, 1.0).vx, 1.0);
+    }
+
+    #[test]
+    fn test_gilrs_events_map_to_controller_input() {
+        let mut input = ControllerInput::default();
+        input.apply_axis(Axis::LeftStickY, 0.8);
+        input.apply_axis(Axis::RightStickX, -0.05);
+        input.apply_button(Button::RightTrigger2, 0.6);
+        input.apply_button(Button::South, 1.0);
+        input.apply_button(Button::LeftTrigger, 1.0);
+        input.apply_button(Button::LeftTrigger, 0.0);
+
+        assert_eq!(input.left_stick_y, 0.8);
+        assert_eq!(input.right_trigger, 0.6);
+        assert!(input.face_button_south);
+        assert!(!input.left_shoulder);
+
+        let filtered = input.with_deadzone(0.1);
+        assert_eq!(filtered.right_stick_x, 0.0);
+        assert_eq!(filtered.left_stick_y, 0.8);
+        assert!(filtered.face_button_south);
+    }
+
     #[test]
     fn test_advanced_controller() {
         let config = JoystickConfig {
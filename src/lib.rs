@@ -33,18 +33,21 @@ pub mod command;
 pub mod control;
 pub mod crc;
 pub mod error;
+pub mod telemetry;
 
 // Optional modules
 #[cfg(feature = "cli")]
 pub mod joystick;
 
 // Re-exports for convenience
-pub use crate::command::{MovementParams, GimbalParams, LedColor};
+pub use crate::command::{MovementParams, GimbalParams, LedColor, LedEffect, LedZone, TwistEnable, ChassisMode, WheelSpeeds};
 pub use crate::can::{CanInterface, CommandCounters};
-pub use crate::control::{RoboMaster, MovementCommand, LedCommand, SensorData};
+pub use crate::control::{RoboMaster, RoboMasterBuilder, MovementCommand, CompassDirection, LedCommand, SensorData, RobotSettings, RoboMasterConfig};
 pub use crate::error::RoboMasterError;
-pub use crate::joystick::{JoystickController, JoystickManager, ControllerInput};
+pub use crate::telemetry::decode_telemetry_fields;
 
+#[cfg(feature = "cli")]
+pub use crate::joystick::{JoystickController, JoystickManager, ControllerInput, InputCurve};
 #[cfg(feature = "cli")]
 pub use crate::joystick::JoystickController as JoystickControllerCli;
 
@@ -0,0 +1,90 @@
+//! Decoded telemetry export as CSV for spreadsheet analysis
+
+use crate::control::SensorData;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Header row written at the top of every CSV log
+pub const CSV_HEADER: &str =
+    "timestamp,battery_v,current_a,temperature_c,accel_x,accel_y,accel_z,gyro_x,gyro_y,gyro_z";
+
+/// CSV log with one row of sensor readings per decoded telemetry message
+///
+/// Accelerations are in m/s² and angular rates in rad/s, as in
+/// [`SensorData`]. Unavailable readings are left empty.
+pub struct TelemetryCsv {
+    writer: Box<dyn Write + Send + Sync>,
+}
+
+impl TelemetryCsv {
+    /// Create a CSV file at `path`, replacing any existing file
+    pub fn create(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let file = File::create(path)?;
+        Self::from_writer(BufWriter::new(file))
+    }
+
+    /// Write CSV to an arbitrary writer, starting with the header row
+    pub fn from_writer(writer: impl Write + Send + Sync + 'static) -> std::io::Result<Self> {
+        let mut csv = Self {
+            writer: Box::new(writer),
+        };
+        writeln!(csv.writer, "{}", CSV_HEADER)?;
+        Ok(csv)
+    }
+
+    /// Append the current readings as one row
+    pub fn write_row(&mut self, sensors: &SensorData) -> std::io::Result<()> {
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+        let cell = |value: f32| if value.is_nan() { String::new() } else { value.to_string() };
+
+        let mut row = format!("{}.{:06}", timestamp.as_secs(), timestamp.subsec_micros());
+        let readings = [sensors.battery_voltage, sensors.current, sensors.temperature]
+            .into_iter()
+            .chain(sensors.imu.acceleration)
+            .chain(sensors.imu.angular_velocity);
+        for value in readings {
+            row.push(',');
+            row.push_str(&cell(value));
+        }
+        writeln!(self.writer, "{}", row)
+    }
+
+    /// Flush buffered rows to the underlying writer
+    pub fn flush(&mut self) -> std::io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+impl Drop for TelemetryCsv {
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rows_follow_header_and_leave_missing_readings_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("telemetry.csv");
+
+        let mut csv = TelemetryCsv::create(&path).unwrap();
+        let sensors = SensorData { battery_voltage: 12.5, ..SensorData::default() };
+        csv.write_row(&sensors).unwrap();
+        drop(csv);
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines[0], CSV_HEADER);
+        assert_eq!(lines.len(), 2);
+
+        let cells: Vec<&str> = lines[1].split(',').collect();
+        assert_eq!(cells.len(), CSV_HEADER.split(',').count());
+        assert_eq!(&cells[1..4], &["12.5", "", ""]);
+        assert_eq!(cells[4], "", "IMU readings not received yet are empty");
+    }
+}
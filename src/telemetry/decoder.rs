@@ -0,0 +1,75 @@
+//! Pluggable telemetry decoders
+
+use crate::control::SensorData;
+
+/// Decodes raw telemetry messages into [`SensorData`]
+///
+/// Implement this to support telemetry layouts the built-in decoder does not
+/// know about, then register it with
+/// [`RoboMaster::set_telemetry_decoder`](crate::control::RoboMaster::set_telemetry_decoder).
+pub trait TelemetryDecoder: Send + Sync {
+    /// Decode a reassembled telemetry message, or `None` if it is not recognised
+    fn decode(&self, frame: &[u8]) -> Option<SensorData>;
+}
+
+/// Decoder for the layouts in [`KNOWN_LAYOUTS`](super::KNOWN_LAYOUTS)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BuiltinDecoder;
+
+impl TelemetryDecoder for BuiltinDecoder {
+    fn decode(&self, frame: &[u8]) -> Option<SensorData> {
+        super::decode_sensor_data(frame)
+    }
+}
+
+impl<F> TelemetryDecoder for F
+where
+    F: Fn(&[u8]) -> Option<SensorData> + Send + Sync,
+{
+    fn decode(&self, frame: &[u8]) -> Option<SensorData> {
+        self(frame)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telemetry::tests::{build_message, chassis_status_payload};
+
+    struct FixedVoltageDecoder;
+
+    impl TelemetryDecoder for FixedVoltageDecoder {
+        fn decode(&self, frame: &[u8]) -> Option<SensorData> {
+            (frame.first() == Some(&0xAA)).then(|| SensorData {
+                battery_voltage: 12.6,
+                ..SensorData::default()
+            })
+        }
+    }
+
+    #[test]
+    fn test_builtin_decoder_uses_known_layouts() {
+        let message = build_message(0x3F, 0xA0, &chassis_status_payload());
+        let data = BuiltinDecoder.decode(&message).unwrap();
+        assert!((data.battery_voltage - 11.85).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_custom_decoder_as_trait_object() {
+        let decoder: Box<dyn TelemetryDecoder> = Box::new(FixedVoltageDecoder);
+
+        assert_eq!(decoder.decode(&[0xAA, 0x01]).unwrap().battery_voltage, 12.6);
+        assert!(decoder.decode(&[0x55]).is_none());
+    }
+
+    #[test]
+    fn test_closure_decoder() {
+        let decoder = |frame: &[u8]| {
+            Some(SensorData {
+                temperature: frame.len() as f32,
+                ..SensorData::default()
+            })
+        };
+        assert_eq!(decoder.decode(&[0; 4]).unwrap().temperature, 4.0);
+    }
+}
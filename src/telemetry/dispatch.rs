@@ -0,0 +1,268 @@
+//! Routing of telemetry messages to per-type decoders
+//!
+//! Each known message type is identified by its command set and command id.
+//! The dispatch table maps that pair to a decoder that updates only the
+//! [`SensorData`] fields carried by the message.
+
+use super::{
+    payload, TelemetryLayout, BATTERY_LAYOUT, BLASTER_STATUS_LAYOUT, CHASSIS_STATUS_LAYOUT, CMD_ID_OFFSET,
+    CMD_SET_OFFSET, GIMBAL_LAYOUT, IMU_LAYOUT, LINK_STATUS_LAYOUT, MOTOR_STATUS_LAYOUT, STANDARD_GRAVITY,
+};
+use crate::control::{GimbalLimits, SensorData};
+
+/// Telemetry message types understood by the dispatcher
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TelemetryKind {
+    /// Combined battery, temperature and IMU status
+    ChassisStatus,
+    /// Battery voltage and current
+    Battery,
+    /// Accelerometer and gyroscope readings
+    Imu,
+    /// Gimbal pitch and yaw angles
+    Gimbal,
+    /// Blaster armed state and gimbal readiness
+    Blaster,
+    /// Per-wheel motor current and temperature
+    Motors,
+    /// Connection quality
+    Link,
+    /// A message only a custom [`TelemetryDecoder`](super::TelemetryDecoder) recognised
+    Custom,
+}
+
+/// Decoder updating sensor data from a message payload
+type ApplyFn = fn(&TelemetryLayout, &[u8], &mut SensorData);
+
+struct Route {
+    kind: TelemetryKind,
+    layout: &'static TelemetryLayout,
+    apply: ApplyFn,
+}
+
+const ROUTES: &[Route] = &[
+    Route { kind: TelemetryKind::ChassisStatus, layout: &CHASSIS_STATUS_LAYOUT, apply: apply_chassis_status },
+    Route { kind: TelemetryKind::Battery, layout: &BATTERY_LAYOUT, apply: apply_battery },
+    Route { kind: TelemetryKind::Imu, layout: &IMU_LAYOUT, apply: apply_imu },
+    Route { kind: TelemetryKind::Gimbal, layout: &GIMBAL_LAYOUT, apply: apply_gimbal },
+    Route { kind: TelemetryKind::Blaster, layout: &BLASTER_STATUS_LAYOUT, apply: apply_blaster },
+    Route { kind: TelemetryKind::Motors, layout: &MOTOR_STATUS_LAYOUT, apply: apply_motors },
+    Route { kind: TelemetryKind::Link, layout: &LINK_STATUS_LAYOUT, apply: apply_link },
+];
+
+/// Identify the type of a telemetry message from its command set and id
+pub fn message_kind(data: &[u8]) -> Option<TelemetryKind> {
+    find_route(data).map(|route| route.kind)
+}
+
+/// Route a telemetry message to its decoder and update `sensors`
+///
+/// Only the fields carried by the message are updated. Returns the message
+/// type, or `None` if the message is unknown or truncated.
+pub fn dispatch(data: &[u8], sensors: &mut SensorData) -> Option<TelemetryKind> {
+    let route = find_route(data)?;
+    let payload = payload(data)?;
+    (route.apply)(route.layout, payload, sensors);
+    Some(route.kind)
+}
+
+fn find_route(data: &[u8]) -> Option<&'static Route> {
+    let cmd_set = *data.get(CMD_SET_OFFSET)?;
+    let cmd_id = *data.get(CMD_ID_OFFSET)?;
+    ROUTES
+        .iter()
+        .find(|route| route.layout.cmd_set == cmd_set && route.layout.cmd_id == cmd_id)
+}
+
+/// Read a named field, or `NaN` if it is missing or faulted
+fn field(layout: &TelemetryLayout, payload: &[u8], name: &str) -> f32 {
+    layout
+        .fields
+        .iter()
+        .find(|spec| spec.name == name)
+        .and_then(|spec| spec.read(payload))
+        .map_or(f32::NAN, |value| value as f32)
+}
+
+pub(super) fn apply_chassis_status(layout: &TelemetryLayout, payload: &[u8], sensors: &mut SensorData) {
+    apply_battery(layout, payload, sensors);
+    apply_imu(layout, payload, sensors);
+    sensors.temperature = field(layout, payload, "temperature_c");
+}
+
+fn apply_battery(layout: &TelemetryLayout, payload: &[u8], sensors: &mut SensorData) {
+    sensors.battery_voltage = field(layout, payload, "battery_mv") / 1000.0;
+    sensors.current = field(layout, payload, "current_ma") / 1000.0;
+}
+
+fn apply_imu(layout: &TelemetryLayout, payload: &[u8], sensors: &mut SensorData) {
+    if let Some(spec) = layout.fields.iter().find(|spec| spec.name == "yaw_deg") {
+        sensors.imu.orientation[2] = spec.read(payload).map_or(f32::NAN, |yaw| (yaw as f32).to_radians());
+    }
+    sensors.imu.acceleration = [
+        field(layout, payload, "accel_x") * STANDARD_GRAVITY,
+        field(layout, payload, "accel_y") * STANDARD_GRAVITY,
+        field(layout, payload, "accel_z") * STANDARD_GRAVITY,
+    ];
+    sensors.imu.angular_velocity = [
+        field(layout, payload, "gyro_x").to_radians(),
+        field(layout, payload, "gyro_y").to_radians(),
+        field(layout, payload, "gyro_z").to_radians(),
+    ];
+}
+
+fn apply_gimbal(layout: &TelemetryLayout, payload: &[u8], sensors: &mut SensorData) {
+    sensors.gimbal.pitch = field(layout, payload, "pitch_deg").to_radians();
+    sensors.gimbal.yaw = field(layout, payload, "yaw_deg").to_radians();
+    let flags = field(layout, payload, "limit_flags");
+    sensors.gimbal.limits = if flags.is_nan() {
+        GimbalLimits::default()
+    } else {
+        GimbalLimits::from_flags(flags as u8)
+    };
+}
+
+fn apply_blaster(layout: &TelemetryLayout, payload: &[u8], sensors: &mut SensorData) {
+    let flag = |name: &str| {
+        let value = field(layout, payload, name);
+        (!value.is_nan()).then_some(value != 0.0)
+    };
+    sensors.blaster.armed = flag("armed");
+    sensors.blaster.gimbal_ready = flag("gimbal_ready");
+}
+
+fn apply_motors(layout: &TelemetryLayout, payload: &[u8], sensors: &mut SensorData) {
+    const WHEELS: [&str; 4] = ["fl", "fr", "rl", "rr"];
+    sensors.motor_currents = WHEELS.map(|wheel| field(layout, payload, &format!("current_{}_ma", wheel)) / 1000.0);
+    sensors.motor_temperatures = WHEELS.map(|wheel| field(layout, payload, &format!("temperature_{}_c", wheel)));
+}
+
+fn apply_link(layout: &TelemetryLayout, payload: &[u8], sensors: &mut SensorData) {
+    sensors.link_quality = field(layout, payload, "link_quality_percent");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telemetry::tests::{build_message, chassis_status_payload};
+
+    fn battery_message() -> Vec<u8> {
+        let mut payload = Vec::new();
+        payload.extend(12100u16.to_le_bytes());
+        payload.extend(500i16.to_le_bytes());
+        payload.push(95);
+        build_message(BATTERY_LAYOUT.cmd_set, BATTERY_LAYOUT.cmd_id, &payload)
+    }
+
+    fn gimbal_message() -> Vec<u8> {
+        let mut payload = Vec::new();
+        payload.extend((-150i16).to_le_bytes());
+        payload.extend(900i16.to_le_bytes());
+        build_message(GIMBAL_LAYOUT.cmd_set, GIMBAL_LAYOUT.cmd_id, &payload)
+    }
+
+    #[test]
+    fn test_routes_each_type_to_its_decoder() {
+        let mut sensors = SensorData::default();
+
+        assert_eq!(dispatch(&battery_message(), &mut sensors), Some(TelemetryKind::Battery));
+        assert!((sensors.battery_voltage - 12.1).abs() < 1e-4);
+        assert!((sensors.current - 0.5).abs() < 1e-4);
+        assert!(sensors.gimbal.yaw.is_nan(), "Gimbal readings should not be touched");
+
+        assert_eq!(dispatch(&gimbal_message(), &mut sensors), Some(TelemetryKind::Gimbal));
+        assert!((sensors.gimbal.pitch - (-15.0f32).to_radians()).abs() < 1e-4);
+        assert!((sensors.gimbal.yaw - 90.0f32.to_radians()).abs() < 1e-4);
+        // Battery readings from the earlier message are kept
+        assert!((sensors.battery_voltage - 12.1).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_gimbal_limit_flag_decoded() {
+        use crate::control::gimbal_limits::PITCH_LOWER_LIMIT;
+
+        let mut sensors = SensorData::default();
+        dispatch(&gimbal_message(), &mut sensors);
+        assert!(!sensors.gimbal.limits.any(), "Messages without flags report no limits");
+
+        let mut payload = Vec::new();
+        payload.extend((-300i16).to_le_bytes());
+        payload.extend(0i16.to_le_bytes());
+        payload.push(PITCH_LOWER_LIMIT);
+        let message = build_message(GIMBAL_LAYOUT.cmd_set, GIMBAL_LAYOUT.cmd_id, &payload);
+
+        assert_eq!(dispatch(&message, &mut sensors), Some(TelemetryKind::Gimbal));
+        assert!(sensors.gimbal.limits.pitch_lower);
+        assert!(!sensors.gimbal.limits.pitch_upper);
+
+        let restricted = sensors.gimbal.limits.restrict(crate::command::GimbalParams { ry: -1.0, rz: 0.4 });
+        assert_eq!(restricted.ry, 0.0);
+        assert_eq!(restricted.rz, 0.4);
+    }
+
+    #[test]
+    fn test_chassis_status_updates_everything() {
+        let mut sensors = SensorData::default();
+        let message = build_message(0x3F, 0xA0, &chassis_status_payload());
+
+        assert_eq!(message_kind(&message), Some(TelemetryKind::ChassisStatus));
+        assert_eq!(dispatch(&message, &mut sensors), Some(TelemetryKind::ChassisStatus));
+        assert!((sensors.temperature - 25.3).abs() < 1e-4);
+        assert!((sensors.imu.acceleration[2] - STANDARD_GRAVITY).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_imu_yaw_is_decoded() {
+        let mut payload = vec![0; 12];
+        payload.extend(900i16.to_le_bytes());
+        let message = build_message(IMU_LAYOUT.cmd_set, IMU_LAYOUT.cmd_id, &payload);
+
+        let mut sensors = SensorData::default();
+        assert_eq!(dispatch(&message, &mut sensors), Some(TelemetryKind::Imu));
+        assert!((sensors.yaw().unwrap() - 90.0f32.to_radians()).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_motor_status_is_decoded() {
+        let mut payload = Vec::new();
+        for current_ma in [800i16, -750, 820, 2600] {
+            payload.extend(current_ma.to_le_bytes());
+        }
+        for temperature in [310i16, 305, 312, i16::MAX] {
+            payload.extend(temperature.to_le_bytes());
+        }
+        let message = build_message(MOTOR_STATUS_LAYOUT.cmd_set, MOTOR_STATUS_LAYOUT.cmd_id, &payload);
+
+        let mut sensors = SensorData::default();
+        assert_eq!(dispatch(&message, &mut sensors), Some(TelemetryKind::Motors));
+        assert!((sensors.motor_currents[1] + 0.75).abs() < 1e-4);
+        assert!((sensors.motor_currents[3] - 2.6).abs() < 1e-4);
+        assert!((sensors.motor_temperatures[0] - 31.0).abs() < 1e-4);
+        assert!(sensors.motor_temperatures[3].is_nan(), "Faulted sensor decodes to NaN");
+        assert_eq!(sensors.wheel_anomaly(), Some(3));
+    }
+
+    #[test]
+    fn test_link_quality_is_decoded() {
+        let message = build_message(LINK_STATUS_LAYOUT.cmd_set, LINK_STATUS_LAYOUT.cmd_id, &[87]);
+
+        let mut sensors = SensorData::default();
+        assert!(sensors.link_quality().is_err());
+        assert_eq!(dispatch(&message, &mut sensors), Some(TelemetryKind::Link));
+        assert_eq!(sensors.link_quality().unwrap(), 87.0);
+
+        let unknown = build_message(LINK_STATUS_LAYOUT.cmd_set, LINK_STATUS_LAYOUT.cmd_id, &[0xFF]);
+        dispatch(&unknown, &mut sensors);
+        assert!(sensors.link_quality().is_err());
+    }
+
+    #[test]
+    fn test_unknown_message_is_not_dispatched() {
+        let mut sensors = SensorData::default();
+        let message = build_message(0x3F, 0x01, &[0; 4]);
+
+        assert_eq!(message_kind(&message), None);
+        assert_eq!(dispatch(&message, &mut sensors), None);
+        assert!(sensors.battery_voltage().is_err());
+    }
+}
@@ -0,0 +1,516 @@
+//! Telemetry decoding for messages pushed by the RoboMaster S1
+//!
+//! Telemetry messages use the same framing as outgoing commands:
+//!
+//! | Offset    | Size | Meaning                        |
+//! |-----------|------|--------------------------------|
+//! | 0         | 1    | `0x55` start of frame          |
+//! | 1         | 1    | total message length           |
+//! | 2         | 1    | protocol version (`0x04`)      |
+//! | 3         | 1    | CRC8 of bytes 0..3             |
+//! | 4         | 1    | sender                         |
+//! | 5         | 1    | receiver                       |
+//! | 6         | 2    | sequence counter (LE)          |
+//! | 8         | 1    | attribute                      |
+//! | 9         | 1    | command set                    |
+//! | 10        | 1    | command id                     |
+//! | 11        | n    | payload                        |
+//! | len - 2   | 2    | CRC16 (LE)                     |
+//!
+//! The payload layout of each known message is described by a
+//! [`TelemetryLayout`], so decoding is table-driven.
+//!
+//! None of the built-in layouts has been confirmed against a capture from a
+//! real S1 yet; their ids, offsets and scales are best guesses. Each layout
+//! notes this, and a [`TelemetryDecoder`] can correct one without a fork.
+//! [`decode_telemetry_fields`] helps compare a captured message with a
+//! layout.
+//!
+//! Sensors that are disconnected or faulted report a sentinel value instead
+//! of a reading (see [`FieldKind::is_sentinel`]). Such fields are left out of
+//! decoded field maps and decode to `NaN` in [`SensorData`].
+//!
+//! Messages are routed to per-type decoders by [`dispatch`]. Custom layouts
+//! can be supported by implementing [`TelemetryDecoder`].
+
+pub mod csv;
+pub mod decoder;
+pub mod dispatch;
+pub mod receiver;
+pub mod session;
+
+use crate::can::validate_message_length;
+use crate::command::SERIAL_NUMBER_CMD;
+use crate::control::SensorData;
+use std::collections::HashMap;
+
+pub use csv::{TelemetryCsv, CSV_HEADER};
+pub use decoder::{BuiltinDecoder, TelemetryDecoder};
+pub use dispatch::{dispatch, message_kind, TelemetryKind};
+pub use receiver::{TelemetryLog, TelemetryReceiver};
+pub use session::{read_candump, write_candump, Direction, SessionReader, SessionRecord, SessionRecorder};
+
+/// Start-of-frame byte for every RoboMaster message
+pub const TELEMETRY_SOF: u8 = 0x55;
+
+/// Offset of the command set byte
+pub const CMD_SET_OFFSET: usize = 9;
+
+/// Offset of the command id byte
+pub const CMD_ID_OFFSET: usize = 10;
+
+/// Offset of the first payload byte
+pub const PAYLOAD_OFFSET: usize = 11;
+
+/// Length of the trailing CRC16
+pub const CRC16_LEN: usize = 2;
+
+/// Raw encoding of a telemetry field
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldKind {
+    /// Unsigned 8-bit value
+    U8,
+    /// Unsigned 16-bit little-endian value
+    U16,
+    /// Signed 16-bit little-endian value
+    I16,
+}
+
+impl FieldKind {
+    /// Number of bytes occupied by this field
+    pub fn size(self) -> usize {
+        match self {
+            Self::U8 => 1,
+            Self::U16 | Self::I16 => 2,
+        }
+    }
+
+    /// Check whether a raw value is the "sensor unavailable" sentinel
+    ///
+    /// Unsigned fields use all-ones. Signed fields use the two extreme
+    /// values, so small negative readings such as sub-zero temperatures
+    /// still decode normally.
+    pub fn is_sentinel(self, raw: i32) -> bool {
+        match self {
+            Self::U8 => raw == u8::MAX as i32,
+            Self::U16 => raw == u16::MAX as i32,
+            Self::I16 => raw == i16::MAX as i32 || raw == i16::MIN as i32,
+        }
+    }
+}
+
+/// Description of a single field inside a telemetry payload
+#[derive(Debug, Clone, Copy)]
+pub struct FieldSpec {
+    /// Field name used in decoded field maps
+    pub name: &'static str,
+    /// Offset relative to the start of the payload
+    pub offset: usize,
+    /// Raw encoding
+    pub kind: FieldKind,
+    /// Factor converting the raw value to the field's unit
+    pub scale: f64,
+}
+
+impl FieldSpec {
+    /// Read the raw field value from a payload, if it is long enough
+    pub fn read_raw(&self, payload: &[u8]) -> Option<i32> {
+        let bytes = payload.get(self.offset..self.offset + self.kind.size())?;
+        Some(match self.kind {
+            FieldKind::U8 => bytes[0] as i32,
+            FieldKind::U16 => u16::from_le_bytes([bytes[0], bytes[1]]) as i32,
+            FieldKind::I16 => i16::from_le_bytes([bytes[0], bytes[1]]) as i32,
+        })
+    }
+
+    /// Read the field value converted to its unit
+    ///
+    /// Returns `None` if the payload is too short or the sensor reported
+    /// its fault sentinel.
+    pub fn read(&self, payload: &[u8]) -> Option<f64> {
+        self.read_raw(payload)
+            .filter(|&raw| !self.kind.is_sentinel(raw))
+            .map(|raw| raw as f64 * self.scale)
+    }
+}
+
+/// Payload layout of one telemetry message type
+#[derive(Debug, Clone, Copy)]
+pub struct TelemetryLayout {
+    /// Human-readable message name
+    pub name: &'static str,
+    /// Command set identifying the message
+    pub cmd_set: u8,
+    /// Command id identifying the message
+    pub cmd_id: u8,
+    /// Fields contained in the payload
+    pub fields: &'static [FieldSpec],
+}
+
+/// Chassis status push: battery, temperature and IMU readings
+///
+/// Not confirmed against a capture: the offsets assume the battery push
+/// fields, then the temperature, then the IMU push fields without yaw.
+pub const CHASSIS_STATUS_LAYOUT: TelemetryLayout = TelemetryLayout {
+    name: "chassis_status",
+    cmd_set: 0x3F,
+    cmd_id: 0xA0,
+    fields: &[
+        FieldSpec { name: "battery_mv", offset: 0, kind: FieldKind::U16, scale: 1.0 },
+        FieldSpec { name: "current_ma", offset: 2, kind: FieldKind::I16, scale: 1.0 },
+        FieldSpec { name: "battery_percent", offset: 4, kind: FieldKind::U8, scale: 1.0 },
+        FieldSpec { name: "temperature_c", offset: 5, kind: FieldKind::I16, scale: 0.1 },
+        FieldSpec { name: "accel_x", offset: 7, kind: FieldKind::I16, scale: 0.001 },
+        FieldSpec { name: "accel_y", offset: 9, kind: FieldKind::I16, scale: 0.001 },
+        FieldSpec { name: "accel_z", offset: 11, kind: FieldKind::I16, scale: 0.001 },
+        FieldSpec { name: "gyro_x", offset: 13, kind: FieldKind::I16, scale: 0.1 },
+        FieldSpec { name: "gyro_y", offset: 15, kind: FieldKind::I16, scale: 0.1 },
+        FieldSpec { name: "gyro_z", offset: 17, kind: FieldKind::I16, scale: 0.1 },
+    ],
+};
+
+/// Battery push: voltage, current and charge
+///
+/// Not confirmed against a capture; the table is the assumed layout.
+///
+/// Payload bytes (little endian):
+///
+/// | Offset | Type | Field                      |
+/// |--------|------|----------------------------|
+/// | 0      | u16  | pack voltage in mV         |
+/// | 2      | i16  | current draw in mA         |
+/// | 4      | u8   | remaining charge in %      |
+pub const BATTERY_LAYOUT: TelemetryLayout = TelemetryLayout {
+    name: "battery",
+    cmd_set: 0x3F,
+    cmd_id: 0xA1,
+    fields: &[
+        FieldSpec { name: "battery_mv", offset: 0, kind: FieldKind::U16, scale: 1.0 },
+        FieldSpec { name: "current_ma", offset: 2, kind: FieldKind::I16, scale: 1.0 },
+        FieldSpec { name: "battery_percent", offset: 4, kind: FieldKind::U8, scale: 1.0 },
+    ],
+};
+
+/// IMU push: accelerometer (g), gyroscope (deg/s) and yaw (deg)
+///
+/// Not confirmed against a capture, including the id and the 0.1° yaw
+/// resolution.
+pub const IMU_LAYOUT: TelemetryLayout = TelemetryLayout {
+    name: "imu",
+    cmd_set: 0x3F,
+    cmd_id: 0xA2,
+    fields: &[
+        FieldSpec { name: "accel_x", offset: 0, kind: FieldKind::I16, scale: 0.001 },
+        FieldSpec { name: "accel_y", offset: 2, kind: FieldKind::I16, scale: 0.001 },
+        FieldSpec { name: "accel_z", offset: 4, kind: FieldKind::I16, scale: 0.001 },
+        FieldSpec { name: "gyro_x", offset: 6, kind: FieldKind::I16, scale: 0.1 },
+        FieldSpec { name: "gyro_y", offset: 8, kind: FieldKind::I16, scale: 0.1 },
+        FieldSpec { name: "gyro_z", offset: 10, kind: FieldKind::I16, scale: 0.1 },
+        FieldSpec { name: "yaw_deg", offset: 12, kind: FieldKind::I16, scale: 0.1 },
+    ],
+};
+
+/// Gimbal push: pitch and yaw angles in degrees
+///
+/// Not confirmed against a capture. The command set is that of the gimbal
+/// commands; the id and offsets are unverified.
+pub const GIMBAL_LAYOUT: TelemetryLayout = TelemetryLayout {
+    name: "gimbal",
+    cmd_set: 0x04,
+    cmd_id: 0x70,
+    fields: &[
+        FieldSpec { name: "pitch_deg", offset: 0, kind: FieldKind::I16, scale: 0.1 },
+        FieldSpec { name: "yaw_deg", offset: 2, kind: FieldKind::I16, scale: 0.1 },
+        FieldSpec { name: "limit_flags", offset: 4, kind: FieldKind::U8, scale: 1.0 },
+    ],
+};
+
+/// Blaster status push: armed flag and gimbal firing readiness
+///
+/// Not confirmed against a capture; the id and the one-byte flags are
+/// assumed.
+pub const BLASTER_STATUS_LAYOUT: TelemetryLayout = TelemetryLayout {
+    name: "blaster_status",
+    cmd_set: 0x3F,
+    cmd_id: 0xB0,
+    fields: &[
+        FieldSpec { name: "armed", offset: 0, kind: FieldKind::U8, scale: 1.0 },
+        FieldSpec { name: "gimbal_ready", offset: 1, kind: FieldKind::U8, scale: 1.0 },
+    ],
+};
+
+/// Per-wheel motor push: current draw and winding temperature
+///
+/// Wheels are in [`WheelSpeeds`](crate::command::WheelSpeeds) order:
+/// front left, front right, rear left, rear right. Not confirmed against a
+/// capture, including that wheel order and the units below.
+///
+/// | Offset | Type | Field                           |
+/// |--------|------|---------------------------------|
+/// | 0      | i16  | motor current in mA, ×4         |
+/// | 8      | i16  | motor temperature in 0.1 °C, ×4 |
+pub const MOTOR_STATUS_LAYOUT: TelemetryLayout = TelemetryLayout {
+    name: "motor_status",
+    cmd_set: 0x3F,
+    cmd_id: 0xA3,
+    fields: &[
+        FieldSpec { name: "current_fl_ma", offset: 0, kind: FieldKind::I16, scale: 1.0 },
+        FieldSpec { name: "current_fr_ma", offset: 2, kind: FieldKind::I16, scale: 1.0 },
+        FieldSpec { name: "current_rl_ma", offset: 4, kind: FieldKind::I16, scale: 1.0 },
+        FieldSpec { name: "current_rr_ma", offset: 6, kind: FieldKind::I16, scale: 1.0 },
+        FieldSpec { name: "temperature_fl_c", offset: 8, kind: FieldKind::I16, scale: 0.1 },
+        FieldSpec { name: "temperature_fr_c", offset: 10, kind: FieldKind::I16, scale: 0.1 },
+        FieldSpec { name: "temperature_rl_c", offset: 12, kind: FieldKind::I16, scale: 0.1 },
+        FieldSpec { name: "temperature_rr_c", offset: 14, kind: FieldKind::I16, scale: 0.1 },
+    ],
+};
+
+/// Link status push: quality of the connection to the robot in percent
+///
+/// No capture has confirmed that the S1 pushes this message, so its id
+/// continues the chassis push range; `0xFF` means the quality is unknown.
+///
+/// | Offset | Type | Field                    |
+/// |--------|------|--------------------------|
+/// | 0      | u8   | link quality, 0 to 100 % |
+pub const LINK_STATUS_LAYOUT: TelemetryLayout = TelemetryLayout {
+    name: "link_status",
+    cmd_set: 0x3F,
+    cmd_id: 0xA4,
+    fields: &[
+        FieldSpec { name: "link_quality_percent", offset: 0, kind: FieldKind::U8, scale: 1.0 },
+    ],
+};
+
+/// All telemetry layouts known to the decoder
+pub const KNOWN_LAYOUTS: &[TelemetryLayout] = &[
+    CHASSIS_STATUS_LAYOUT,
+    BATTERY_LAYOUT,
+    IMU_LAYOUT,
+    GIMBAL_LAYOUT,
+    BLASTER_STATUS_LAYOUT,
+    MOTOR_STATUS_LAYOUT,
+    LINK_STATUS_LAYOUT,
+];
+
+/// Find the layout matching a message's command set and id
+pub fn find_layout(data: &[u8]) -> Option<&'static TelemetryLayout> {
+    let cmd_set = *data.get(CMD_SET_OFFSET)?;
+    let cmd_id = *data.get(CMD_ID_OFFSET)?;
+    KNOWN_LAYOUTS
+        .iter()
+        .find(|layout| layout.cmd_set == cmd_set && layout.cmd_id == cmd_id)
+}
+
+/// Get the payload of a complete telemetry message
+///
+/// Returns `None` if the header is not a RoboMaster header or the buffer
+/// length does not match the declared message length (see
+/// [`validate_message_length`]).
+pub fn payload(data: &[u8]) -> Option<&[u8]> {
+    if data.first() != Some(&TELEMETRY_SOF) {
+        return None;
+    }
+    let declared_len = validate_message_length(data).ok()?;
+    Some(&data[PAYLOAD_OFFSET..declared_len - CRC16_LEN])
+}
+
+/// Standard gravity used to convert accelerations from g to m/s²
+const STANDARD_GRAVITY: f32 = 9.80665;
+
+/// Decode a chassis status message into [`SensorData`]
+///
+/// Returns `None` if the message is not a chassis status message. Faulted
+/// sensors decode to `NaN`.
+pub fn decode_sensor_data(data: &[u8]) -> Option<SensorData> {
+    let layout = find_layout(data)?;
+    if layout.cmd_id != CHASSIS_STATUS_LAYOUT.cmd_id || layout.cmd_set != CHASSIS_STATUS_LAYOUT.cmd_set {
+        return None;
+    }
+    let payload = payload(data)?;
+
+    let mut sensors = SensorData::default();
+    dispatch::apply_chassis_status(layout, payload, &mut sensors);
+    Some(sensors)
+}
+
+/// Decode every known field of a telemetry message into a name/value map
+///
+/// Values are converted to the units named by the field (millivolts,
+/// milliamps, degrees Celsius, g, degrees per second). Unknown messages
+/// decode to an empty map and faulted sensors are omitted. Checksums are not verified, so this can also be
+/// used to inspect captured frames with damaged CRCs.
+///
+/// # Examples
+/// ```rust
+/// use robomaster_rust::telemetry::decode_telemetry_fields;
+///
+/// let fields = decode_telemetry_fields(&[0x55, 0x0d, 0x04]);
+/// assert!(fields.is_empty());
+/// ```
+pub fn decode_telemetry_fields(data: &[u8]) -> HashMap<String, f64> {
+    let mut fields = HashMap::new();
+
+    let (Some(layout), Some(payload)) = (find_layout(data), payload(data)) else {
+        return fields;
+    };
+
+    for spec in layout.fields {
+        if let Some(value) = spec.read(payload) {
+            fields.insert(spec.name.to_string(), value);
+        }
+    }
+
+    fields
+}
+
+/// Check whether a complete message answers the serial number query
+pub fn is_serial_number_response(data: &[u8]) -> bool {
+    let (cmd_set, cmd_id) = SERIAL_NUMBER_CMD;
+    data.get(CMD_SET_OFFSET) == Some(&cmd_set) && data.get(CMD_ID_OFFSET) == Some(&cmd_id)
+}
+
+/// Decode the serial number carried by a serial number response
+///
+/// The payload is a return code (zero on success), a little-endian length
+/// and that many ASCII bytes, of which trailing NUL padding is dropped.
+/// Returns `None` for other messages, a nonzero return code or a serial
+/// that is not printable ASCII.
+pub fn decode_serial_number(data: &[u8]) -> Option<String> {
+    if !is_serial_number_response(data) {
+        return None;
+    }
+    let (&return_code, rest) = payload(data)?.split_first()?;
+    if return_code != 0 || rest.len() < 2 {
+        return None;
+    }
+
+    let length = u16::from_le_bytes([rest[0], rest[1]]) as usize;
+    let serial = std::str::from_utf8(rest[2..].get(..length)?).ok()?.trim_end_matches('\0');
+    if serial.is_empty() || !serial.bytes().all(|byte| byte.is_ascii_graphic()) {
+        return None;
+    }
+    Some(serial.to_string())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::crc::{append_crc8_checksum, append_crc16_checksum, CRC16_INIT};
+
+    /// Build a correctly framed telemetry message around `payload`
+    pub(crate) fn build_message(cmd_set: u8, cmd_id: u8, payload: &[u8]) -> Vec<u8> {
+        let length = PAYLOAD_OFFSET + payload.len() + CRC16_LEN;
+        let mut message = vec![TELEMETRY_SOF, length as u8, 0x04];
+        append_crc8_checksum(&mut message);
+        message.extend([0x09, 0x03, 0x00, 0x00, 0x00, cmd_set, cmd_id]);
+        message.extend(payload);
+        append_crc16_checksum(&mut message, CRC16_INIT);
+        message
+    }
+
+    /// Chassis status payload: 11.85 V, -1.2 A, 80 %, 25.3 °C, 1 g on Z, 12.5 °/s yaw
+    pub(crate) fn chassis_status_payload() -> Vec<u8> {
+        let mut payload = Vec::new();
+        payload.extend(11850u16.to_le_bytes());
+        payload.extend((-1200i16).to_le_bytes());
+        payload.push(80);
+        payload.extend(253i16.to_le_bytes());
+        payload.extend(0i16.to_le_bytes());
+        payload.extend(0i16.to_le_bytes());
+        payload.extend(1000i16.to_le_bytes());
+        payload.extend(0i16.to_le_bytes());
+        payload.extend(0i16.to_le_bytes());
+        payload.extend(125i16.to_le_bytes());
+        payload
+    }
+
+    #[test]
+    fn test_decode_chassis_status_fields() {
+        let message = build_message(0x3F, 0xA0, &chassis_status_payload());
+        let fields = decode_telemetry_fields(&message);
+
+        assert_eq!(fields.len(), CHASSIS_STATUS_LAYOUT.fields.len());
+        assert_eq!(fields["battery_mv"], 11850.0);
+        assert_eq!(fields["current_ma"], -1200.0);
+        assert_eq!(fields["battery_percent"], 80.0);
+        assert!((fields["temperature_c"] - 25.3).abs() < 1e-9);
+        assert!((fields["accel_z"] - 1.0).abs() < 1e-9);
+        assert!((fields["gyro_z"] - 12.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_decode_serial_number() {
+        let serial_payload = |return_code: u8, serial: &[u8]| {
+            let mut payload = vec![return_code];
+            payload.extend((serial.len() as u16).to_le_bytes());
+            payload.extend(serial);
+            payload
+        };
+
+        let message = build_message(0x00, 0x4F, &serial_payload(0, b"3JKDH2T001ABCD\0\0"));
+        assert_eq!(decode_serial_number(&message).as_deref(), Some("3JKDH2T001ABCD"));
+
+        assert_eq!(decode_serial_number(&build_message(0x00, 0x4F, &serial_payload(1, b"3JKDH2T001ABCD"))), None);
+        assert_eq!(decode_serial_number(&build_message(0x00, 0x4F, &serial_payload(0, &[0xFF, 0x01]))), None);
+        assert_eq!(decode_serial_number(&build_message(0x00, 0x4F, &[0, 20, 0, b'3'])), None);
+        assert_eq!(decode_serial_number(&build_message(0x3F, 0xA0, &chassis_status_payload())), None);
+    }
+
+    #[test]
+    fn test_decode_negative_temperature() {
+        let mut payload = chassis_status_payload();
+        payload[5..7].copy_from_slice(&(-52i16).to_le_bytes());
+
+        let fields = decode_telemetry_fields(&build_message(0x3F, 0xA0, &payload));
+        assert!((fields["temperature_c"] + 5.2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_decode_sentinel_marks_sensor_unavailable() {
+        let mut payload = chassis_status_payload();
+        payload[5..7].copy_from_slice(&i16::MIN.to_le_bytes());
+        payload[0..2].copy_from_slice(&u16::MAX.to_le_bytes());
+        let message = build_message(0x3F, 0xA0, &payload);
+
+        let fields = decode_telemetry_fields(&message);
+        assert!(!fields.contains_key("temperature_c"));
+        assert!(!fields.contains_key("battery_mv"));
+        assert!(fields.contains_key("current_ma"));
+
+        let sensors = decode_sensor_data(&message).unwrap();
+        assert!(sensors.temperature.is_nan());
+        assert!(sensors.battery_voltage.is_nan());
+        assert!(sensors.temperature().is_err());
+    }
+
+    #[test]
+    fn test_decode_sensor_data() {
+        let sensors = decode_sensor_data(&build_message(0x3F, 0xA0, &chassis_status_payload())).unwrap();
+
+        assert!((sensors.battery_voltage - 11.85).abs() < 1e-4);
+        assert!((sensors.current + 1.2).abs() < 1e-4);
+        assert!((sensors.temperature().unwrap() - 25.3).abs() < 1e-4);
+        assert!((sensors.imu.acceleration[2] - STANDARD_GRAVITY).abs() < 1e-4);
+        assert!((sensors.imu.angular_velocity[2] - 12.5f32.to_radians()).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_decode_unknown_message_is_empty() {
+        let message = build_message(0x3F, 0x01, &chassis_status_payload());
+        assert!(decode_telemetry_fields(&message).is_empty());
+    }
+
+    #[test]
+    fn test_decode_over_long_message_is_empty() {
+        let mut message = build_message(0x3F, 0xA0, &chassis_status_payload());
+        message.extend([0, 0]);
+        assert!(decode_telemetry_fields(&message).is_empty());
+    }
+
+    #[test]
+    fn test_decode_truncated_message_is_empty() {
+        let message = build_message(0x3F, 0xA0, &chassis_status_payload());
+        assert!(decode_telemetry_fields(&message[..message.len() - 4]).is_empty());
+    }
+}
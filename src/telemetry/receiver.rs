@@ -0,0 +1,198 @@
+//! Reassembly of received frames with optional raw logging
+
+use crate::can::MessageReassembler;
+use crate::command::verify_message;
+use crate::crc::CRC16_INIT;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Raw frame log in `candump -l` format
+///
+/// Each line reads `(<seconds>.<micros>) <interface> <id>#<data>`, so logs
+/// can be replayed or inspected with the usual SocketCAN tools.
+pub struct TelemetryLog {
+    writer: Box<dyn Write + Send + Sync>,
+    interface_name: String,
+}
+
+impl TelemetryLog {
+    /// Create a log file at `path`, replacing any existing file
+    pub fn create(path: impl AsRef<Path>, interface_name: &str) -> std::io::Result<Self> {
+        let file = File::create(path)?;
+        Ok(Self::from_writer(BufWriter::new(file), interface_name))
+    }
+
+    /// Log to an arbitrary writer
+    pub fn from_writer(writer: impl Write + Send + Sync + 'static, interface_name: &str) -> Self {
+        Self {
+            writer: Box::new(writer),
+            interface_name: interface_name.to_string(),
+        }
+    }
+
+    /// Append one received frame
+    pub fn log_frame(&mut self, id: u16, data: &[u8]) -> std::io::Result<()> {
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+        let data: String = data.iter().map(|byte| format!("{:02X}", byte)).collect();
+        writeln!(
+            self.writer,
+            "({}.{:06}) {} {:03X}#{}",
+            timestamp.as_secs(),
+            timestamp.subsec_micros(),
+            self.interface_name,
+            id,
+            data
+        )
+    }
+
+    /// Flush buffered lines to the underlying writer
+    pub fn flush(&mut self) -> std::io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+/// Turns received frames into complete messages, teeing them to a log
+///
+/// Each CAN id is reassembled separately, so multi-frame messages from
+/// different senders may interleave on the bus. Completed messages are only
+/// returned once their CRC8 and CRC16 check out.
+pub struct TelemetryReceiver {
+    reassemblers: HashMap<u16, MessageReassembler>,
+    log: Option<TelemetryLog>,
+    crc16_init: u16,
+}
+
+impl TelemetryReceiver {
+    /// Create a receiver without logging
+    pub fn new() -> Self {
+        Self::with_crc16_init(CRC16_INIT)
+    }
+
+    /// Create a receiver checking messages with a firmware-specific CRC16 init value
+    pub fn with_crc16_init(crc16_init: u16) -> Self {
+        Self {
+            reassemblers: HashMap::new(),
+            log: None,
+            crc16_init,
+        }
+    }
+
+    /// Set the CRC16 init value used to check completed messages
+    pub fn set_crc16_init(&mut self, crc16_init: u16) {
+        self.crc16_init = crc16_init;
+    }
+
+    /// Start logging every received frame, replacing any previous log
+    pub fn set_log(&mut self, log: TelemetryLog) {
+        self.log = Some(log);
+    }
+
+    /// Stop logging and flush the previous log
+    pub fn clear_log(&mut self) -> std::io::Result<()> {
+        match self.log.take() {
+            Some(mut log) => log.flush(),
+            None => Ok(()),
+        }
+    }
+
+    /// Log a received frame and return a message once one is complete
+    ///
+    /// Malformed messages and messages failing their CRC checks are dropped
+    /// with a warning so a single bad frame does not stop live processing.
+    pub fn handle_frame(&mut self, id: u16, data: &[u8]) -> std::io::Result<Option<Vec<u8>>> {
+        if let Some(log) = &mut self.log {
+            log.log_frame(id, data)?;
+        }
+
+        let checked = self.reassemblers.entry(id).or_default().push(data).and_then(|message| match message {
+            Some(message) => verify_message(&message, self.crc16_init).map(|_| Some(message)),
+            None => Ok(None),
+        });
+        match checked {
+            Ok(message) => Ok(message),
+            Err(error) => {
+                tracing::warn!("dropping malformed telemetry message: {}", error);
+                Ok(None)
+            }
+        }
+    }
+}
+
+impl Default for TelemetryReceiver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for TelemetryReceiver {
+    fn drop(&mut self) {
+        let _ = self.clear_log();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::can::MessageSplitter;
+    use crate::telemetry::decode_sensor_data;
+    use crate::telemetry::tests::{build_message, chassis_status_payload};
+
+    #[test]
+    fn test_frames_are_logged_and_decoded() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("telemetry.log");
+
+        let mut receiver = TelemetryReceiver::new();
+        receiver.set_log(TelemetryLog::create(&path, "can0").unwrap());
+
+        let message = build_message(0x3F, 0xA0, &chassis_status_payload());
+        let frames = MessageSplitter::split_command(&message);
+
+        let mut completed = Vec::new();
+        for frame in &frames {
+            if let Some(message) = receiver.handle_frame(0x202, frame).unwrap() {
+                completed.push(message);
+            }
+        }
+
+        assert_eq!(completed, vec![message]);
+        let sensors = decode_sensor_data(&completed[0]).unwrap();
+        assert!((sensors.battery_voltage - 11.85).abs() < 1e-4);
+
+        receiver.clear_log().unwrap();
+        let log = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = log.lines().collect();
+        assert_eq!(lines.len(), frames.len());
+        for (line, frame) in lines.iter().zip(&frames) {
+            let data: String = frame.iter().map(|byte| format!("{:02X}", byte)).collect();
+            assert!(line.starts_with('('));
+            assert!(line.ends_with(&format!(" can0 202#{}", data)), "unexpected line {line}");
+        }
+    }
+
+    #[test]
+    fn test_interleaved_ids_reassemble_separately() {
+        let mut receiver = TelemetryReceiver::new();
+        let chassis = build_message(0x3F, 0xA0, &chassis_status_payload());
+        let other = build_message(0x3F, 0xA1, &[0x5A; 20]);
+        let chassis_frames = MessageSplitter::split_command(&chassis);
+        let other_frames = MessageSplitter::split_command(&other);
+        assert!(chassis_frames.len() > 1 && other_frames.len() > 1);
+
+        let mut completed = Vec::new();
+        for index in 0..chassis_frames.len().max(other_frames.len()) {
+            for (id, frames) in [(0x202, &chassis_frames), (0x203, &other_frames)] {
+                if let Some(frame) = frames.get(index) {
+                    completed.extend(receiver.handle_frame(id, frame).unwrap());
+                }
+            }
+        }
+
+        assert_eq!(completed.len(), 2);
+        assert!(completed.contains(&chassis));
+        assert!(completed.contains(&other));
+    }
+}
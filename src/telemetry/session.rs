@@ -0,0 +1,335 @@
+//! Compact binary recording of command and telemetry sessions
+//!
+//! A session file starts with [`SESSION_MAGIC`] and a version byte, followed
+//! by length-prefixed records:
+//!
+//! | Size | Meaning                                   |
+//! |------|-------------------------------------------|
+//! | 2    | length of the rest of the record (LE)     |
+//! | 1    | direction (`0` sent, `1` received)        |
+//! | 8    | timestamp in µs since the Unix epoch (LE) |
+//! | 2    | CAN identifier (LE)                       |
+//! | n    | frame data                                |
+//!
+//! A classic CAN frame takes 21 bytes instead of the ~45 of a `candump -l`
+//! line. [`write_candump`] and [`read_candump`] convert to and from text.
+
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Bytes identifying a session file
+pub const SESSION_MAGIC: [u8; 4] = *b"RMS1";
+
+/// Version of the record layout written by [`SessionRecorder`]
+pub const SESSION_VERSION: u8 = 1;
+
+/// Bytes of a record after the length prefix, excluding the frame data
+const RECORD_HEADER_LEN: usize = 1 + 8 + 2;
+
+/// Whether a frame was sent to or received from the robot
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// Frame sent to the robot
+    Sent,
+    /// Frame received from the robot
+    Received,
+}
+
+impl Direction {
+    fn to_byte(self) -> u8 {
+        match self {
+            Self::Sent => 0,
+            Self::Received => 1,
+        }
+    }
+
+    fn from_byte(byte: u8) -> io::Result<Self> {
+        match byte {
+            0 => Ok(Self::Sent),
+            1 => Ok(Self::Received),
+            _ => Err(invalid_data(format!("unknown direction {}", byte))),
+        }
+    }
+}
+
+/// One recorded CAN frame
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionRecord {
+    /// Whether the frame was sent or received
+    pub direction: Direction,
+    /// Time since the Unix epoch, with microsecond resolution
+    pub timestamp: Duration,
+    /// CAN identifier
+    pub id: u16,
+    /// Frame data
+    pub data: Vec<u8>,
+}
+
+impl SessionRecord {
+    /// Record a frame at the current time
+    pub fn now(direction: Direction, id: u16, data: &[u8]) -> Self {
+        Self {
+            direction,
+            timestamp: SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default(),
+            id,
+            data: data.to_vec(),
+        }
+    }
+}
+
+/// Writes records in the binary session format
+pub struct SessionRecorder<W: Write> {
+    writer: W,
+}
+
+impl SessionRecorder<BufWriter<File>> {
+    /// Create a session file at `path`, replacing any existing file
+    pub fn create(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::new(BufWriter::new(File::create(path)?))
+    }
+}
+
+impl<W: Write> SessionRecorder<W> {
+    /// Start a session on `writer`, writing the file header
+    pub fn new(mut writer: W) -> io::Result<Self> {
+        writer.write_all(&SESSION_MAGIC)?;
+        writer.write_all(&[SESSION_VERSION])?;
+        Ok(Self { writer })
+    }
+
+    /// Append one record
+    pub fn record(&mut self, record: &SessionRecord) -> io::Result<()> {
+        let length = u16::try_from(RECORD_HEADER_LEN + record.data.len())
+            .map_err(|_| invalid_data("record data too long".to_string()))?;
+        let micros = u64::try_from(record.timestamp.as_micros()).unwrap_or(u64::MAX);
+
+        self.writer.write_all(&length.to_le_bytes())?;
+        self.writer.write_all(&[record.direction.to_byte()])?;
+        self.writer.write_all(&micros.to_le_bytes())?;
+        self.writer.write_all(&record.id.to_le_bytes())?;
+        self.writer.write_all(&record.data)
+    }
+
+    /// Flush buffered records to the underlying writer
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    /// Flush and return the underlying writer
+    pub fn into_inner(mut self) -> io::Result<W> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+/// Reads records written by [`SessionRecorder`]
+///
+/// Iterating yields records until the end of the session; a truncated or
+/// corrupt record ends iteration with an error.
+pub struct SessionReader<R: Read> {
+    reader: R,
+}
+
+impl SessionReader<BufReader<File>> {
+    /// Open the session file at `path`
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::new(BufReader::new(File::open(path)?))
+    }
+}
+
+impl<R: Read> SessionReader<R> {
+    /// Read a session from `reader`, checking the file header
+    pub fn new(mut reader: R) -> io::Result<Self> {
+        let mut header = [0u8; SESSION_MAGIC.len() + 1];
+        reader.read_exact(&mut header)?;
+        if header[..SESSION_MAGIC.len()] != SESSION_MAGIC {
+            return Err(invalid_data("not a RoboMaster session file".to_string()));
+        }
+        if header[SESSION_MAGIC.len()] != SESSION_VERSION {
+            return Err(invalid_data(format!("unsupported session version {}", header[SESSION_MAGIC.len()])));
+        }
+        Ok(Self { reader })
+    }
+
+    /// Read the next record, or `None` at the end of the session
+    pub fn read_record(&mut self) -> io::Result<Option<SessionRecord>> {
+        let mut length = [0u8; 2];
+        match self.reader.read_exact(&mut length) {
+            Ok(()) => {}
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(e),
+        }
+        let length = u16::from_le_bytes(length) as usize;
+        if length < RECORD_HEADER_LEN {
+            return Err(invalid_data(format!("record length {} too short", length)));
+        }
+
+        let mut body = vec![0u8; length];
+        self.reader.read_exact(&mut body)?;
+        let data = body.split_off(RECORD_HEADER_LEN);
+        Ok(Some(SessionRecord {
+            direction: Direction::from_byte(body[0])?,
+            timestamp: Duration::from_micros(u64::from_le_bytes(body[1..9].try_into().unwrap())),
+            id: u16::from_le_bytes([body[9], body[10]]),
+            data,
+        }))
+    }
+}
+
+impl<R: Read> Iterator for SessionReader<R> {
+    type Item = io::Result<SessionRecord>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_record().transpose()
+    }
+}
+
+/// Write records as `candump -l` lines on `interface_name`
+///
+/// The text format has no direction, so it is dropped.
+pub fn write_candump<'a>(
+    records: impl IntoIterator<Item = &'a SessionRecord>,
+    interface_name: &str,
+    mut writer: impl Write,
+) -> io::Result<()> {
+    for record in records {
+        let data: String = record.data.iter().map(|byte| format!("{:02X}", byte)).collect();
+        writeln!(
+            writer,
+            "({}.{:06}) {} {:03X}#{}",
+            record.timestamp.as_secs(),
+            record.timestamp.subsec_micros(),
+            interface_name,
+            record.id,
+            data
+        )?;
+    }
+    writer.flush()
+}
+
+/// Read `candump -l` lines as received records
+///
+/// Blank lines are skipped; any other line that does not parse is an error.
+pub fn read_candump(reader: impl BufRead) -> io::Result<Vec<SessionRecord>> {
+    let mut records = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record = parse_candump_line(&line)
+            .ok_or_else(|| invalid_data(format!("malformed candump line: {}", line)))?;
+        records.push(record);
+    }
+    Ok(records)
+}
+
+fn parse_candump_line(line: &str) -> Option<SessionRecord> {
+    let mut fields = line.split_whitespace();
+    let timestamp = fields.next()?.strip_prefix('(')?.strip_suffix(')')?;
+    let _interface = fields.next()?;
+    let (id, data) = fields.next()?.split_once('#')?;
+
+    let (secs, micros) = timestamp.split_once('.')?;
+    let timestamp = Duration::from_secs(secs.parse().ok()?) + Duration::from_micros(micros.parse().ok()?);
+
+    if data.len() % 2 != 0 {
+        return None;
+    }
+    let data = (0..data.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&data[i..i + 2], 16).ok())
+        .collect::<Option<Vec<u8>>>()?;
+
+    Some(SessionRecord {
+        direction: Direction::Received,
+        timestamp,
+        id: u16::from_str_radix(id, 16).ok()?,
+        data,
+    })
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_records() -> Vec<SessionRecord> {
+        vec![
+            SessionRecord {
+                direction: Direction::Sent,
+                timestamp: Duration::from_micros(1_700_000_000_123_456),
+                id: 0x201,
+                data: vec![0x55, 0x1b, 0x04, 0x75, 0x09, 0xc3, 0x00, 0x00],
+            },
+            SessionRecord {
+                direction: Direction::Received,
+                timestamp: Duration::from_micros(1_700_000_000_124_000),
+                id: 0x202,
+                data: vec![0x40, 0x04],
+            },
+            SessionRecord {
+                direction: Direction::Received,
+                timestamp: Duration::from_micros(1_700_000_000_125_000),
+                id: 0x202,
+                data: Vec::new(),
+            },
+        ]
+    }
+
+    #[test]
+    fn test_session_round_trip() {
+        let records = sample_records();
+        let mut recorder = SessionRecorder::new(Vec::new()).unwrap();
+        for record in &records {
+            recorder.record(record).unwrap();
+        }
+        let bytes = recorder.into_inner().unwrap();
+        assert_eq!(bytes.len(), 5 + records.iter().map(|r| 2 + RECORD_HEADER_LEN + r.data.len()).sum::<usize>());
+
+        let read: Vec<SessionRecord> = SessionReader::new(bytes.as_slice())
+            .unwrap()
+            .collect::<io::Result<_>>()
+            .unwrap();
+        assert_eq!(read, records);
+    }
+
+    #[test]
+    fn test_reader_rejects_bad_header_and_truncated_record() {
+        assert!(SessionReader::new(&b"NOPE\x01"[..]).is_err());
+
+        let mut recorder = SessionRecorder::new(Vec::new()).unwrap();
+        recorder.record(&sample_records()[0]).unwrap();
+        let bytes = recorder.into_inner().unwrap();
+
+        let mut reader = SessionReader::new(&bytes[..bytes.len() - 1]).unwrap();
+        assert!(reader.read_record().is_err());
+    }
+
+    #[test]
+    fn test_candump_round_trip_keeps_frames() {
+        let records = sample_records();
+        let mut text = Vec::new();
+        write_candump(&records, "can0", &mut text).unwrap();
+
+        let text = String::from_utf8(text).unwrap();
+        assert_eq!(text.lines().next(), Some("(1700000000.123456) can0 201#551B047509C30000"));
+
+        let parsed = read_candump(text.as_bytes()).unwrap();
+        assert_eq!(parsed.len(), records.len());
+        for (parsed, original) in parsed.iter().zip(&records) {
+            assert_eq!(parsed.direction, Direction::Received);
+            assert_eq!((parsed.timestamp, parsed.id, &parsed.data), (original.timestamp, original.id, &original.data));
+        }
+    }
+
+    #[test]
+    fn test_read_candump_rejects_malformed_line() {
+        assert!(read_candump(&b"(1.000000) can0 201#5\n"[..]).is_err());
+    }
+}
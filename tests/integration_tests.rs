@@ -1,9 +1,17 @@
 /// Integration tests for RoboMaster Rust library
 /// These tests verify the complete functionality of the library
 
-use robomaster_rust::{RoboMaster, MovementCommand, LedCommand};
+use robomaster_rust::can::{MessageSplitter, MockCanBackend};
+use robomaster_rust::{CanInterface, RoboMaster, MovementCommand, LedCommand};
 use tokio::time::{timeout, Duration};
 
+/// Create a controller backed by a mock CAN bus
+fn mock_robot() -> (RoboMaster, MockCanBackend) {
+    let mock = MockCanBackend::new();
+    let robot = RoboMaster::with_interface(CanInterface::with_backend(mock.clone()));
+    (robot, mock)
+}
+
 #[tokio::test]
 async fn test_robot_initialization() {
     // Note: This test requires a CAN interface to be available
@@ -194,3 +202,1494 @@ fn test_rgb_command() {
     assert_eq!(color.green, 64);
     assert_eq!(color.blue, 192);
 }
+
+#[tokio::test]
+async fn test_custom_telemetry_decoder() {
+    use robomaster_rust::SensorData;
+
+    let (mut robot, mock) = mock_robot();
+
+    // A message outside the built-in layouts carrying a link quality byte
+    robot.set_telemetry_decoder(|message: &[u8]| {
+        (message[9..11] == [0x3F, 0xB7]).then(|| SensorData {
+            link_quality: message[11] as f32,
+            ..SensorData::default()
+        })
+    });
+    queue_message(&mock, &telemetry_message(0x3F, 0xB7, &[87]));
+    let sensors = robot.poll_sensors().await.unwrap();
+    assert_eq!(sensors.link_quality, 87.0);
+
+    // Built-in readings are kept alongside the custom ones
+    let mut status = Vec::new();
+    status.extend(12100u16.to_le_bytes());
+    status.extend(2000i16.to_le_bytes());
+    status.push(90);
+    status.extend(315i16.to_le_bytes());
+    for value in [0i16, 0, 1000, 0, 0, 0] {
+        status.extend(value.to_le_bytes());
+    }
+    queue_message(&mock, &telemetry_message(0x3F, 0xA0, &status));
+    let sensors = robot.poll_sensors().await.unwrap();
+    assert!((sensors.battery_voltage - 12.1).abs() < 1e-4);
+    assert_eq!(sensors.link_quality, 87.0);
+
+    let custom = telemetry_message(0x3F, 0xB7, &[40]);
+    assert_eq!(robot.decode_sensor_data(&custom).map(|data| data.link_quality), Some(40.0));
+    robot.reset_telemetry_decoder();
+    assert!(robot.decode_sensor_data(&custom).is_none());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_command_channel() {
+    use robomaster_rust::can::CommandCounters;
+    use robomaster_rust::command::CommandBuilder;
+    use robomaster_rust::control::RobotCommand;
+    use robomaster_rust::{LedColor, MovementParams};
+
+    let (mut robot, mock) = mock_robot();
+    robot.initialize().await.unwrap();
+    mock.take_sent_frames();
+    let (sender, handle) = robot.command_sender(4);
+
+    let movements: Vec<MovementParams> = (1..=5)
+        .map(|i| MovementParams { vx: 0.1 * i as f32, vy: 0.0, vz: 0.0 })
+        .collect();
+    let driver = {
+        let sender = sender.clone();
+        let movements = movements.clone();
+        tokio::spawn(async move {
+            for movement in movements {
+                sender.send(RobotCommand::Move(movement)).await.unwrap();
+            }
+        })
+    };
+    let lights = {
+        let sender = sender.clone();
+        tokio::spawn(async move {
+            for red in [10u8, 20, 30, 40, 50] {
+                sender.send(RobotCommand::Led(LedColor { red, green: 0, blue: 0 })).await.unwrap();
+            }
+        })
+    };
+    driver.await.unwrap();
+    lights.await.unwrap();
+    sender.send(RobotCommand::Stop).await.unwrap();
+    drop(sender);
+    handle.await.unwrap();
+
+    // Each producer's commands reach the bus in the order it sent them
+    let builder = CommandBuilder::new();
+    let counters = CommandCounters::default();
+    let twist = builder.build_twist_command(MovementParams::stopped(), &counters).unwrap();
+    let led = builder.build_led_command(LedColor { red: 0, green: 0, blue: 0 }, &counters).unwrap();
+    let sent = sent_messages(&mock);
+    let twists: Vec<Vec<u8>> = sent
+        .iter()
+        .filter(|message| message[9..11] == twist[9..11])
+        .map(|message| message[11..25].to_vec())
+        .collect();
+    let mut expected: Vec<Vec<u8>> = movements
+        .iter()
+        .map(|movement| builder.build_twist_command(*movement, &counters).unwrap()[11..25].to_vec())
+        .collect();
+    expected.push(twist[11..25].to_vec());
+    assert_eq!(twists, expected);
+    let reds: Vec<u8> = sent
+        .iter()
+        .filter(|message| message[9..11] == led[9..11])
+        .map(|message| message[14])
+        .collect();
+    assert_eq!(reds, [10, 20, 30, 40, 50]);
+}
+
+/// Count the twist commands among the messages sent through a mock backend
+fn twist_count(mock: &MockCanBackend) -> usize {
+    use robomaster_rust::command::CommandBuilder;
+    use robomaster_rust::can::CommandCounters;
+    use robomaster_rust::MovementParams;
+
+    let twist = CommandBuilder::new()
+        .build_twist_command(MovementParams::stopped(), &CommandCounters::default())
+        .unwrap();
+    sent_messages(mock)
+        .iter()
+        .filter(|message| message[9..11] == twist[9..11])
+        .count()
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_hold_resends_until_dropped() {
+    use robomaster_rust::MovementParams;
+
+    let (mut robot, mock) = mock_robot();
+    robot.initialize().await.unwrap();
+    mock.take_sent_frames();
+
+    let handle = robot.hold(MovementParams { vx: 0.2, vy: 0.0, vz: 0.0 }).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(250)).await;
+    assert!(handle.is_active());
+    assert!(twist_count(&mock) > 1, "Movement should be resent while held");
+
+    drop(handle);
+    tokio::task::yield_now().await;
+    mock.take_sent_frames();
+    tokio::time::sleep(Duration::from_millis(250)).await;
+    assert_eq!(twist_count(&mock), 0, "Resending should stop after drop");
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_stop_cancels_hold() {
+    use robomaster_rust::MovementParams;
+
+    let (mut robot, mock) = mock_robot();
+    robot.initialize().await.unwrap();
+    let handle = robot.hold(MovementParams { vx: 0.2, vy: 0.0, vz: 0.0 }).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    robot.stop().await.unwrap();
+    tokio::task::yield_now().await;
+    assert!(!handle.is_active(), "Stopping should cancel the hold");
+    mock.take_sent_frames();
+    tokio::time::sleep(Duration::from_millis(250)).await;
+    assert_eq!(twist_count(&mock), 0, "Nothing should be resent after a stop");
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_rotate_continuous_tracks_yaw() {
+    let (mut robot, mock) = mock_robot();
+    robot.initialize().await.unwrap();
+    mock.take_sent_frames();
+
+    let handle = robot.rotate_continuous(0.3).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert!(twist_count(&mock) >= 2, "Rotation frames should be sent");
+
+    // Upright IMU pushes turning through ±180°: 170°, -170°, -150°
+    for yaw_decidegrees in [1700i16, -1700, -1500] {
+        let mut imu = Vec::new();
+        for value in [0i16, 0, 1000, 0, 0, 0, yaw_decidegrees] {
+            imu.extend(value.to_le_bytes());
+        }
+        queue_message(&mock, &telemetry_message(0x3F, 0xA2, &imu));
+        robot.poll_sensors().await.unwrap();
+    }
+    assert!((robot.accumulated_yaw() - 40f32.to_radians()).abs() < 1e-4);
+    assert!(handle.is_active());
+
+    handle.release();
+    robot.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_min_axis_change_skips_tiny_changes() {
+    use robomaster_rust::MovementParams;
+
+    let (mut robot, mock) = mock_robot();
+    robot.set_min_axis_change(0.01);
+    robot.move_robot(MovementParams { vx: 0.3, vy: 0.0, vz: 0.0 }).await.unwrap();
+    mock.take_sent_frames();
+
+    for delta in [0.001, 0.004, 0.009, 0.002] {
+        robot.move_robot(MovementParams { vx: 0.3 + delta, vy: 0.0, vz: 0.0 }).await.unwrap();
+    }
+    assert!(mock.sent_frames().is_empty(), "Sub-threshold changes should not be sent");
+
+    robot.move_robot(MovementParams { vx: 0.35, vy: 0.0, vz: 0.0 }).await.unwrap();
+    assert!(!mock.sent_frames().is_empty(), "A larger change should be sent");
+}
+
+#[tokio::test]
+async fn test_twist_only_stop_emits_only_twist() {
+    use robomaster_rust::command::CommandBuilder;
+    use robomaster_rust::control::StopMode;
+    use robomaster_rust::MovementParams;
+
+    let (mut robot, mock) = mock_robot();
+    robot.initialize().await.unwrap();
+    mock.take_sent_frames();
+
+    robot.set_stop_mode(StopMode::TwistOnly);
+    let before = robot.get_counters();
+    robot.stop().await.unwrap();
+
+    let twist = CommandBuilder::new().build_twist_command(MovementParams::stopped(), &before).unwrap();
+    assert_eq!(mock.sent_frames(), MessageSplitter::split_command(&twist));
+    assert_eq!(robot.get_counters().gimbal, before.gimbal, "Stop should not send a gimbal command");
+}
+
+#[tokio::test]
+async fn test_move_robot_emits_twist_and_gimbal_bytes() {
+    use robomaster_rust::command::CommandBuilder;
+    use robomaster_rust::{GimbalParams, MovementParams};
+
+    let (mut robot, mock) = mock_robot();
+    robot.initialize().await.unwrap();
+    let boot = CommandBuilder::new().build_boot_sequence().unwrap();
+    assert_eq!(mock.take_sent_frames(), MessageSplitter::split_command(&boot));
+
+    let movement = MovementParams { vx: 0.5, vy: -0.25, vz: 0.1 };
+    let counters = robot.get_counters();
+    robot.move_robot(movement).await.unwrap();
+
+    let builder = CommandBuilder::new();
+    let mut expected = MessageSplitter::split_command(&builder.build_twist_command(movement, &counters).unwrap());
+    expected.extend(MessageSplitter::split_command(
+        &builder.build_gimbal_command(GimbalParams { ry: 0.0, rz: movement.vz }, &counters).unwrap(),
+    ));
+    assert_eq!(mock.sent_frames(), expected);
+}
+
+#[tokio::test]
+async fn test_flipped_orientation_turns_forward_into_backward() {
+    use robomaster_rust::command::CommandBuilder;
+    use robomaster_rust::MovementParams;
+
+    let (mut robot, mock) = mock_robot();
+    robot.initialize().await.unwrap();
+    robot.set_orientation(true);
+    mock.take_sent_frames();
+
+    let counters = robot.get_counters();
+    robot.move_robot(MovementParams { vx: 0.5, vy: 0.2, vz: 0.3 }).await.unwrap();
+
+    let backward = MovementParams { vx: -0.5, vy: -0.2, vz: 0.3 };
+    let twist = CommandBuilder::new().build_twist_command(backward, &counters).unwrap();
+    assert_eq!(sent_messages(&mock)[0], twist);
+}
+
+#[tokio::test]
+async fn test_speed_limit_clamps_every_axis() {
+    use robomaster_rust::command::CommandBuilder;
+    use robomaster_rust::error::ControlError;
+    use robomaster_rust::{MovementParams, RoboMasterError};
+
+    let (mut robot, mock) = mock_robot();
+    robot.initialize().await.unwrap();
+    robot.set_speed_limit(0.3).unwrap();
+    mock.take_sent_frames();
+
+    let counters = robot.get_counters();
+    robot.move_robot(MovementParams { vx: 1.0, vy: -0.8, vz: 0.1 }).await.unwrap();
+
+    let limited = MovementParams { vx: 0.3, vy: -0.3, vz: 0.1 };
+    let twist = CommandBuilder::new().build_twist_command(limited, &counters).unwrap();
+    assert_eq!(sent_messages(&mock)[0], twist);
+
+    for invalid in [-0.1, 1.5, f32::NAN] {
+        assert!(matches!(
+            robot.set_speed_limit(invalid),
+            Err(RoboMasterError::Control(ControlError::SpeedOutOfRange { .. }))
+        ));
+    }
+    assert_eq!(robot.speed_limit(), 0.3);
+}
+
+#[tokio::test]
+async fn test_min_effective_speed_boosts_creep_commands() {
+    use robomaster_rust::command::CommandBuilder;
+    use robomaster_rust::MovementParams;
+
+    let (mut robot, mock) = mock_robot();
+    robot.initialize().await.unwrap();
+    robot.set_min_effective_speed(0.05);
+    mock.take_sent_frames();
+
+    let counters = robot.get_counters();
+    robot.move_robot(MovementParams { vx: 0.02, vy: 0.0, vz: 0.0 }).await.unwrap();
+
+    let boosted = MovementParams { vx: 0.05, vy: 0.0, vz: 0.0 };
+    let twist = CommandBuilder::new().build_twist_command(boosted, &counters).unwrap();
+    assert_eq!(sent_messages(&mock)[0], twist);
+}
+
+#[tokio::test]
+async fn test_chassis_mode_sets_flags_and_gimbal_coupling() {
+    use robomaster_rust::command::CommandBuilder;
+    use robomaster_rust::{ChassisMode, GimbalParams, MovementParams};
+
+    let (mut robot, mock) = mock_robot();
+    robot.initialize().await.unwrap();
+    assert_eq!(robot.chassis_mode(), Some(ChassisMode::ChassisLead));
+    let movement = MovementParams { vx: 0.5, vy: 0.0, vz: 0.4 };
+
+    for (mode, gimbal) in [
+        (ChassisMode::GimbalLead, GimbalParams { ry: 0.0, rz: 0.4 }),
+        (ChassisMode::Free, GimbalParams::neutral()),
+    ] {
+        robot.set_chassis_mode(mode);
+        assert_eq!(robot.chassis_mode(), Some(mode));
+        mock.take_sent_frames();
+
+        let counters = robot.get_counters();
+        robot.move_robot(movement).await.unwrap();
+
+        let mut builder = CommandBuilder::new();
+        builder.set_twist_enable(mode.twist_enable());
+        let messages = sent_messages(&mock);
+        assert_eq!(messages[0], builder.build_twist_command(movement, &counters).unwrap());
+        assert_eq!(messages[1], builder.build_gimbal_command(gimbal, &counters).unwrap());
+        robot.stop().await.unwrap();
+    }
+}
+
+#[tokio::test]
+async fn test_apply_settings_takes_effect() {
+    use robomaster_rust::command::CommandBuilder;
+    use robomaster_rust::{GimbalParams, LedColor, MovementParams, RobotSettings};
+
+    let (mut robot, mock) = mock_robot();
+    robot.initialize().await.unwrap();
+    mock.take_sent_frames();
+
+    let green = LedColor { red: 0, green: 255, blue: 0 };
+    let settings = RobotSettings {
+        deadzone: 0.1,
+        max_speed: 0.5,
+        gimbal_follow: false,
+        status_led: Some(green),
+        ..Default::default()
+    };
+    let counters = robot.get_counters();
+    robot.apply_settings(&settings).await.unwrap();
+
+    // The status LED is sent right away
+    let builder = CommandBuilder::new();
+    assert_eq!(sent_messages(&mock), vec![builder.build_led_command(green, &counters).unwrap()]);
+    mock.take_sent_frames();
+
+    // The next movement is scaled by the max speed, with the deadzone
+    // applied, and the gimbal no longer follows the rotation
+    let counters = robot.get_counters();
+    robot.move_robot(MovementParams { vx: 1.0, vy: 0.05, vz: 0.4 }).await.unwrap();
+    let messages = sent_messages(&mock);
+    let expected = MovementParams { vx: 0.5, vy: 0.0, vz: 0.2 };
+    assert_eq!(messages[0], builder.build_twist_command(expected, &counters).unwrap());
+    assert_eq!(messages[1], builder.build_gimbal_command(GimbalParams::neutral(), &counters).unwrap());
+}
+
+#[tokio::test]
+async fn test_command_hook_sees_move_robot_commands() {
+    use robomaster_rust::can::CommandHook;
+    use robomaster_rust::command::CommandKind;
+    use robomaster_rust::{MovementParams, RoboMasterError};
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Default)]
+    struct Recorder {
+        before: Mutex<Vec<(CommandKind, usize)>>,
+        after: Mutex<Vec<(CommandKind, bool)>>,
+    }
+
+    impl CommandHook for Recorder {
+        fn before_send(&self, kind: &CommandKind, frames: &[Vec<u8>]) {
+            self.before.lock().unwrap().push((*kind, frames.len()));
+        }
+
+        fn after_send(&self, kind: &CommandKind, result: &Result<(), RoboMasterError>) {
+            self.after.lock().unwrap().push((*kind, result.is_ok()));
+        }
+    }
+
+    let (mut robot, mock) = mock_robot();
+    robot.initialize().await.unwrap();
+    let recorder = Arc::new(Recorder::default());
+    robot.add_command_hook(recorder.clone());
+    mock.take_sent_frames();
+
+    robot.move_robot(MovementParams { vx: 0.5, vy: 0.0, vz: 0.0 }).await.unwrap();
+
+    // 27-byte twist and 20-byte gimbal commands
+    assert_eq!(mock.sent_frames().len(), 7);
+    assert_eq!(
+        *recorder.before.lock().unwrap(),
+        [(CommandKind::Twist, 4), (CommandKind::Gimbal, 3)]
+    );
+    assert_eq!(
+        *recorder.after.lock().unwrap(),
+        [(CommandKind::Twist, true), (CommandKind::Gimbal, true)]
+    );
+}
+
+#[tokio::test]
+async fn test_echo_from_mock_backend_resyncs_counter() {
+    use socketcan::{CanFrame, EmbeddedFrame, StandardId};
+
+    let (mut robot, mock) = mock_robot();
+    let echo = [0x55, 0x1b, 0x04, 0x75, 0x09, 0xc3, 0x2c, 0x01];
+    mock.queue_frame(CanFrame::new(StandardId::new(0x201).unwrap(), &echo).unwrap());
+
+    robot.receive_messages().await.unwrap();
+    assert_eq!(mock.queued(), 0);
+    assert_eq!(robot.get_counters().joy, 301);
+    assert_eq!(robot.counter_resyncs(), 1);
+}
+
+#[tokio::test]
+async fn test_silent_robot_reports_protocol_mismatch() {
+    use robomaster_rust::error::ProtocolError;
+    use robomaster_rust::{MovementParams, RoboMasterError};
+    use socketcan::{CanFrame, EmbeddedFrame, StandardId};
+
+    let (mut robot, mock) = mock_robot();
+    robot.initialize().await.unwrap();
+    robot.set_max_unechoed_commands(Some(3));
+
+    let movement = MovementParams { vx: 0.3, vy: 0.0, vz: 0.0 };
+    robot.move_robot(movement).await.unwrap();
+    robot.move_robot(movement).await.unwrap();
+    robot.receive_messages().await.unwrap();
+    assert!(matches!(
+        robot.move_robot(movement).await,
+        Err(RoboMasterError::Protocol(ProtocolError::NoCounterEcho { commands: 3, .. }))
+    ));
+    assert!(robot.stop().await.is_err(), "keeps failing until the robot echoes");
+
+    let echo = [0x55, 0x1b, 0x04, 0x75, 0x09, 0xc3, 0x04, 0x00];
+    mock.queue_frame(CanFrame::new(StandardId::new(0x201).unwrap(), &echo).unwrap());
+    robot.receive_messages().await.unwrap();
+    robot.move_robot(movement).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_reconnect_reopens_installed_backend() {
+    use robomaster_rust::can::ConnectionState;
+    use robomaster_rust::MovementParams;
+
+    let (mut robot, mock) = mock_robot();
+    robot.initialize().await.unwrap();
+    let movement = MovementParams { vx: 0.3, vy: 0.0, vz: 0.0 };
+
+    mock.disconnect();
+    assert!(robot.move_robot(movement).await.is_err());
+    assert_eq!(robot.connection_state(), ConnectionState::Disconnected);
+
+    mock.fail_reconnects(1);
+    assert!(robot.reconnect().await.is_err());
+    assert_eq!(mock.reconnects(), 0);
+
+    mock.take_sent_frames();
+    robot.reconnect().await.unwrap();
+    assert_eq!(mock.reconnects(), 1);
+    assert_eq!(robot.connection_state(), ConnectionState::Connected);
+    assert!(!mock.take_sent_frames().is_empty(), "The boot sequence should be resent");
+
+    robot.move_robot(movement).await.unwrap();
+    assert!(twist_count(&mock) > 0);
+}
+
+#[tokio::test]
+async fn test_auto_reconnect_survives_adapter_drop() {
+    use robomaster_rust::can::{AutoReconnect, ConnectionState};
+    use robomaster_rust::error::{CanError, RoboMasterError};
+    use robomaster_rust::MovementParams;
+
+    let (mut robot, mock) = mock_robot();
+    robot.initialize().await.unwrap();
+    let movement = MovementParams { vx: 0.3, vy: 0.0, vz: 0.0 };
+
+    mock.disconnect();
+    assert!(robot.move_robot(movement).await.is_err());
+    assert_eq!(robot.connection_state(), ConnectionState::Disconnected);
+
+    robot.set_auto_reconnect(Some(AutoReconnect {
+        max_attempts: 3,
+        initial_delay: Duration::from_millis(1),
+        max_delay: Duration::from_millis(10),
+    }));
+    mock.fail_reconnects(1);
+    mock.take_sent_frames();
+
+    // Sends fail fast while the backoff runs instead of blocking the runtime
+    loop {
+        match robot.move_robot(movement).await {
+            Ok(()) => break,
+            Err(RoboMasterError::CanInterface(CanError::Reconnecting { retry_after_ms })) => {
+                assert_eq!(robot.connection_state(), ConnectionState::Reconnecting);
+                tokio::time::sleep(Duration::from_millis(retry_after_ms)).await;
+            }
+            Err(error) => panic!("unexpected error: {}", error),
+        }
+    }
+    assert_eq!(robot.connection_state(), ConnectionState::Connected);
+    assert_eq!(mock.reconnects(), 1);
+    assert!(!mock.sent_frames().is_empty());
+}
+
+#[tokio::test]
+async fn test_gimbal_limit_flag_zeroes_gimbal_command() {
+    use robomaster_rust::command::CommandBuilder;
+    use robomaster_rust::GimbalParams;
+
+    let (mut robot, mock) = mock_robot();
+    robot.initialize().await.unwrap();
+    mock.take_sent_frames();
+
+    // Gimbal telemetry: pitch 30°, yaw 0°, pitch upper limit reached
+    let mut gimbal = Vec::new();
+    gimbal.extend(300i16.to_le_bytes());
+    gimbal.extend(0i16.to_le_bytes());
+    gimbal.push(0x01);
+    robot.process_telemetry(&telemetry_message(0x04, 0x70, &gimbal));
+    assert!(robot.sensor_data().gimbal.limits.pitch_upper);
+
+    let counters = robot.get_counters();
+    robot.move_gimbal(GimbalParams { ry: 0.5, rz: 0.0 }).await.unwrap();
+
+    let stopped = CommandBuilder::new().build_gimbal_command(GimbalParams::neutral(), &counters).unwrap();
+    assert_eq!(mock.sent_frames(), MessageSplitter::split_command(&stopped));
+}
+
+#[tokio::test]
+async fn test_poll_sensors_parses_battery_frames() {
+    use socketcan::{CanFrame, EmbeddedFrame, StandardId};
+
+    let (mut robot, mock) = mock_robot();
+    let queue_message = |message: &[u8]| {
+        for data in MessageSplitter::split_command(message) {
+            mock.queue_frame(CanFrame::new(StandardId::new(0x202).unwrap(), &data).unwrap());
+        }
+    };
+
+    assert!(robot.poll_sensors().await.unwrap().battery_voltage().is_err());
+
+    // Battery push: 11.9 V, 1.5 A, 72 %
+    let mut battery = Vec::new();
+    battery.extend(11900u16.to_le_bytes());
+    battery.extend(1500i16.to_le_bytes());
+    battery.push(72);
+    queue_message(&telemetry_message(0x3F, 0xA1, &battery));
+
+    let sensors = robot.poll_sensors().await.unwrap();
+    assert_eq!(mock.queued(), 0);
+    assert!((sensors.battery_voltage().unwrap() - 11.9).abs() < 1e-4);
+    assert!((sensors.current - 1.5).abs() < 1e-4);
+
+    // An unknown message leaves the battery readings untouched
+    queue_message(&telemetry_message(0x3F, 0x01, &[0, 0]));
+    let sensors = robot.poll_sensors().await.unwrap();
+    assert!((sensors.battery_voltage - 11.9).abs() < 1e-4);
+}
+
+#[tokio::test]
+async fn test_corrupted_telemetry_is_ignored() {
+    let (mut robot, mock) = mock_robot();
+    let mut battery = Vec::new();
+    battery.extend(11900u16.to_le_bytes());
+    battery.extend(1500i16.to_le_bytes());
+    battery.push(72);
+    let message = telemetry_message(0x3F, 0xA1, &battery);
+
+    // A flipped payload bit fails the CRC16, a flipped header CRC8 the header check
+    let mut bad_payload = message.clone();
+    bad_payload[11] ^= 0x01;
+    let mut bad_header = message.clone();
+    bad_header[3] ^= 0x01;
+    for corrupted in [bad_payload, bad_header] {
+        queue_message(&mock, &corrupted);
+        let sensors = robot.poll_sensors().await.unwrap();
+        assert!(sensors.battery_voltage.is_nan(), "corrupted message was decoded");
+        assert!(robot.process_telemetry(&corrupted).is_none());
+        assert!(robot.sensor_data().battery_voltage.is_nan());
+    }
+
+    queue_message(&mock, &message);
+    assert!((robot.poll_sensors().await.unwrap().battery_voltage - 11.9).abs() < 1e-4);
+}
+
+/// Reassemble the protocol messages sent through a mock backend
+fn sent_messages(mock: &MockCanBackend) -> Vec<Vec<u8>> {
+    let bytes: Vec<u8> = mock.sent_frames().concat();
+    let mut messages = Vec::new();
+    let mut offset = 0;
+    while offset + 1 < bytes.len() {
+        let length = bytes[offset + 1] as usize;
+        messages.push(bytes[offset..offset + length].to_vec());
+        offset += length;
+    }
+    messages
+}
+
+#[tokio::test]
+async fn test_control_loop_runs_bounded_iterations() {
+    use robomaster_rust::command::CommandBuilder;
+    use robomaster_rust::control::ControlLoop;
+    use robomaster_rust::MovementParams;
+
+    let (mut robot, mock) = mock_robot();
+    robot.initialize().await.unwrap();
+    mock.take_sent_frames();
+
+    let control_loop = ControlLoop::new(500).unwrap();
+    let mut cycle = 0;
+    control_loop
+        .run_for_iterations(&mut robot, 10, || {
+            cycle += 1;
+            MovementParams { vx: 0.05 * cycle as f32, vy: 0.0, vz: 0.0 }
+        })
+        .await
+        .unwrap();
+
+    let twist = CommandBuilder::new()
+        .build_twist_command(MovementParams::stopped(), &robot.get_counters())
+        .unwrap();
+    let twists = sent_messages(&mock)
+        .iter()
+        .filter(|message| message[9..11] == twist[9..11])
+        .count();
+    assert_eq!(cycle, 10);
+    assert_eq!(twists, 10, "Each iteration should send exactly one twist");
+    assert_eq!(robot.get_counters().gimbal, 10);
+}
+
+#[tokio::test]
+async fn test_set_gimbal_angle_sends_position_command() {
+    use robomaster_rust::command::CommandBuilder;
+    use robomaster_rust::error::{ControlError, RoboMasterError};
+
+    let (mut robot, mock) = mock_robot();
+    robot.initialize().await.unwrap();
+    mock.take_sent_frames();
+
+    let expected = CommandBuilder::new()
+        .build_gimbal_position_command(10.0, -45.0, &robot.get_counters())
+        .unwrap();
+    robot.set_gimbal_angle(10.0, -45.0).await.unwrap();
+    assert_eq!(sent_messages(&mock), vec![expected]);
+    assert_eq!(robot.get_counters().gimbal, 1);
+
+    mock.take_sent_frames();
+    let error = robot.set_gimbal_angle(0.0, 300.0).await.unwrap_err();
+    assert!(matches!(
+        error,
+        RoboMasterError::Control(ControlError::AngleOutOfRange { ref axis, .. }) if axis == "yaw"
+    ));
+    assert!(mock.sent_frames().is_empty(), "Out-of-range angles must not be sent");
+}
+
+#[tokio::test]
+async fn test_set_gimbal_angle_respects_limit_flags() {
+    use robomaster_rust::command::CommandBuilder;
+
+    let (mut robot, mock) = mock_robot();
+    robot.initialize().await.unwrap();
+
+    // Gimbal telemetry: pitch 30°, yaw 0°, pitch upper limit reached
+    let mut gimbal = Vec::new();
+    gimbal.extend(300i16.to_le_bytes());
+    gimbal.extend(0i16.to_le_bytes());
+    gimbal.push(0x01);
+    robot.process_telemetry(&telemetry_message(0x04, 0x70, &gimbal));
+    mock.take_sent_frames();
+
+    let counters = robot.get_counters();
+    robot.set_gimbal_angle(35.0, -45.0).await.unwrap();
+    let held = CommandBuilder::new().build_gimbal_position_command(30.0, -45.0, &counters).unwrap();
+    assert_eq!(sent_messages(&mock), vec![held]);
+}
+
+#[tokio::test]
+async fn test_last_send_confirmed_through_loopback() {
+    let (mut robot, mock) = mock_robot();
+    robot.initialize().await.unwrap();
+    assert!(!robot.last_send_confirmed(), "Confirmation is off by default");
+
+    robot.enable_tx_confirmation().unwrap();
+    robot.stop().await.unwrap();
+    assert!(robot.last_send_confirmed());
+
+    // Frames that never come back were queued but not transmitted
+    mock.set_loopback(false);
+    robot.stop().await.unwrap();
+    assert!(!robot.last_send_confirmed());
+}
+
+#[tokio::test]
+async fn test_set_wheel_speeds_sends_clamped_wheel_command() {
+    use robomaster_rust::command::{CommandBuilder, MAX_WHEEL_RPM};
+    use robomaster_rust::WheelSpeeds;
+
+    let (mut robot, mock) = mock_robot();
+    robot.initialize().await.unwrap();
+    mock.take_sent_frames();
+
+    let speeds = WheelSpeeds { front_left: 200.0, front_right: -200.0, rear_left: 5000.0, rear_right: 0.0 };
+    let counters = robot.get_counters();
+    robot.set_wheel_speeds(speeds).await.unwrap();
+
+    let messages = sent_messages(&mock);
+    assert_eq!(messages, vec![CommandBuilder::new().build_wheel_command(speeds, &counters).unwrap()]);
+    let rear_left = i16::from_le_bytes([messages[0][15], messages[0][16]]);
+    assert_eq!(rear_left, MAX_WHEEL_RPM as i16);
+    assert_eq!(robot.get_counters().joy, counters.joy.wrapping_add(1));
+}
+
+#[tokio::test]
+async fn test_set_wheel_speeds_respects_safe_mode_and_replaces_twist() {
+    use robomaster_rust::command::builder::WHEEL_SPEED_CMD;
+    use robomaster_rust::command::MAX_WHEEL_RPM;
+    use robomaster_rust::{MovementParams, WheelSpeeds};
+
+    let (mut robot, mock) = mock_robot();
+    robot.initialize().await.unwrap();
+    robot.enable_safe_mode(0.5);
+    robot.move_robot(MovementParams { vx: 0.5, vy: 0.0, vz: 0.0 }).await.unwrap();
+    mock.take_sent_frames();
+
+    let speeds = WheelSpeeds { front_left: 800.0, front_right: -800.0, rear_left: 100.0, rear_right: 0.0 };
+    robot.set_wheel_speeds(speeds).await.unwrap();
+    let messages = sent_messages(&mock);
+    assert_eq!(messages.len(), 1);
+    assert_eq!((messages[0][9], messages[0][10]), WHEEL_SPEED_CMD);
+    let wheels: Vec<i16> = messages[0][11..19]
+        .chunks(2)
+        .map(|bytes| i16::from_le_bytes([bytes[0], bytes[1]]))
+        .collect();
+    let ceiling = (0.5 * MAX_WHEEL_RPM) as i16;
+    assert_eq!(wheels, vec![ceiling, -ceiling, 100, 0]);
+
+    // The keepalive refreshes the wheel command, not the earlier twist
+    std::thread::sleep(Duration::from_millis(60));
+    mock.take_sent_frames();
+    assert!(robot.service_keepalive().await.unwrap().twist);
+    assert_eq!(twist_count(&mock), 0);
+    let refreshed = sent_messages(&mock);
+    let wheel_refresh = refreshed.iter().find(|message| (message[9], message[10]) == WHEEL_SPEED_CMD).unwrap();
+    assert_eq!(wheel_refresh[11..19], messages[0][11..19]);
+
+    // The next movement replaces the wheel command
+    robot.move_robot(MovementParams { vx: 0.5, vy: 0.0, vz: 0.0 }).await.unwrap();
+    assert_eq!(twist_count(&mock), 1);
+    std::thread::sleep(Duration::from_millis(60));
+    mock.take_sent_frames();
+    robot.service_keepalive().await.unwrap();
+    assert_eq!(twist_count(&mock), 1);
+}
+
+#[tokio::test]
+async fn test_control_all_zones_sends_one_batch() {
+    use robomaster_rust::command::{CommandBuilder, LedZone, LED_SEGMENT_COUNT};
+    use robomaster_rust::CommandCounters;
+
+    let (mut robot, mock) = mock_robot();
+    let green = LedCommand::green().color();
+    let blue = LedCommand::blue().color();
+
+    // Uniform colors fit in a single command and advance the counter once
+    robot.control_all_zones([green; LED_SEGMENT_COUNT]).await.unwrap();
+    let expected = CommandBuilder::new().build_zone_led_command(LedZone::All, green, &CommandCounters::default()).unwrap();
+    assert_eq!(sent_messages(&mock), vec![expected]);
+    assert_eq!(robot.get_counters().led, 1);
+
+    mock.take_sent_frames();
+    let counters = robot.get_counters();
+    let colors = [green, green, green, green, blue, blue];
+    robot.control_all_zones(colors).await.unwrap();
+    let expected = CommandBuilder::new().build_segment_led_commands(colors, &counters).unwrap();
+    assert_eq!(expected.len(), 2, "Mixed colors need one command per color");
+    assert_eq!(sent_messages(&mock), expected);
+    assert_eq!(robot.get_counters().led, 3);
+}
+
+#[tokio::test]
+async fn test_set_led_effect_sends_effect_command() {
+    use robomaster_rust::command::CommandBuilder;
+    use robomaster_rust::LedEffect;
+
+    let (mut robot, mock) = mock_robot();
+    let color = LedCommand::blue().color();
+    let effect = LedEffect::Breathe { on_ms: 800, off_ms: 400 };
+
+    let expected = CommandBuilder::new()
+        .build_led_effect_command(color, effect, &robot.get_counters())
+        .unwrap();
+    robot.set_led_effect(color, effect).await.unwrap();
+
+    assert_eq!(sent_messages(&mock), vec![expected]);
+    assert_eq!(robot.get_counters().led, 1);
+}
+
+/// Queue a telemetry message on a mock backend, split into CAN frames
+fn queue_message(mock: &MockCanBackend, message: &[u8]) {
+    use socketcan::{CanFrame, EmbeddedFrame, StandardId};
+
+    for data in MessageSplitter::split_command(message) {
+        mock.queue_frame(CanFrame::new(StandardId::new(0x202).unwrap(), &data).unwrap());
+    }
+}
+
+/// Build a telemetry message around a payload; the CRC16 is left zero
+fn telemetry_message(cmd_set: u8, cmd_id: u8, payload: &[u8]) -> Vec<u8> {
+    use robomaster_rust::crc::{append_crc8_checksum, append_crc16_checksum, CRC16_INIT};
+
+    let mut message = vec![0x55, (13 + payload.len()) as u8, 0x04];
+    append_crc8_checksum(&mut message);
+    message.extend([0x09, 0x03, 0x00, 0x00, 0x00, cmd_set, cmd_id]);
+    message.extend(payload);
+    append_crc16_checksum(&mut message, CRC16_INIT);
+    message
+}
+
+#[tokio::test]
+async fn test_sensor_monitor_reads_battery_temperature_and_imu() {
+    let (mut robot, mock) = mock_robot();
+
+    // Chassis status: 12.1 V, 2.0 A, 90 %, 31.5 °C, 1 g on z, 45 deg/s yaw rate
+    let mut status = Vec::new();
+    status.extend(12100u16.to_le_bytes());
+    status.extend(2000i16.to_le_bytes());
+    status.push(90);
+    status.extend(315i16.to_le_bytes());
+    for value in [0i16, 0, 1000, 0, 0, 450] {
+        status.extend(value.to_le_bytes());
+    }
+    queue_message(&mock, &telemetry_message(0x3F, 0xA0, &status));
+
+    // IMU push carrying a 30 degree yaw
+    let mut imu = Vec::new();
+    for value in [0i16, 0, 1000, 0, 0, 450, 300] {
+        imu.extend(value.to_le_bytes());
+    }
+    queue_message(&mock, &telemetry_message(0x3F, 0xA2, &imu));
+
+    let sensors = robot.poll_sensors().await.unwrap();
+    assert!((sensors.battery_voltage().unwrap() - 12.1).abs() < 1e-4);
+    assert!((sensors.current - 2.0).abs() < 1e-4);
+    assert!((sensors.temperature().unwrap() - 31.5).abs() < 1e-4);
+    assert!((sensors.imu.acceleration[2] - 9.80665).abs() < 1e-3);
+    assert!((sensors.imu.angular_velocity[2] - 45f32.to_radians()).abs() < 1e-4);
+    assert!((sensors.yaw().unwrap() - 30f32.to_radians()).abs() < 1e-4);
+
+    let report = sensors.to_string();
+    assert!(report.contains("Battery:     12.10 V"), "{}", report);
+    assert!(report.contains("Temperature: 31.5 °C"), "{}", report);
+    assert!(report.contains("Accel:       [0.00, 0.00, 9.81] m/s²"), "{}", report);
+    assert!(report.contains("Yaw:         30.0 °"), "{}", report);
+}
+
+#[tokio::test]
+async fn test_telemetry_csv_has_header_and_one_row_per_frame() {
+    use robomaster_rust::telemetry::CSV_HEADER;
+
+    let (mut robot, mock) = mock_robot();
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("telemetry.csv");
+    robot.set_telemetry_csv(&path).unwrap();
+
+    // Battery push: 12.0 V, 1.5 A, 75 %
+    let mut battery = Vec::new();
+    battery.extend(12000u16.to_le_bytes());
+    battery.extend(1500i16.to_le_bytes());
+    battery.push(75);
+    queue_message(&mock, &telemetry_message(0x3F, 0xA1, &battery));
+
+    // IMU push: 1 g on z
+    let mut imu = Vec::new();
+    for value in [0i16, 0, 1000, 0, 0, 0, 0] {
+        imu.extend(value.to_le_bytes());
+    }
+    queue_message(&mock, &telemetry_message(0x3F, 0xA2, &imu));
+
+    robot.poll_sensors().await.unwrap();
+    robot.clear_telemetry_csv().unwrap();
+
+    let content = std::fs::read_to_string(&path).unwrap();
+    let lines: Vec<&str> = content.lines().collect();
+    assert_eq!(lines.len(), 3, "{}", content);
+    assert_eq!(lines[0], CSV_HEADER);
+    assert_eq!(lines[1].split(',').nth(1), Some("12"), "{}", lines[1]);
+    assert!(lines[2].split(',').nth(6).unwrap().starts_with("9.80"), "{}", lines[2]);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_hold_and_heartbeats_beyond_send_limit_do_not_interleave() {
+    use robomaster_rust::crc::{verify_crc16_checksum, CRC16_INIT};
+    use robomaster_rust::MovementParams;
+
+    let (mut robot, mock) = mock_robot();
+    robot.initialize().await.unwrap();
+    robot.set_max_in_flight(1);
+    mock.take_sent_frames();
+
+    let hold = robot.hold(MovementParams { vx: 0.2, vy: 0.0, vz: 0.0 }).await.unwrap();
+    let heartbeats: Vec<_> = (0..3)
+        .map(|_| robot.start_heartbeat(Duration::from_millis(20)).unwrap())
+        .collect();
+    tokio::time::sleep(std::time::Duration::from_millis(150)).await;
+    assert!(hold.is_active(), "No send should fail");
+    assert!(heartbeats.iter().all(|heartbeat| heartbeat.is_active()));
+    drop(hold);
+    drop(heartbeats);
+
+    // Every reassembled message must be a complete, valid command
+    let messages = sent_messages(&mock);
+    assert!(messages.len() >= 8);
+    for message in &messages {
+        assert_eq!(message[0], 0x55);
+        assert!(verify_crc16_checksum(message, CRC16_INIT));
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_new_hold_or_movement_cancels_previous_hold() {
+    use robomaster_rust::command::CommandBuilder;
+    use robomaster_rust::{CommandCounters, MovementParams};
+
+    let (mut robot, mock) = mock_robot();
+    robot.initialize().await.unwrap();
+
+    let first = robot.hold(MovementParams { vx: 0.2, vy: 0.0, vz: 0.0 }).await.unwrap();
+    let second = robot.hold(MovementParams { vx: -0.4, vy: 0.0, vz: 0.0 }).await.unwrap();
+    tokio::task::yield_now().await;
+    assert!(!first.is_active(), "A new hold should replace the previous one");
+    assert!(second.is_active());
+
+    mock.take_sent_frames();
+    tokio::time::sleep(Duration::from_millis(250)).await;
+    let twist = CommandBuilder::new().build_twist_command(MovementParams::stopped(), &CommandCounters::default()).unwrap();
+    let held: Vec<Vec<u8>> = sent_messages(&mock)
+        .iter()
+        .filter(|message| message[9..11] == twist[9..11])
+        .map(|message| message[11..14].to_vec())
+        .collect();
+    assert!(!held.is_empty());
+    assert!(held.windows(2).all(|pair| pair[0] == pair[1]), "Only the second hold should be resent");
+
+    robot.move_robot(MovementParams { vx: 0.1, vy: 0.0, vz: 0.0 }).await.unwrap();
+    tokio::task::yield_now().await;
+    assert!(!second.is_active(), "A movement should replace the hold");
+    mock.take_sent_frames();
+    tokio::time::sleep(Duration::from_millis(250)).await;
+    assert_eq!(twist_count(&mock), 0, "Nothing should be resent after the hold is replaced");
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_hold_ramps_through_slew_limit() {
+    use robomaster_rust::command::CommandBuilder;
+    use robomaster_rust::{CommandCounters, MovementParams};
+
+    let (mut robot, mock) = mock_robot();
+    robot.initialize().await.unwrap();
+    robot.set_slew_limit(1.0).unwrap();
+    mock.take_sent_frames();
+
+    let full = MovementParams { vx: 1.0, vy: 0.0, vz: 0.0 };
+    let _hold = robot.hold(full).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(120)).await;
+
+    let twist = CommandBuilder::new().build_twist_command(full, &CommandCounters::default()).unwrap();
+    let first = sent_messages(&mock)
+        .into_iter()
+        .find(|message| message[9..11] == twist[9..11])
+        .unwrap();
+    assert_ne!(first[11..17], twist[11..17], "A hold from rest should not jump to full speed");
+}
+
+#[tokio::test]
+async fn test_set_zone_led_only_addresses_that_zone() {
+    use robomaster_rust::command::CommandBuilder;
+    use robomaster_rust::LedZone;
+
+    let (mut robot, mock) = mock_robot();
+    let amber = LedCommand::rgb(255, 160, 0).color();
+
+    let expected = CommandBuilder::new()
+        .build_zone_led_command(LedZone::Right, amber, &robot.get_counters())
+        .unwrap();
+    robot.set_zone_led(LedZone::Right, amber).await.unwrap();
+
+    assert_eq!(sent_messages(&mock), vec![expected]);
+    assert_eq!(robot.get_counters().led, 1);
+}
+
+#[tokio::test]
+async fn test_fire_blaster_checks_interlock_and_burst_limit() {
+    use robomaster_rust::command::{CommandBuilder, MAX_BLASTER_BURST};
+    use robomaster_rust::error::{ControlError, RoboMasterError};
+
+    let (mut robot, mock) = mock_robot();
+    robot.initialize().await.unwrap();
+    mock.take_sent_frames();
+
+    assert!(matches!(
+        robot.fire_blaster(1).await,
+        Err(RoboMasterError::Control(ControlError::BlasterInterlocked { .. }))
+    ));
+
+    // Blaster status push: armed, gimbal ready
+    queue_message(&mock, &telemetry_message(0x3F, 0xB0, &[1, 1]));
+    robot.poll_sensors().await.unwrap();
+
+    assert!(matches!(
+        robot.fire_blaster(MAX_BLASTER_BURST + 1).await,
+        Err(RoboMasterError::InvalidParameter { .. })
+    ));
+    assert!(mock.sent_frames().is_empty());
+
+    let counters = robot.get_counters();
+    let expected = CommandBuilder::new().build_blaster_command(2, &counters).unwrap();
+    robot.fire_blaster(2).await.unwrap();
+    assert_eq!(sent_messages(&mock), vec![expected]);
+
+    // The next command waits until the two-shot burst has been fired
+    let started = std::time::Instant::now();
+    robot.fire_blaster(1).await.unwrap();
+    assert!(started.elapsed() >= std::time::Duration::from_millis(150));
+}
+
+#[tokio::test]
+async fn test_recover_to_idle_sends_stop_gimbal_then_led() {
+    use robomaster_rust::command::CommandBuilder;
+    use robomaster_rust::control::IDLE_LED_COLOR;
+    use robomaster_rust::{CommandCounters, GimbalParams, MovementParams};
+
+    let (mut robot, mock) = mock_robot();
+    robot.initialize().await.unwrap();
+    robot.move_robot(MovementParams { vx: 0.5, vy: 0.0, vz: 0.0 }).await.unwrap();
+    mock.take_sent_frames();
+
+    robot.recover_to_idle().await.unwrap();
+
+    let builder = CommandBuilder::new();
+    let fresh = CommandCounters::default();
+    let expected = vec![
+        builder.build_twist_command(MovementParams::stopped(), &fresh).unwrap(),
+        builder.build_gimbal_command(GimbalParams::neutral(), &fresh).unwrap(),
+        builder
+            .build_gimbal_command(GimbalParams::neutral(), &CommandCounters { gimbal: 1, ..fresh })
+            .unwrap(),
+        builder.build_led_command(IDLE_LED_COLOR, &fresh).unwrap(),
+    ];
+    assert_eq!(sent_messages(&mock), expected);
+    assert_eq!(robot.counter_resyncs(), 0);
+}
+
+#[tokio::test]
+async fn test_control_lease_blocks_other_controllers() {
+    use robomaster_rust::error::{ControlError, RoboMasterError};
+    use robomaster_rust::MovementParams;
+
+    let (mut robot, mock) = mock_robot();
+    robot.initialize().await.unwrap();
+    mock.take_sent_frames();
+
+    let lease = robot.acquire_control().unwrap();
+    assert!(matches!(
+        robot.acquire_control(),
+        Err(RoboMasterError::Control(ControlError::MovementBlocked { .. }))
+    ));
+
+    let forward = MovementParams { vx: 0.5, vy: 0.0, vz: 0.0 };
+    assert!(matches!(
+        robot.move_robot(forward).await,
+        Err(RoboMasterError::Control(ControlError::MovementBlocked { .. }))
+    ));
+    assert!(mock.sent_frames().is_empty(), "Blocked movement should not be sent");
+
+    robot.move_robot_with_lease(&lease, forward).await.unwrap();
+    assert!(!mock.take_sent_frames().is_empty());
+
+    drop(lease);
+    assert!(!robot.control_leased());
+    robot.move_robot(MovementParams::stopped()).await.unwrap();
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_control_lease_guards_hold_and_drive() {
+    use robomaster_rust::control::AccelProfile;
+    use robomaster_rust::error::{ControlError, RoboMasterError};
+    use robomaster_rust::MovementParams;
+
+    let (mut robot, mock) = mock_robot();
+    robot.initialize().await.unwrap();
+    let forward = MovementParams { vx: 0.2, vy: 0.0, vz: 0.0 };
+    let lease = robot.acquire_control().unwrap();
+    mock.take_sent_frames();
+
+    assert!(matches!(
+        robot.hold(forward).await,
+        Err(RoboMasterError::Control(ControlError::MovementBlocked { .. }))
+    ));
+    assert!(robot.rotate_continuous(0.5).await.is_err());
+    assert!(robot.drive_for(forward, Duration::from_millis(100), Duration::ZERO, AccelProfile::Trapezoidal).await.is_err());
+    assert!(robot.ramp_to(forward, 1.0).await.is_err());
+    assert!(mock.sent_frames().is_empty(), "Blocked movement should not be sent");
+
+    robot
+        .drive_for_with_lease(&lease, forward, Duration::from_millis(100), Duration::ZERO, AccelProfile::Trapezoidal)
+        .await
+        .unwrap();
+    assert!(twist_count(&mock) > 0);
+
+    let handle = robot.hold_with_lease(&lease, forward).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert!(handle.is_active());
+
+    // Resending ends with the lease it was started under
+    drop(lease);
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert!(!handle.is_active());
+}
+
+#[tokio::test]
+async fn test_move_field_relative_needs_imu_yaw() {
+    use robomaster_rust::error::{ControlError, RoboMasterError};
+
+    let (mut robot, mock) = mock_robot();
+    robot.initialize().await.unwrap();
+    mock.take_sent_frames();
+
+    assert!(matches!(
+        robot.move_field_relative(0.5, 0.0, 0.0).await,
+        Err(RoboMasterError::Control(ControlError::SensorUnavailable { .. }))
+    ));
+    assert!(mock.sent_frames().is_empty(), "Nothing should be sent without a heading");
+
+    // Upright IMU push with yaw 90.0°
+    let mut imu = Vec::new();
+    for value in [0i16, 0, 1000, 0, 0, 0, 900] {
+        imu.extend(value.to_le_bytes());
+    }
+    queue_message(&mock, &telemetry_message(0x3F, 0xA2, &imu));
+    robot.poll_sensors().await.unwrap();
+
+    robot.move_field_relative(0.5, 0.0, 0.0).await.unwrap();
+    assert!(twist_count(&mock) > 0);
+}
+
+#[tokio::test]
+async fn test_tip_over_is_detected_and_stops_motion() {
+    use robomaster_rust::command::CommandBuilder;
+    use robomaster_rust::control::FaultKind;
+    use robomaster_rust::error::{ControlError, RoboMasterError};
+    use robomaster_rust::MovementParams;
+
+    let (mut robot, mock) = mock_robot();
+    robot.initialize().await.unwrap();
+    robot.move_robot(MovementParams { vx: 1.0, vy: 0.0, vz: 0.0 }).await.unwrap();
+    mock.take_sent_frames();
+
+    // IMU push with gravity along the chassis X axis: lying on its side
+    let mut imu = Vec::new();
+    for value in [1000i16, 0, 0, 0, 0, 0, 0] {
+        imu.extend(value.to_le_bytes());
+    }
+    queue_message(&mock, &telemetry_message(0x3F, 0xA2, &imu));
+    robot.poll_sensors().await.unwrap();
+
+    assert_eq!(robot.fault_state(), Some(FaultKind::TipOver));
+    let stop = CommandBuilder::new()
+        .build_twist_command(MovementParams::stopped(), &robot.get_counters())
+        .unwrap();
+    let sent = sent_messages(&mock);
+    assert!(
+        sent.iter().any(|message| message[9..11] == stop[9..11] && message[11..25] == stop[11..25]),
+        "A stop twist should be sent on tip-over"
+    );
+
+    assert!(matches!(
+        robot.move_robot(MovementParams { vx: 1.0, vy: 0.0, vz: 0.0 }).await,
+        Err(RoboMasterError::Control(ControlError::MovementBlocked { .. }))
+    ));
+    robot.clear_fault();
+    assert_eq!(robot.fault_state(), None);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_tip_over_cancels_hold() {
+    use robomaster_rust::control::FaultKind;
+    use robomaster_rust::MovementParams;
+
+    let (mut robot, mock) = mock_robot();
+    robot.initialize().await.unwrap();
+    let handle = robot.rotate_continuous(0.5).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    mock.take_sent_frames();
+
+    let mut imu = Vec::new();
+    for value in [1000i16, 0, 0, 0, 0, 0, 0] {
+        imu.extend(value.to_le_bytes());
+    }
+    queue_message(&mock, &telemetry_message(0x3F, 0xA2, &imu));
+    robot.poll_sensors().await.unwrap();
+    tokio::task::yield_now().await;
+
+    assert_eq!(robot.fault_state(), Some(FaultKind::TipOver));
+    assert!(!handle.is_active(), "A tip-over should cancel the hold");
+    assert_eq!(twist_count(&mock), 1, "Only the stop twist should be sent");
+    tokio::time::sleep(Duration::from_millis(250)).await;
+    assert_eq!(twist_count(&mock), 1, "Nothing should be resent after a tip-over");
+    assert!(robot.hold(MovementParams { vx: 0.2, vy: 0.0, vz: 0.0 }).await.is_err());
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_fade_led_emits_midpoint_color() {
+    use robomaster_rust::command::{CommandBuilder, LedColor};
+    use robomaster_rust::control::LED_UPDATE_PERIOD;
+
+    let (mut robot, mock) = mock_robot();
+    let red = LedColor { red: 255, green: 0, blue: 0 };
+    let green = LedColor { red: 0, green: 255, blue: 0 };
+
+    robot.fade_led(red, green, LED_UPDATE_PERIOD * 4).await.unwrap();
+
+    let builder = CommandBuilder::new();
+    let colors: Vec<LedColor> = sent_messages(&mock)
+        .iter()
+        .filter_map(|message| builder.decode_led_command(message))
+        .collect();
+    assert_eq!(colors.len(), 5, "one color per update period, plus the final color");
+    assert_eq!(colors[0], red);
+    assert_eq!(colors[2], red.interpolate(green, 0.5));
+    assert_eq!(colors[2], LedColor { red: 186, green: 186, blue: 0 });
+    assert_eq!(colors[4], green);
+}
+
+#[tokio::test]
+async fn test_nan_led_brightness_means_full() {
+    use robomaster_rust::command::CommandBuilder;
+
+    let (mut robot, mock) = mock_robot();
+    robot.set_led_brightness(0.2);
+    robot.set_led_brightness(f32::NAN);
+    assert_eq!(robot.led_brightness(), 1.0);
+
+    let green = LedCommand::green().color();
+    robot.control_led(green).await.unwrap();
+    let colors: Vec<_> = sent_messages(&mock)
+        .iter()
+        .filter_map(|message| CommandBuilder::new().decode_led_command(message))
+        .collect();
+    assert_eq!(colors, vec![green]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_identify_flashes_white_three_times() {
+    use robomaster_rust::command::{CommandBuilder, LedColor};
+
+    let (mut robot, mock) = mock_robot();
+    robot.set_led_brightness(0.2);
+
+    robot.identify().await.unwrap();
+
+    let builder = CommandBuilder::new();
+    let colors: Vec<LedColor> = sent_messages(&mock)
+        .iter()
+        .filter_map(|message| builder.decode_led_command(message))
+        .collect();
+    let white = LedColor { red: 255, green: 255, blue: 255 };
+    let off = LedColor { red: 0, green: 0, blue: 0 };
+    assert_eq!(colors, [white, off, white, off, white, off]);
+}
+
+#[tokio::test]
+async fn test_telemetry_stream_pushes_updates_until_shutdown() {
+    use robomaster_rust::control::DEFAULT_TELEMETRY_STREAM_CAPACITY;
+
+    let (robot, mock) = mock_robot();
+    let mut stream = robot.telemetry_stream(DEFAULT_TELEMETRY_STREAM_CAPACITY);
+
+    // Battery: 12.3 V, 0.5 A, 88 %
+    let mut payload = Vec::new();
+    payload.extend(12300u16.to_le_bytes());
+    payload.extend(500i16.to_le_bytes());
+    payload.push(88);
+    queue_message(&mock, &telemetry_message(0x3F, 0xA1, &payload));
+
+    let update = timeout(Duration::from_secs(1), stream.next_update()).await.unwrap().unwrap();
+    assert!((update.battery_voltage - 12.3).abs() < 1e-4);
+    assert!((update.current - 0.5).abs() < 1e-4);
+
+    robot.shutdown().await.unwrap();
+    let ended = timeout(Duration::from_secs(1), stream.next_update()).await.unwrap();
+    assert!(ended.is_none(), "The stream ends on shutdown");
+}
+
+#[tokio::test]
+async fn test_telemetry_stream_uses_registered_decoder() {
+    use robomaster_rust::control::DEFAULT_TELEMETRY_STREAM_CAPACITY;
+    use robomaster_rust::SensorData;
+
+    let (mut robot, mock) = mock_robot();
+    robot.set_telemetry_decoder(|message: &[u8]| {
+        (message[9..11] == [0x3F, 0xB7]).then(|| SensorData {
+            link_quality: message[11] as f32,
+            ..SensorData::default()
+        })
+    });
+    let mut stream = robot.telemetry_stream(DEFAULT_TELEMETRY_STREAM_CAPACITY);
+
+    queue_message(&mock, &telemetry_message(0x3F, 0xB7, &[64]));
+    let update = timeout(Duration::from_secs(1), stream.next_update()).await.unwrap().unwrap();
+    assert_eq!(update.link_quality, 64.0);
+}
+
+#[tokio::test]
+async fn test_shutdown_after_signal_stops_robot() {
+    use robomaster_rust::command::CommandBuilder;
+    use robomaster_rust::MovementParams;
+
+    let (mut robot, mock) = mock_robot();
+    robot.initialize().await.unwrap();
+    robot.move_robot(MovementParams { vx: 0.5, vy: 0.0, vz: 0.0 }).await.unwrap();
+    let counters = robot.get_counters();
+    mock.take_sent_frames();
+
+    // A signal future that resolves at once stands in for SIGTERM
+    robot.shutdown_after(async { Ok(()) }).await.unwrap();
+
+    let stop = CommandBuilder::new().build_twist_command(MovementParams::stopped(), &counters).unwrap();
+    assert!(sent_messages(&mock).contains(&stop), "A stop is sent before shutting down");
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_heartbeat_sends_touch_until_dropped_or_shutdown() {
+    let (robot, mock) = mock_robot();
+    assert!(robot.start_heartbeat(Duration::ZERO).is_err());
+
+    let heartbeat = robot.start_heartbeat(Duration::from_millis(100)).unwrap();
+    tokio::time::sleep(Duration::from_millis(250)).await;
+    assert_eq!(robot.get_counters().joy, 3, "Touches at 0, 100 and 200 ms");
+    assert!(!mock.sent_frames().is_empty());
+
+    heartbeat.stop();
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    assert_eq!(robot.get_counters().joy, 3);
+
+    let heartbeat = robot.start_heartbeat(Duration::from_millis(100)).unwrap();
+    robot.shutdown().await.unwrap();
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    assert!(!heartbeat.is_active());
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_watchdog_stops_once_when_movement_stops() {
+    use robomaster_rust::command::CommandBuilder;
+    use robomaster_rust::MovementParams;
+
+    let (mut robot, mock) = mock_robot();
+    robot.initialize().await.unwrap();
+    assert!(robot.enable_watchdog(Duration::ZERO).is_err());
+    robot.enable_watchdog(Duration::from_millis(200)).unwrap();
+
+    robot.move_robot(MovementParams { vx: 0.5, vy: 0.0, vz: 0.0 }).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(150)).await;
+
+    // Explicitly holding still keeps the watchdog fed
+    robot.move_robot(MovementParams::stopped()).await.unwrap();
+    let counters = robot.get_counters();
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    assert_eq!(robot.get_counters().joy, counters.joy);
+
+    robot.move_robot(MovementParams { vx: 0.5, vy: 0.0, vz: 0.0 }).await.unwrap();
+    let counters = robot.get_counters();
+    mock.take_sent_frames();
+    tokio::time::sleep(Duration::from_millis(250)).await;
+    let stop = CommandBuilder::new().build_twist_command(MovementParams::stopped(), &counters).unwrap();
+    assert_eq!(sent_messages(&mock)[0], stop);
+
+    // One stop per missed deadline, until movement resumes
+    tokio::time::sleep(Duration::from_secs(1)).await;
+    assert_eq!(robot.get_counters().joy, counters.joy.wrapping_add(1));
+    assert!(robot.watchdog().unwrap().is_active());
+
+    robot.disable_watchdog();
+    assert!(robot.watchdog().is_none());
+}
+
+#[tokio::test]
+async fn test_builder_returns_initialized_robot() {
+    use robomaster_rust::command::CommandBuilder;
+
+    let mock = MockCanBackend::new();
+    let robot = RoboMaster::builder()
+        .backend(mock.clone())
+        .can_id(0x211)
+        .timeout(Duration::from_millis(50))
+        .build()
+        .await
+        .unwrap();
+
+    assert!(robot.is_initialized());
+    assert_eq!(robot.receive_timeout(), Duration::from_millis(50));
+    let boot = CommandBuilder::new().build_boot_sequence().unwrap();
+    assert!(mock.sent_frames().concat().starts_with(&boot), "The boot sequence is sent");
+    assert!(mock.sent_ids().iter().all(|&id| id == 0x211));
+
+    let idle = RoboMaster::builder().backend(MockCanBackend::new()).auto_initialize(false).build().await.unwrap();
+    assert!(!idle.is_initialized());
+
+    let invalid = RoboMaster::builder().backend(MockCanBackend::new()).can_id(0x800).build().await;
+    assert!(invalid.is_err());
+}
+
+#[tokio::test]
+async fn test_serial_number_parsed_from_response() {
+    use robomaster_rust::command::CommandBuilder;
+
+    let (mut robot, mock) = mock_robot();
+    robot.initialize().await.unwrap();
+    mock.take_sent_frames();
+
+    // Telemetry ahead of the answer is still processed
+    let mut status = Vec::new();
+    status.extend(11900u16.to_le_bytes());
+    status.extend(0i16.to_le_bytes());
+    status.push(75);
+    for value in [250i16, 0, 0, 1000, 0, 0, 0] {
+        status.extend(value.to_le_bytes());
+    }
+    queue_message(&mock, &telemetry_message(0x3F, 0xA0, &status));
+
+    let serial = b"3JKDH2T00123456";
+    let mut response = vec![0x00];
+    response.extend((serial.len() as u16).to_le_bytes());
+    response.extend(serial);
+    queue_message(&mock, &telemetry_message(0x00, 0x4F, &response));
+
+    let counters = robot.get_counters();
+    assert_eq!(robot.serial_number().await.unwrap(), "3JKDH2T00123456");
+    assert_eq!(sent_messages(&mock), vec![CommandBuilder::new().build_serial_number_query(&counters).unwrap()]);
+    assert!((robot.sensor_data().battery_voltage - 11.9).abs() < 1e-4);
+
+    // An error code is reported instead of a bogus serial
+    response[0] = 0x01;
+    queue_message(&mock, &telemetry_message(0x00, 0x4F, &response));
+    assert!(robot.serial_number().await.is_err());
+}
+
+#[tokio::test]
+async fn test_serial_number_query_stops_on_tip_over_without_holding_the_send_permit() {
+    use robomaster_rust::command::CommandBuilder;
+    use robomaster_rust::control::FaultKind;
+    use robomaster_rust::MovementParams;
+
+    let (mut robot, mock) = mock_robot();
+    robot.initialize().await.unwrap();
+    robot.set_max_in_flight(1);
+    mock.take_sent_frames();
+
+    // A tip-over ahead of the answer stops the robot while the query waits
+    let mut imu = Vec::new();
+    for value in [1000i16, 0, 0, 0, 0, 0, 0] {
+        imu.extend(value.to_le_bytes());
+    }
+    queue_message(&mock, &telemetry_message(0x3F, 0xA2, &imu));
+
+    let serial = b"3JKDH2T00123456";
+    let mut response = vec![0x00];
+    response.extend((serial.len() as u16).to_le_bytes());
+    response.extend(serial);
+    queue_message(&mock, &telemetry_message(0x00, 0x4F, &response));
+
+    let serial = timeout(Duration::from_millis(500), robot.serial_number()).await.unwrap();
+    assert_eq!(serial.unwrap(), "3JKDH2T00123456");
+    assert_eq!(robot.fault_state(), Some(FaultKind::TipOver));
+    let stop = CommandBuilder::new()
+        .build_twist_command(MovementParams::stopped(), &robot.get_counters())
+        .unwrap();
+    assert!(
+        sent_messages(&mock).iter().any(|message| message[9..11] == stop[9..11]),
+        "A stop twist should be sent on tip-over"
+    );
+}